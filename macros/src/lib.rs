@@ -48,8 +48,11 @@ single_token_macro!(ident_to_lower, proc_macro::TokenTree::Ident(v), {
 // 	item
 // }
 
-#[proc_macro_derive(FromFile, attributes(from_file_count))]
-pub fn derive_from_file(stream: TokenStream) -> TokenStream {
+///A struct field as `(count field if it has `from_file_count`, name, type)`, a counted field has the type of its array items
+type Field = (Option<String>, String, String);
+
+///The name and fields of the struct a derive is on
+fn parse_struct(stream: TokenStream)->Result<(String, Vec<Field>), TokenStream>{
 	// println!("{:#?}",stream);
 	let mut streami = stream.into_iter();
 	let mut struct_name = None;
@@ -59,44 +62,44 @@ pub fn derive_from_file(stream: TokenStream) -> TokenStream {
 		if let TokenTree::Ident(i) = token.clone(){if i.to_string() == "struct"{
 			if let Some(TokenTree::Ident(i)) = streami.next(){
 				struct_name = Some(i.to_string());
-			}else{return err("expected struct name after `struct`");}
+			}else{return Err(err("expected struct name after `struct`"));}
 		}}
 		if let TokenTree::Group(g) = token.clone(){if g.delimiter() == Delimiter::Brace{
-			if struct_name.is_none(){return err("found struct fields before struct name");}
+			if struct_name.is_none(){return Err(err("found struct fields before struct name"));}
 			streami = g.stream().into_iter();
 			let mut last_token = None;
 			let mut next_prop_count = None;
 			while let Some(token) = streami.next(){
 				if let TokenTree::Punct(p) = token.clone(){if p.as_char() == ':'{
-					if last_token.is_none(){return err("expected a field name before a type");}
+					if last_token.is_none(){return Err(err("expected a field name before a type"));}
 					if let Some(TokenTree::Ident(f_name)) = last_token{
 						if let Some(TokenTree::Ident(mut f_type)) = streami.next(){
 							if next_prop_count.is_some(){
 								let not_box_err = err("items with a count must be a boxed array");
 
-								if f_type.to_string() != "Box"{return not_box_err;}
+								if f_type.to_string() != "Box"{return Err(not_box_err);}
 
 								if let Some(TokenTree::Punct(p)) = streami.next(){
-									if p.as_char() != '<' {return not_box_err;}
-								}else{return not_box_err;}
+									if p.as_char() != '<' {return Err(not_box_err);}
+								}else{return Err(not_box_err);}
 
 								if let Some(TokenTree::Group(t)) = streami.next(){
-									if t.delimiter() != Delimiter::Bracket {return not_box_err;}
+									if t.delimiter() != Delimiter::Bracket {return Err(not_box_err);}
 									let mut t = t.stream().into_iter();
 									if let Some(TokenTree::Ident(b_type)) = t.next(){
 										f_type = b_type;
-										if t.next().is_some(){return not_box_err;}
-									}else{return not_box_err;}
-								}else{return not_box_err;}
+										if t.next().is_some(){return Err(not_box_err);}
+									}else{return Err(not_box_err);}
+								}else{return Err(not_box_err);}
 
 								if let Some(TokenTree::Punct(p)) = streami.next(){
-									if p.as_char() != '>' {return not_box_err;}
-								}else{return not_box_err;}
+									if p.as_char() != '>' {return Err(not_box_err);}
+								}else{return Err(not_box_err);}
 							}
 							struct_values.push((next_prop_count, f_name.to_string(), f_type.to_string()));
 							next_prop_count = None;
-						}else{return err("expected type after field name then `:`");}
-					}else{return err("expected a field name before a type");}
+						}else{return Err(err("expected type after field name then `:`"));}
+					}else{return Err(err("expected a field name before a type"));}
 				}else if p.as_char() == '#' {if let Some(TokenTree::Group(attr)) = streami.next(){
 					if attr.delimiter() == Delimiter::Bracket{
 						let mut attr = attr.stream().into_iter();
@@ -104,18 +107,18 @@ pub fn derive_from_file(stream: TokenStream) -> TokenStream {
 							if attr_name.to_string() == "from_file_count"{
 								let count_name = match attr.next(){
 									Some(TokenTree::Group(count_name))=>count_name,
-									_=>return err("Expected parenthasis after `from_file_count`"),
+									_=>return Err(err("Expected parenthasis after `from_file_count`")),
 								};
 								if count_name.delimiter() != Delimiter::Parenthesis
-								{ return err("Expected parenthasis after `from_file_count`"); }
+								{ return Err(err("Expected parenthasis after `from_file_count`")); }
 
 								let mut count_name_iter = count_name.stream().into_iter();
 								let count_name = match count_name_iter.next(){
 									Some(TokenTree::Ident(count_name))=>count_name.to_string(),
-									_=>return err("Expected name of count attr as argument to `from_file_count`"),
+									_=>return Err(err("Expected name of count attr as argument to `from_file_count`")),
 								};
 								if count_name_iter.next().is_some()
-								{ return err("`from_file_count` attribute only accepts one argument"); }
+								{ return Err(err("`from_file_count` attribute only accepts one argument")); }
 								next_prop_count = Some(count_name);
 							}
 						}
@@ -126,6 +129,18 @@ pub fn derive_from_file(stream: TokenStream) -> TokenStream {
 			break;
 		}}
 	}
+	match struct_name{
+		Some(name) => Ok((name, struct_values)),
+		None => Err(err("expected a struct")),
+	}
+}
+
+#[proc_macro_derive(FromFile, attributes(from_file_count))]
+pub fn derive_from_file(stream: TokenStream) -> TokenStream {
+	let (struct_name, struct_values) = match parse_struct(stream){
+		Ok(v) => v,
+		Err(e) => return e,
+	};
 
 	let mut definitions = String::new();
 	let mut init = String::new();
@@ -153,5 +168,35 @@ pub fn derive_from_file(stream: TokenStream) -> TokenStream {
 				Ok(Self{{{}}})
 			}}
 		}}
-	"###, struct_name.unwrap(), definitions, init).as_str()).unwrap()
+	"###, struct_name, definitions, init).as_str()).unwrap()
+}
+
+#[proc_macro_derive(ToFile, attributes(from_file_count))]
+pub fn derive_to_file(stream: TokenStream) -> TokenStream {
+	let (struct_name, struct_values) = match parse_struct(stream){
+		Ok(v) => v,
+		Err(e) => return e,
+	};
+
+	let mut writes = String::new();
+	for val in struct_values {
+		writes.push_str(&if let Some(count) = val.0{format!(
+			"unwrap_or_ret!(array_to_file(f, &self.{}, self.{} as usize));", val.1, count
+		)}else {format!(
+			"unwrap_or_ret!(self.{}.to_file(f));", val.1
+		)});
+	}
+
+	TokenStream::from_str(format!(r###"
+		#[automatically_derived]
+		impl ToFile for {}{{
+			fn to_file<F>(&self, f: &mut F)->Result<(), std::io::Error> where
+				F: Write,
+				F: Seek
+			{{
+				{}
+				Ok(())
+			}}
+		}}
+	"###, struct_name, writes).as_str()).unwrap()
 }
@@ -0,0 +1,634 @@
+use crate::core::FromFileErr;
+
+///The static dictionary of RFC 7932 Appendix A
+static DICTIONARY: &[u8; 122784] = include_bytes!("brotli_dictionary.bin");
+///Offset in the dictionary of the words of each length
+const DICTIONARY_OFFSETS_BY_LENGTH: [u32; 25] = [
+	0, 0, 0, 0, 0, 4096, 9216, 21504, 35840, 44032, 53248, 63488, 74752, 87040, 93696, 100864, 104704, 106752, 108928, 113536, 115968, 118528,
+	119872, 121280, 122016,
+];
+///Log2 of the number of words of each length in the dictionary
+const DICTIONARY_SIZE_BITS_BY_LENGTH: [u8; 25] = [0, 0, 0, 0, 10, 10, 11, 11, 10, 10, 10, 10, 10, 9, 9, 8, 7, 7, 8, 7, 7, 6, 6, 5, 5];
+const MIN_DICTIONARY_WORD_LENGTH: usize = 4;
+const MAX_DICTIONARY_WORD_LENGTH: usize = 24;
+
+#[derive(Debug, Clone, Copy)]
+enum WordTransform{
+	Identity,
+	OmitLast(usize),
+	UppercaseFirst,
+	UppercaseAll,
+	OmitFirst(usize),
+}
+use WordTransform::*;
+///The prefix, transform and suffix applied to a dictionary word for each transform id (RFC 7932 Appendix B)
+const TRANSFORMS: [(&[u8], WordTransform, &[u8]); 121] = [
+	(b"", Identity, b""),
+	(b"", Identity, b" "),
+	(b" ", Identity, b" "),
+	(b"", OmitFirst(1), b""),
+	(b"", UppercaseFirst, b" "),
+	(b"", Identity, b" the "),
+	(b" ", Identity, b""),
+	(b"s ", Identity, b" "),
+	(b"", Identity, b" of "),
+	(b"", UppercaseFirst, b""),
+	(b"", Identity, b" and "),
+	(b"", OmitFirst(2), b""),
+	(b"", OmitLast(1), b""),
+	(b", ", Identity, b" "),
+	(b"", Identity, b", "),
+	(b" ", UppercaseFirst, b" "),
+	(b"", Identity, b" in "),
+	(b"", Identity, b" to "),
+	(b"e ", Identity, b" "),
+	(b"", Identity, b"\""),
+	(b"", Identity, b"."),
+	(b"", Identity, b"\">"),
+	(b"", Identity, b"\n"),
+	(b"", OmitLast(3), b""),
+	(b"", Identity, b"]"),
+	(b"", Identity, b" for "),
+	(b"", OmitFirst(3), b""),
+	(b"", OmitLast(2), b""),
+	(b"", Identity, b" a "),
+	(b"", Identity, b" that "),
+	(b" ", UppercaseFirst, b""),
+	(b"", Identity, b". "),
+	(b".", Identity, b""),
+	(b" ", Identity, b", "),
+	(b"", OmitFirst(4), b""),
+	(b"", Identity, b" with "),
+	(b"", Identity, b"'"),
+	(b"", Identity, b" from "),
+	(b"", Identity, b" by "),
+	(b"", OmitFirst(5), b""),
+	(b"", OmitFirst(6), b""),
+	(b" the ", Identity, b""),
+	(b"", OmitLast(4), b""),
+	(b"", Identity, b". The "),
+	(b"", UppercaseAll, b""),
+	(b"", Identity, b" on "),
+	(b"", Identity, b" as "),
+	(b"", Identity, b" is "),
+	(b"", OmitLast(7), b""),
+	(b"", OmitLast(1), b"ing "),
+	(b"", Identity, b"\n\t"),
+	(b"", Identity, b":"),
+	(b" ", Identity, b". "),
+	(b"", Identity, b"ed "),
+	(b"", OmitFirst(9), b""),
+	(b"", OmitFirst(7), b""),
+	(b"", OmitLast(6), b""),
+	(b"", Identity, b"("),
+	(b"", UppercaseFirst, b", "),
+	(b"", OmitLast(8), b""),
+	(b"", Identity, b" at "),
+	(b"", Identity, b"ly "),
+	(b" the ", Identity, b" of "),
+	(b"", OmitLast(5), b""),
+	(b"", OmitLast(9), b""),
+	(b" ", UppercaseFirst, b", "),
+	(b"", UppercaseFirst, b"\""),
+	(b".", Identity, b"("),
+	(b"", UppercaseAll, b" "),
+	(b"", UppercaseFirst, b"\">"),
+	(b"", Identity, b"=\""),
+	(b" ", Identity, b"."),
+	(b".com/", Identity, b""),
+	(b" the ", Identity, b" of the "),
+	(b"", UppercaseFirst, b"'"),
+	(b"", Identity, b". This "),
+	(b"", Identity, b","),
+	(b".", Identity, b" "),
+	(b"", UppercaseFirst, b"("),
+	(b"", UppercaseFirst, b"."),
+	(b"", Identity, b" not "),
+	(b" ", Identity, b"=\""),
+	(b"", Identity, b"er "),
+	(b" ", UppercaseAll, b" "),
+	(b"", Identity, b"al "),
+	(b" ", UppercaseAll, b""),
+	(b"", Identity, b"='"),
+	(b"", UppercaseAll, b"\""),
+	(b"", UppercaseFirst, b". "),
+	(b" ", Identity, b"("),
+	(b"", Identity, b"ful "),
+	(b" ", UppercaseFirst, b". "),
+	(b"", Identity, b"ive "),
+	(b"", Identity, b"less "),
+	(b"", UppercaseAll, b"'"),
+	(b"", Identity, b"est "),
+	(b" ", UppercaseFirst, b"."),
+	(b"", UppercaseAll, b"\">"),
+	(b" ", Identity, b"='"),
+	(b"", UppercaseFirst, b","),
+	(b"", Identity, b"ize "),
+	(b"", UppercaseAll, b"."),
+	(b"\xC2\xA0", Identity, b""),
+	(b" ", Identity, b","),
+	(b"", UppercaseFirst, b"=\""),
+	(b"", UppercaseAll, b"=\""),
+	(b"", Identity, b"ous "),
+	(b"", UppercaseAll, b", "),
+	(b"", UppercaseFirst, b"='"),
+	(b" ", UppercaseFirst, b","),
+	(b" ", UppercaseAll, b"=\""),
+	(b" ", UppercaseAll, b", "),
+	(b"", UppercaseAll, b","),
+	(b"", UppercaseAll, b"("),
+	(b"", UppercaseAll, b". "),
+	(b" ", UppercaseAll, b"."),
+	(b"", UppercaseAll, b"='"),
+	(b" ", UppercaseAll, b". "),
+	(b" ", UppercaseFirst, b"=\""),
+	(b" ", UppercaseAll, b"='"),
+	(b" ", UppercaseFirst, b"='"),
+];
+
+///The UTF8 context mode part from the last byte
+const UTF8_CONTEXT_LAST: [u8; 256] = [
+	0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 4, 0, 0, 4, 0, 0,
+	0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+	8, 12, 16, 12, 12, 20, 12, 16, 24, 28, 12, 12, 32, 12, 36, 12,
+	44, 44, 44, 44, 44, 44, 44, 44, 44, 44, 32, 32, 24, 40, 28, 12,
+	12, 48, 52, 52, 52, 48, 52, 52, 52, 48, 52, 52, 52, 52, 52, 48,
+	52, 52, 52, 52, 52, 48, 52, 52, 52, 52, 52, 24, 12, 28, 12, 12,
+	12, 56, 60, 60, 60, 56, 60, 60, 60, 56, 60, 60, 60, 60, 60, 56,
+	60, 60, 60, 60, 60, 56, 60, 60, 60, 60, 60, 24, 12, 28, 12, 0,
+	0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1,
+	0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1,
+	0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1,
+	0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1,
+	2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3,
+	2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3,
+	2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3,
+	2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3,
+];
+///The UTF8 context mode part from the second last byte
+const UTF8_CONTEXT_SECOND_LAST: [u8; 256] = [
+	0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+	0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+	0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+	2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 1,
+	1, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
+	2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1,
+	1, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+	3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 1, 1, 1, 1, 0,
+	0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+	0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+	0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+	0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+	0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+	0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+	2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
+	2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
+];
+
+///The order the code lengths of the code length code are stored in
+const CODE_LENGTH_ORDER: [usize; 18] = [1, 2, 3, 4, 0, 5, 17, 6, 16, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+///Lengths of the fixed code the code lengths of the code length code are stored with
+const CODE_LENGTH_CODE_LENGTHS: [u8; 6] = [2, 4, 3, 2, 2, 4];
+const MAX_BITS: usize = 15;
+const BLOCK_LENGTH_BASE: [u32; 26] = [
+	1, 5, 9, 13, 17, 25, 33, 41, 49, 65, 81, 97, 113, 145, 177, 209, 241, 305, 369, 497, 753, 1265, 2289, 4337, 8433, 16625,
+];
+const BLOCK_LENGTH_EXTRA: [u32; 26] = [2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 6, 6, 7, 8, 9, 10, 11, 12, 13, 24];
+const INSERT_LENGTH_BASE: [u32; 24] = [0, 1, 2, 3, 4, 5, 6, 8, 10, 14, 18, 26, 34, 50, 66, 98, 130, 194, 322, 578, 1090, 2114, 6210, 22594];
+const INSERT_LENGTH_EXTRA: [u32; 24] = [0, 0, 0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 7, 8, 9, 10, 12, 14, 24];
+const COPY_LENGTH_BASE: [u32; 24] = [2, 3, 4, 5, 6, 7, 8, 9, 10, 12, 14, 18, 22, 30, 38, 54, 70, 102, 134, 198, 326, 582, 1094, 2118];
+const COPY_LENGTH_EXTRA: [u32; 24] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 7, 8, 9, 10, 24];
+///The first insert and copy length code of each cell of 64 insert-and-copy commands
+const COMMAND_CELLS: [(usize, usize); 11] = [(0, 0), (0, 8), (0, 0), (0, 8), (8, 0), (8, 8), (0, 16), (16, 0), (8, 16), (16, 8), (16, 16)];
+///Which of the last distances each short distance code refers to and what is added to it
+const SHORT_DISTANCE_INDEX: [usize; 16] = [0, 1, 2, 3, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1];
+const SHORT_DISTANCE_OFFSET: [i64; 16] = [0, 0, 0, 0, -1, 1, -2, 2, -3, 3, -1, 1, -2, 2, -3, 3];
+
+///Reads bits from the least significant bit of each byte first
+struct BitReader<'a>{
+	data: &'a [u8],
+	pos: usize,
+	buffer: u64,
+	count: u32,
+}
+impl<'a> BitReader<'a>{
+	fn new(data: &'a [u8])->Self{Self{data, pos: 0, buffer: 0, count: 0}}
+	fn bits(&mut self, n: u32)->Result<u32, FromFileErr<(),()>>{
+		while self.count < n{
+			let Some(byte) = self.data.get(self.pos) else{return Err(FromFileErr::EOF)};
+			self.pos += 1;
+			self.buffer |= (*byte as u64) << self.count;
+			self.count += 8;
+		}
+		let rv = (self.buffer & ((1u64 << n) - 1)) as u32;
+		self.buffer >>= n;
+		self.count -= n;
+		Ok(rv)
+	}
+	///Skips to the next byte, the bits skipped have to be zero
+	fn align(&mut self)->Result<(), FromFileErr<(),()>>{
+		let padding = unwrap_or_ret!(self.bits(self.count % 8));
+		if padding != 0{return Err(FromFileErr::InvalidData(()));}
+		Ok(())
+	}
+	///The next `n` bytes after the reader has been aligned
+	fn bytes(&mut self, n: usize)->Result<&'a [u8], FromFileErr<(),()>>{
+		let Some(rv) = self.data.get(self.pos..self.pos + n) else{return Err(FromFileErr::EOF)};
+		self.pos += n;
+		Ok(rv)
+	}
+}
+
+///A canonical prefix code as the number of codes of each length and the symbols in code order, a code of one symbol uses no bits
+struct PrefixCode{
+	counts: [u16; MAX_BITS + 1],
+	symbols: Vec<u16>,
+}
+impl PrefixCode{
+	fn single(symbol: u16)->Self{Self{counts: [0; MAX_BITS + 1], symbols: vec![symbol]}}
+	fn new(lengths: &[u8])->Self{
+		let mut counts = [0u16; MAX_BITS + 1];
+		for l in lengths{counts[*l as usize] += 1;}
+		counts[0] = 0;
+		let mut offsets = [0u16; MAX_BITS + 2];
+		for l in 1..=MAX_BITS{offsets[l + 1] = offsets[l] + counts[l];}
+		let mut symbols = vec![0; offsets[MAX_BITS + 1] as usize];
+		for (symbol, l) in lengths.iter().enumerate(){if *l != 0{
+			symbols[offsets[*l as usize] as usize] = symbol as u16;
+			offsets[*l as usize] += 1;
+		}}
+		Self{counts, symbols}
+	}
+	fn decode(&self, reader: &mut BitReader)->Result<u16, FromFileErr<(),()>>{
+		if self.symbols.len() == 1{return Ok(self.symbols[0]);}
+		let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+		for l in 1..=MAX_BITS{
+			code |= unwrap_or_ret!(reader.bits(1)) as i32;
+			let count = self.counts[l] as i32;
+			if code - first < count{return Ok(self.symbols[(index + code - first) as usize]);}
+			index += count;
+			first = (first + count) << 1;
+			code <<= 1;
+		}
+		Err(FromFileErr::InvalidData(()))
+	}
+	///Reads a simple or complex prefix code for an alphabet of `alphabet_size` symbols
+	fn read(reader: &mut BitReader, alphabet_size: usize)->Result<Self, FromFileErr<(),()>>{
+		let skip = unwrap_or_ret!(reader.bits(2)) as usize;
+		if skip == 1{
+			// the symbols are listed with lengths set by how many there are
+			let symbol_count = unwrap_or_ret!(reader.bits(2)) as usize + 1;
+			let symbol_bits = usize::BITS - (alphabet_size - 1).leading_zeros();
+			let mut symbols = [0usize; 4];
+			for i in 0..symbol_count{
+				symbols[i] = unwrap_or_ret!(reader.bits(symbol_bits)) as usize;
+				if symbols[i] >= alphabet_size || symbols[..i].contains(&symbols[i]){return Err(FromFileErr::InvalidData(()));}
+			}
+			let lengths: &[u8] = match symbol_count{
+				1 => return Ok(Self::single(symbols[0] as u16)),
+				2 => &[1, 1],
+				3 => &[1, 2, 2],
+				_ => if unwrap_or_ret!(reader.bits(1)) == 0{&[2, 2, 2, 2]}else{&[1, 2, 3, 3]},
+			};
+			let mut all = vec![0u8; alphabet_size];
+			for (symbol, length) in symbols.iter().zip(lengths.iter()){all[*symbol] = *length;}
+			return Ok(Self::new(&all));
+		}
+
+		let fixed = Self::new(&CODE_LENGTH_CODE_LENGTHS);
+		let mut code_lengths = [0u8; 18];
+		let (mut space, mut count) = (32i32, 0);
+		for i in CODE_LENGTH_ORDER[skip..].iter(){
+			let length = unwrap_or_ret!(fixed.decode(reader)) as u8;
+			code_lengths[*i] = length;
+			if length != 0{
+				space -= 32 >> length;
+				count += 1;
+				if space <= 0{break;}
+			}
+		}
+		if count != 1 && space != 0{return Err(FromFileErr::InvalidData(()));}
+		let code_length_code = if count == 1{
+			Self::single(code_lengths.iter().position(|l|*l != 0).unwrap() as u16)
+		}else{Self::new(&code_lengths)};
+
+		let mut lengths = vec![0u8; alphabet_size];
+		let (mut symbol, mut space) = (0, 32768i32);
+		let (mut previous, mut repeat, mut repeat_length) = (8u8, 0usize, 0u8);
+		while symbol < alphabet_size && space > 0{
+			let code = unwrap_or_ret!(code_length_code.decode(reader)) as u8;
+			if code < 16{
+				repeat = 0;
+				lengths[symbol] = code;
+				symbol += 1;
+				if code != 0{
+					previous = code;
+					space -= 32768 >> code;
+				}
+				continue;
+			}
+			// repeated lengths, consecutive repeat codes extend the last repeat
+			let (length, extra_bits) = if code == 16{(previous, 2)}else{(0, 3)};
+			if repeat_length != length{
+				repeat = 0;
+				repeat_length = length;
+			}
+			let old_repeat = repeat;
+			if repeat > 0{repeat = (repeat - 2) << extra_bits;}
+			repeat += unwrap_or_ret!(reader.bits(extra_bits)) as usize + 3;
+			let delta = repeat - old_repeat;
+			if symbol + delta > alphabet_size{return Err(FromFileErr::InvalidData(()));}
+			lengths[symbol..symbol + delta].fill(length);
+			symbol += delta;
+			if length != 0{space -= (delta << (15 - length)) as i32;}
+		}
+		if space != 0{return Err(FromFileErr::InvalidData(()));}
+		Ok(Self::new(&lengths))
+	}
+}
+
+///Reads a number from 0 to 255 stored in 1 to 11 bits
+fn read_var_len_u8(reader: &mut BitReader)->Result<usize, FromFileErr<(),()>>{
+	if unwrap_or_ret!(reader.bits(1)) == 0{return Ok(0);}
+	let n = unwrap_or_ret!(reader.bits(3));
+	if n == 0{return Ok(1);}
+	Ok((1 << n) + unwrap_or_ret!(reader.bits(n)) as usize)
+}
+
+///The block types and counts of literals, commands or distances in a meta-block
+struct BlockSwitch{
+	num_types: usize,
+	codes: Option<(PrefixCode, PrefixCode)>,
+	current: usize,
+	previous: usize,
+	remaining: u32,
+}
+impl BlockSwitch{
+	fn read(reader: &mut BitReader)->Result<Self, FromFileErr<(),()>>{
+		let num_types = unwrap_or_ret!(read_var_len_u8(reader)) + 1;
+		let mut rv = Self{num_types, codes: None, current: 0, previous: 1, remaining: u32::MAX};
+		if num_types >= 2{
+			let type_code = unwrap_or_ret!(PrefixCode::read(reader, num_types + 2));
+			let count_code = unwrap_or_ret!(PrefixCode::read(reader, BLOCK_LENGTH_BASE.len()));
+			rv.remaining = unwrap_or_ret!(Self::read_count(reader, &count_code));
+			rv.codes = Some((type_code, count_code));
+		}
+		Ok(rv)
+	}
+	fn read_count(reader: &mut BitReader, code: &PrefixCode)->Result<u32, FromFileErr<(),()>>{
+		let symbol = unwrap_or_ret!(code.decode(reader)) as usize;
+		Ok(BLOCK_LENGTH_BASE[symbol] + unwrap_or_ret!(reader.bits(BLOCK_LENGTH_EXTRA[symbol])))
+	}
+	///The block type of the next item, switching block when the current one has run out
+	fn next(&mut self, reader: &mut BitReader)->Result<usize, FromFileErr<(),()>>{
+		if self.remaining == 0{
+			let Some((type_code, count_code)) = &self.codes else{return Err(FromFileErr::InvalidData(()))};
+			let block_type = match unwrap_or_ret!(type_code.decode(reader)) as usize{
+				0 => self.previous,
+				1 => (self.current + 1) % self.num_types,
+				n => n - 2,
+			};
+			self.previous = self.current;
+			self.current = block_type;
+			self.remaining = unwrap_or_ret!(Self::read_count(reader, count_code));
+		}
+		self.remaining -= 1;
+		Ok(self.current)
+	}
+}
+
+///Reads a context map of `size` entries each choosing one of `num_trees` prefix codes
+fn read_context_map(reader: &mut BitReader, size: usize, num_trees: usize)->Result<Vec<u8>, FromFileErr<(),()>>{
+	let mut map = vec![0u8; size];
+	if num_trees < 2{return Ok(map);}
+	let max_run_prefix = if unwrap_or_ret!(reader.bits(1)) == 1{unwrap_or_ret!(reader.bits(4)) as usize + 1}else{0};
+	let code = unwrap_or_ret!(PrefixCode::read(reader, num_trees + max_run_prefix));
+	let mut i = 0;
+	while i < size{
+		match unwrap_or_ret!(code.decode(reader)) as usize{
+			0 => i += 1,
+			// a run of zeros
+			n if n <= max_run_prefix => {
+				let run = (1 << n) + unwrap_or_ret!(reader.bits(n as u32)) as usize;
+				if i + run > size{return Err(FromFileErr::InvalidData(()));}
+				i += run;
+			},
+			n => {
+				map[i] = (n - max_run_prefix) as u8;
+				i += 1;
+			},
+		}
+	}
+	// inverse move-to-front transform
+	if unwrap_or_ret!(reader.bits(1)) == 1{
+		let mut mtf: Vec<u8> = (0..=255).collect();
+		for v in map.iter_mut(){
+			let index = *v as usize;
+			*v = mtf[index];
+			mtf.remove(index);
+			mtf.insert(0, *v);
+		}
+	}
+	if map.iter().any(|v|*v as usize >= num_trees){return Err(FromFileErr::InvalidData(()));}
+	Ok(map)
+}
+
+///Uppercases the UTF-8 character at the start of `p` the way RFC 7932 does, returns the length of the character
+fn uppercase(p: &mut [u8])->usize{
+	if p[0] < 0xC0{
+		if p[0].is_ascii_lowercase(){p[0] ^= 32;}
+		1
+	}else if p[0] < 0xE0{
+		if let Some(b) = p.get_mut(1){*b ^= 32;}
+		2
+	}else{
+		if let Some(b) = p.get_mut(2){*b ^= 5;}
+		3
+	}
+}
+fn transform_word(word: &[u8], transform: usize, out: &mut Vec<u8>){
+	let (prefix, transform, suffix) = TRANSFORMS[transform];
+	out.extend_from_slice(prefix);
+	let start = out.len();
+	out.extend_from_slice(match transform{
+		OmitFirst(n) => &word[n.min(word.len())..],
+		OmitLast(n) => &word[..word.len().saturating_sub(n)],
+		_ => word,
+	});
+	match transform{
+		UppercaseFirst if out.len() > start => {uppercase(&mut out[start..]);},
+		UppercaseAll => {
+			let mut i = start;
+			while i < out.len(){i += uppercase(&mut out[i..]);}
+		},
+		_ => {},
+	}
+	out.extend_from_slice(suffix);
+}
+
+///The id of the literal context from the last two bytes for a context mode
+fn literal_context(mode: u8, p1: u8, p2: u8)->usize{
+	let signed = |b: u8|match b{0 => 0, 1..=15 => 1, 16..=63 => 2, 64..=127 => 3, 128..=191 => 4, 192..=239 => 5, 240..=254 => 6, 255 => 7};
+	match mode{
+		0 => (p1 & 0x3F) as usize,
+		1 => (p1 >> 2) as usize,
+		2 => (UTF8_CONTEXT_LAST[p1 as usize] | UTF8_CONTEXT_SECOND_LAST[p2 as usize]) as usize,
+		_ => (signed(p1) << 3 | signed(p2)) as usize,
+	}
+}
+
+///The state kept between meta-blocks
+struct Decoder<'a>{
+	reader: BitReader<'a>,
+	out: Vec<u8>,
+	window_size: usize,
+	///The last four distances with the most recent first
+	distances: [usize; 4],
+}
+impl Decoder<'_>{
+	fn meta_block(&mut self, length: usize)->Result<(), FromFileErr<(),()>>{
+		let reader = &mut self.reader;
+		let mut literal_blocks = unwrap_or_ret!(BlockSwitch::read(reader));
+		let mut command_blocks = unwrap_or_ret!(BlockSwitch::read(reader));
+		let mut distance_blocks = unwrap_or_ret!(BlockSwitch::read(reader));
+		let postfix_bits = unwrap_or_ret!(reader.bits(2));
+		let direct_count = (unwrap_or_ret!(reader.bits(4)) << postfix_bits) as usize;
+		let mut context_modes = Vec::with_capacity(literal_blocks.num_types);
+		for _ in 0..literal_blocks.num_types{context_modes.push(unwrap_or_ret!(reader.bits(2)) as u8);}
+		let literal_trees = unwrap_or_ret!(read_var_len_u8(reader)) + 1;
+		let literal_map = unwrap_or_ret!(read_context_map(reader, 64 * literal_blocks.num_types, literal_trees));
+		let distance_trees = unwrap_or_ret!(read_var_len_u8(reader)) + 1;
+		let distance_map = unwrap_or_ret!(read_context_map(reader, 4 * distance_blocks.num_types, distance_trees));
+		let mut literal_codes = Vec::with_capacity(literal_trees);
+		for _ in 0..literal_trees{literal_codes.push(unwrap_or_ret!(PrefixCode::read(reader, 256)));}
+		let mut command_codes = Vec::with_capacity(command_blocks.num_types);
+		for _ in 0..command_blocks.num_types{command_codes.push(unwrap_or_ret!(PrefixCode::read(reader, 704)));}
+		let distance_alphabet = 16 + direct_count + (48 << postfix_bits);
+		let mut distance_codes = Vec::with_capacity(distance_trees);
+		for _ in 0..distance_trees{distance_codes.push(unwrap_or_ret!(PrefixCode::read(reader, distance_alphabet)));}
+
+		let mut remaining = length;
+		while remaining > 0{
+			let command = unwrap_or_ret!(command_codes[unwrap_or_ret!(command_blocks.next(reader))].decode(reader)) as usize;
+			let (insert_base, copy_base) = COMMAND_CELLS[command >> 6];
+			let insert_code = insert_base + ((command >> 3) & 7);
+			let copy_code = copy_base + (command & 7);
+			let insert_length = INSERT_LENGTH_BASE[insert_code] as usize + unwrap_or_ret!(reader.bits(INSERT_LENGTH_EXTRA[insert_code])) as usize;
+			let copy_length = COPY_LENGTH_BASE[copy_code] as usize + unwrap_or_ret!(reader.bits(COPY_LENGTH_EXTRA[copy_code])) as usize;
+
+			if insert_length > remaining{return Err(FromFileErr::InvalidData(()));}
+			for _ in 0..insert_length{
+				let block_type = unwrap_or_ret!(literal_blocks.next(reader));
+				let len = self.out.len();
+				let p1 = if len > 0{self.out[len - 1]}else{0};
+				let p2 = if len > 1{self.out[len - 2]}else{0};
+				let tree = literal_map[64 * block_type + literal_context(context_modes[block_type], p1, p2)];
+				self.out.push(unwrap_or_ret!(literal_codes[tree as usize].decode(reader)) as u8);
+			}
+			remaining -= insert_length;
+			// the copy of the last command is ignored once the meta-block is full
+			if remaining == 0{break;}
+
+			let distance_code = if command < 128{0}else{
+				let block_type = unwrap_or_ret!(distance_blocks.next(reader));
+				let tree = distance_map[4 * block_type + copy_length.min(5) - 2];
+				unwrap_or_ret!(distance_codes[tree as usize].decode(reader)) as usize
+			};
+			let distance = if distance_code < 16{
+				let d = self.distances[SHORT_DISTANCE_INDEX[distance_code]] as i64 + SHORT_DISTANCE_OFFSET[distance_code];
+				if d <= 0{return Err(FromFileErr::InvalidData(()));}
+				d as usize
+			}else if distance_code < 16 + direct_count{
+				distance_code - 15
+			}else{
+				let code = distance_code - direct_count - 16;
+				let extra_bits = 1 + (code >> (postfix_bits + 1)) as u32;
+				let extra = unwrap_or_ret!(reader.bits(extra_bits)) as usize;
+				let high = code >> postfix_bits;
+				let low = code & ((1 << postfix_bits) - 1);
+				let offset = ((2 + (high & 1)) << extra_bits) - 4;
+				((offset + extra) << postfix_bits) + low + direct_count + 1
+			};
+
+			let max_distance = self.window_size.min(self.out.len());
+			if distance > max_distance{
+				// a reference to a transformed word of the static dictionary
+				if !(MIN_DICTIONARY_WORD_LENGTH..=MAX_DICTIONARY_WORD_LENGTH).contains(&copy_length){return Err(FromFileErr::InvalidData(()));}
+				let word_id = distance - max_distance - 1;
+				let size_bits = DICTIONARY_SIZE_BITS_BY_LENGTH[copy_length];
+				let index = word_id & ((1 << size_bits) - 1);
+				let transform = word_id >> size_bits;
+				if transform >= TRANSFORMS.len(){return Err(FromFileErr::InvalidData(()));}
+				let offset = DICTIONARY_OFFSETS_BY_LENGTH[copy_length] as usize + index * copy_length;
+				let start = self.out.len();
+				transform_word(&DICTIONARY[offset..offset + copy_length], transform, &mut self.out);
+				let written = self.out.len() - start;
+				if written > remaining{return Err(FromFileErr::InvalidData(()));}
+				remaining -= written;
+			}else{
+				if copy_length > remaining{return Err(FromFileErr::InvalidData(()));}
+				if distance_code != 0{
+					self.distances = [distance, self.distances[0], self.distances[1], self.distances[2]];
+				}
+				// the copy can overlap the bytes it produces
+				let start = self.out.len() - distance;
+				for i in 0..copy_length{self.out.push(self.out[start + i]);}
+				remaining -= copy_length;
+			}
+		}
+		Ok(())
+	}
+}
+
+///Decompresses a Brotli stream, failing if the output would be longer than `limit`
+pub fn decompress(data: &[u8], limit: usize)->Result<Vec<u8>, FromFileErr<(),()>>{
+	let mut reader = BitReader::new(data);
+	let window_bits = if unwrap_or_ret!(reader.bits(1)) == 0{16}else{
+		match unwrap_or_ret!(reader.bits(3)){
+			0 => match unwrap_or_ret!(reader.bits(3)){
+				0 => 17,
+				1 => return Err(FromFileErr::InvalidData(())),
+				n => 8 + n,
+			},
+			n => 17 + n,
+		}
+	};
+	let mut decoder = Decoder{reader, out: Vec::new(), window_size: (1 << window_bits) - 16, distances: [4, 11, 15, 16]};
+	loop{
+		let reader = &mut decoder.reader;
+		let last = unwrap_or_ret!(reader.bits(1)) == 1;
+		if last && unwrap_or_ret!(reader.bits(1)) == 1{break;}
+		let nibbles = match unwrap_or_ret!(reader.bits(2)){
+			3 => 0,
+			n => n + 4,
+		};
+		if nibbles == 0{
+			// a metadata block which is skipped
+			if unwrap_or_ret!(reader.bits(1)) != 0{return Err(FromFileErr::InvalidData(()));}
+			let skip_bytes = unwrap_or_ret!(reader.bits(2));
+			let mut skip = 0;
+			for i in 0..skip_bytes{
+				let byte = unwrap_or_ret!(reader.bits(8)) as usize;
+				if i + 1 == skip_bytes && skip_bytes > 1 && byte == 0{return Err(FromFileErr::InvalidData(()));}
+				skip |= byte << (8 * i);
+			}
+			if skip_bytes > 0{skip += 1;}
+			unwrap_or_ret!(reader.align());
+			unwrap_or_ret!(reader.bytes(skip));
+			if last{break;}
+			continue;
+		}
+		let length = unwrap_or_ret!(reader.bits(4 * nibbles)) as usize + 1;
+		if nibbles > 4 && (length - 1) >> (4 * nibbles - 4) == 0{return Err(FromFileErr::InvalidData(()));}
+		if decoder.out.len() + length > limit{return Err(FromFileErr::InvalidData(()));}
+		if !last && unwrap_or_ret!(reader.bits(1)) == 1{
+			unwrap_or_ret!(reader.align());
+			let data = unwrap_or_ret!(reader.bytes(length));
+			decoder.out.extend_from_slice(data);
+			continue;
+		}
+		unwrap_or_ret!(decoder.meta_block(length));
+		if last{break;}
+	}
+	Ok(decoder.out)
+}
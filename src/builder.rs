@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+
+use crate::core::{bounded_array_from_file, calc_checksum, FromFileErr, OTTF, SFNTVer, TableDirectory, TableRecord, Tag, ToFile};
+
+///Assembles a font file from tables, the table directory, padding and checksums are computed when it is built
+#[derive(Debug)]
+pub struct FontBuilder{
+	sfnt_version: SFNTVer,
+	///The data of each table by tag, kept sorted as the table records have to be
+	tables: BTreeMap<String, Vec<u8>>,
+}
+impl FontBuilder{
+	///Offset of checkSumAdjustment in the head table
+	const CHECKSUM_ADJUSTMENT_OFFSET: usize = 8;
+	///The whole font sums to this once checkSumAdjustment is set
+	const CHECKSUM_MAGIC: u32 = 0xB1B0AFBA;
+
+	pub fn new(sfnt_version: SFNTVer)->Self{Self{sfnt_version, tables: BTreeMap::new()}}
+	///A builder holding a copy of every table of `font`
+	pub fn from_font<F>(f: &mut F, font: &OTTF)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		let mut rv = Self::new(font.table_directory.sfnt_version);
+		for record in font.table_directory.table_records.iter(){
+			if f.seek(SeekFrom::Start(record.offset as u64)).is_err(){return Err(FromFileErr::EOF);}
+			let data: Box<[u8]> = unwrap_or_ret!(bounded_array_from_file(f, record.length, 1, u64::MAX));
+			rv.tables.insert(record.table_tag.data.clone(), data.into());
+		}
+		Ok(rv)
+	}
+	///Adds the table `tag` as raw bytes, replacing the table with the same tag
+	pub fn add_raw_table(&mut self, tag: &str, data: Vec<u8>){
+		self.tables.insert(tag.to_string(), data);
+	}
+	///Adds the table `tag` by writing `table`, replacing the table with the same tag
+	pub fn add_table<T>(&mut self, tag: &str, table: &T)->Result<(), std::io::Error> where T: ToFile{
+		let mut data = Cursor::new(Vec::new());
+		unwrap_or_ret!(table.to_file(&mut data));
+		self.add_raw_table(tag, data.into_inner());
+		Ok(())
+	}
+	///Removes the table `tag` returning its data
+	pub fn remove_table(&mut self, tag: &str)->Option<Vec<u8>>{self.tables.remove(tag)}
+	pub fn table(&self, tag: &str)->Option<&[u8]>{self.tables.get(tag).map(|t|t.as_slice())}
+
+	///The checksum of the table record for `data`, the checksum of head is taken with checkSumAdjustment as 0
+	pub fn table_checksum(tag: &str, data: &[u8])->u32{
+		if tag == "head" && data.len() >= Self::CHECKSUM_ADJUSTMENT_OFFSET + 4{
+			let mut data = data.to_vec();
+			data[Self::CHECKSUM_ADJUSTMENT_OFFSET..Self::CHECKSUM_ADJUSTMENT_OFFSET + 4].fill(0);
+			calc_checksum(&data)
+		}else{calc_checksum(data)}
+	}
+
+	///The font file with tables in tag order, each padded to 4 bytes with its checksum in its record and head.checkSumAdjustment updated
+	pub fn build(&self)->Result<Vec<u8>, std::io::Error>{
+		if self.tables.len() > u16::MAX as usize{return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "too many tables"));}
+		let num_tables = self.tables.len() as u16;
+		let (search_range, entry_selector, range_shift) = TableDirectory::search_params(num_tables);
+		let mut offset = 12 + 16 * self.tables.len();
+		let mut records = Vec::with_capacity(self.tables.len());
+		let mut head_offset = None;
+		for (tag, data) in self.tables.iter(){
+			if tag == "head" && data.len() >= Self::CHECKSUM_ADJUSTMENT_OFFSET + 4{head_offset = Some(offset);}
+			let checksum = Self::table_checksum(tag, data);
+			records.push(TableRecord{table_tag: Tag{data: tag.clone()}, checksum, offset: offset as u32, length: data.len() as u32});
+			offset += (data.len() + 3) & !3;
+		}
+		let directory = TableDirectory{
+			sfnt_version: self.sfnt_version,
+			num_tables,
+			search_range,
+			entry_selector,
+			range_shift,
+			table_records: records.into(),
+		};
+
+		let mut f = Cursor::new(Vec::with_capacity(offset));
+		unwrap_or_ret!(directory.to_file(&mut f));
+		for data in self.tables.values(){
+			unwrap_or_ret!(f.write_all(data));
+			unwrap_or_ret!(f.write_all(&[0; 3][..(4 - data.len() % 4) % 4]));
+		}
+		let mut font = f.into_inner();
+		if let Some(head_offset) = head_offset{
+			let adjustment = head_offset + Self::CHECKSUM_ADJUSTMENT_OFFSET;
+			font[adjustment..adjustment + 4].fill(0);
+			let value = Self::CHECKSUM_MAGIC.wrapping_sub(calc_checksum(&font));
+			font[adjustment..adjustment + 4].copy_from_slice(&value.to_be_bytes());
+		}
+		Ok(font)
+	}
+}
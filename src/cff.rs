@@ -0,0 +1,1174 @@
+use std::io::{Read, Seek, SeekFrom};
+
+use macros::FromFile;
+
+use crate::core::{FromFile, FromFileErr, ItemVariationStore, array_from_file, bounded_array_from_file, bytes_left};
+use crate::outline::{Outline, Point, Transform};
+
+#[derive(Debug,FromFile)]
+pub struct CffHeader{
+	///Format major version (starting at 1)
+	pub major: u8,
+	///Format minor version (starting at 0)
+	pub minor: u8,
+	///Header size (bytes)
+	pub hdr_size: u8,
+	///Absolute offset (0) size
+	pub off_size: u8,
+}
+
+///An array of variable-sized objects
+#[derive(Debug, Clone, Default)]
+pub struct CffIndex{
+	///Number of objects stored in INDEX
+	pub count: u32,
+	///Offset array element size
+	pub off_size: u8,
+	///Offset array (from byte preceding object data)
+	pub offsets: Box<[u32]>,
+	///Object data
+	pub data: Box<[u8]>,
+}
+impl_from_file!(CffIndex, (), (), f, {
+	let count = unwrap_or_ret!(u16::from_file(f)) as u32;
+	Self::from_file_with_count(f, count)
+});
+impl CffIndex{
+	///Reads a CFF2 INDEX, which is the same as a CFF INDEX except the count is a uint32
+	pub fn from_file_cff2<F>(f: &mut F)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		let count = unwrap_or_ret!(u32::from_file(f));
+		Self::from_file_with_count(f, count)
+	}
+	fn from_file_with_count<F>(f: &mut F, count: u32)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		// an empty INDEX is only the count
+		if count == 0{return Ok(Self::default());}
+		let off_size = unwrap_or_ret!(u8::from_file(f));
+		if !(1..=4).contains(&off_size){return Err(FromFileErr::InvalidData(()));}
+		// the offsets have to fit in what is left of the file before they are allocated
+		if (count as u64 + 1) * off_size as u64 > bytes_left(f){return Err(FromFileErr::InvalidData(()));}
+		let mut offsets = Vec::with_capacity(count as usize + 1);
+		for _ in 0..=count{
+			let mut offset = 0u32;
+			for _ in 0..off_size{offset = offset << 8 | unwrap_or_ret!(u8::from_file(f)) as u32;}
+			offsets.push(offset);
+		}
+		if offsets[0] != 1 || offsets.windows(2).any(|w|w[1] < w[0])
+		{return Err(FromFileErr::InvalidData(()));}
+		let data = unwrap_or_ret!(bounded_array_from_file(f, offsets[count as usize] - 1, 1, u64::MAX));
+		Ok(Self{count, off_size, offsets: offsets.into(), data})
+	}
+	pub fn len(&self)->usize{self.count as usize}
+	pub fn is_empty(&self)->bool{self.count == 0}
+	pub fn get(&self, i: usize)->Option<&[u8]>{
+		if i >= self.count as usize{return None;}
+		self.data.get(self.offsets[i] as usize - 1..self.offsets[i + 1] as usize - 1)
+	}
+	///The bias added to subroutine numbers before they index this INDEX
+	pub fn subr_bias(&self)->i32{match self.count{
+		0..=1239 => 107,
+		1240..=33899 => 1131,
+		_ => 32768,
+	}}
+}
+
+///A DICT as a list of operators along with their operands
+#[derive(Debug, Clone, Default)]
+pub struct CffDict{
+	pub entries: Box<[(u16, Box<[f64]>)]>,
+}
+impl CffDict{
+	pub const VERSION: u16 = 0;
+	pub const NOTICE: u16 = 1;
+	pub const FULL_NAME: u16 = 2;
+	pub const FAMILY_NAME: u16 = 3;
+	pub const WEIGHT: u16 = 4;
+	pub const FONT_BBOX: u16 = 5;
+	pub const BLUE_VALUES: u16 = 6;
+	pub const OTHER_BLUES: u16 = 7;
+	pub const FAMILY_BLUES: u16 = 8;
+	pub const FAMILY_OTHER_BLUES: u16 = 9;
+	pub const STD_HW: u16 = 10;
+	pub const STD_VW: u16 = 11;
+	pub const UNIQUE_ID: u16 = 13;
+	pub const XUID: u16 = 14;
+	pub const CHARSET: u16 = 15;
+	pub const ENCODING: u16 = 16;
+	pub const CHAR_STRINGS: u16 = 17;
+	pub const PRIVATE: u16 = 18;
+	pub const SUBRS: u16 = 19;
+	pub const DEFAULT_WIDTH_X: u16 = 20;
+	pub const NOMINAL_WIDTH_X: u16 = 21;
+	pub const VSINDEX: u16 = 22;
+	pub const BLEND: u16 = 23;
+	pub const VSTORE: u16 = 24;
+	pub const COPYRIGHT: u16 = 0x0C00;
+	pub const IS_FIXED_PITCH: u16 = 0x0C01;
+	pub const ITALIC_ANGLE: u16 = 0x0C02;
+	pub const UNDERLINE_POSITION: u16 = 0x0C03;
+	pub const UNDERLINE_THICKNESS: u16 = 0x0C04;
+	pub const PAINT_TYPE: u16 = 0x0C05;
+	pub const CHARSTRING_TYPE: u16 = 0x0C06;
+	pub const FONT_MATRIX: u16 = 0x0C07;
+	pub const STROKE_WIDTH: u16 = 0x0C08;
+	pub const STEM_SNAP_H: u16 = 0x0C0C;
+	pub const STEM_SNAP_V: u16 = 0x0C0D;
+	pub const FORCE_BOLD: u16 = 0x0C0E;
+	pub const LANGUAGE_GROUP: u16 = 0x0C11;
+	pub const EXPANSION_FACTOR: u16 = 0x0C12;
+	pub const INITIAL_RANDOM_SEED: u16 = 0x0C13;
+	pub const SYNTHETIC_BASE: u16 = 0x0C14;
+	pub const POST_SCRIPT: u16 = 0x0C15;
+	pub const BASE_FONT_NAME: u16 = 0x0C16;
+	pub const BASE_FONT_BLEND: u16 = 0x0C17;
+	pub const ROS: u16 = 0x0C1E;
+	pub const CID_FONT_VERSION: u16 = 0x0C1F;
+	pub const CID_FONT_REVISION: u16 = 0x0C20;
+	pub const CID_FONT_TYPE: u16 = 0x0C21;
+	pub const CID_COUNT: u16 = 0x0C22;
+	pub const UID_BASE: u16 = 0x0C23;
+	pub const FD_ARRAY: u16 = 0x0C24;
+	pub const FD_SELECT: u16 = 0x0C25;
+	pub const FONT_NAME: u16 = 0x0C26;
+
+	pub fn parse(data: &[u8])->Result<Self, FromFileErr<(),()>>{Self::parse_with_store(data, None)}
+	///Parses a CFF2 DICT where `blend` operands are resolved to their default values using the region counts in `store`
+	pub fn parse_with_store(data: &[u8], store: Option<&ItemVariationStore>)->Result<Self, FromFileErr<(),()>>{
+		let mut entries = Vec::new();
+		let mut vsindex = 0usize;
+		let mut operands = Vec::new();
+		let mut i = 0;
+		let byte = |i: usize|data.get(i).copied().ok_or(FromFileErr::InvalidData(()));
+		while i < data.len(){
+			let b0 = data[i];
+			i += 1;
+			match b0{
+				0..=21 => {
+					let op = if b0 == 12{
+						i += 1;
+						0x0C00 | unwrap_or_ret!(byte(i - 1)) as u16
+					}else{b0 as u16};
+					entries.push((op, std::mem::take(&mut operands).into()));
+				},
+				22 => {
+					vsindex = operands.last().copied().unwrap_or(0.0) as usize;
+					entries.push((b0 as u16, std::mem::take(&mut operands).into()));
+				},
+				23 => {
+					let regions = match store.and_then(|s|s.item_variation_data.get(vsindex)){
+						Some(d) => d.region_index_count as usize,
+						None => return Err(FromFileErr::InvalidData(())),
+					};
+					let n = match operands.pop(){
+						Some(n) => n as usize,
+						None => return Err(FromFileErr::InvalidData(())),
+					};
+					// keep the n default values dropping the n*regions deltas after them
+					let start = match n.checked_mul(regions + 1).and_then(|c|operands.len().checked_sub(c)){
+						Some(s) => s,
+						None => return Err(FromFileErr::InvalidData(())),
+					};
+					operands.truncate(start + n);
+				},
+				24 => entries.push((b0 as u16, std::mem::take(&mut operands).into())),
+				28 => {
+					operands.push(i16::from_be_bytes([unwrap_or_ret!(byte(i)), unwrap_or_ret!(byte(i + 1))]) as f64);
+					i += 2;
+				},
+				29 => {
+					operands.push(i32::from_be_bytes([
+						unwrap_or_ret!(byte(i)), unwrap_or_ret!(byte(i + 1)), unwrap_or_ret!(byte(i + 2)), unwrap_or_ret!(byte(i + 3)),
+					]) as f64);
+					i += 4;
+				},
+				30 => {
+					let (v, len) = unwrap_or_ret!(Self::parse_real(&data[i..]));
+					operands.push(v);
+					i += len;
+				},
+				32..=246 => operands.push(b0 as f64 - 139.0),
+				247..=250 => {
+					operands.push((b0 as f64 - 247.0) * 256.0 + unwrap_or_ret!(byte(i)) as f64 + 108.0);
+					i += 1;
+				},
+				251..=254 => {
+					operands.push(-(b0 as f64 - 251.0) * 256.0 - unwrap_or_ret!(byte(i)) as f64 - 108.0);
+					i += 1;
+				},
+				_ => return Err(FromFileErr::InvalidData(())),
+			}
+		}
+		Ok(Self{entries: entries.into()})
+	}
+	///Parses a real number operand (without the leading 30) returning the value and the number of bytes used
+	fn parse_real(data: &[u8])->Result<(f64, usize), FromFileErr<(),()>>{
+		let mut s = String::new();
+		for (i, b) in data.iter().enumerate(){
+			for nibble in [b >> 4, b & 0xF]{match nibble{
+				0..=9 => s.push((b'0' + nibble) as char),
+				0xA => s.push('.'),
+				0xB => s.push('E'),
+				0xC => s.push_str("E-"),
+				0xE => s.push('-'),
+				0xF => return match s.parse(){
+					Ok(v) => Ok((v, i + 1)),
+					// an empty or otherwise malformed number is treated as 0
+					Err(_) => Ok((0.0, i + 1)),
+				},
+				_ => return Err(FromFileErr::InvalidData(())),
+			}}
+		}
+		Err(FromFileErr::InvalidData(()))
+	}
+	pub fn get(&self, op: u16)->Option<&[f64]>{
+		self.entries.iter().find(|(o, _)|*o == op).map(|(_, v)|&**v)
+	}
+	///The first operand of `op` or `default` if it is not present
+	pub fn get_number(&self, op: u16, default: f64)->f64{
+		self.get(op).and_then(|v|v.first().copied()).unwrap_or(default)
+	}
+}
+
+///`base` plus a DICT operand used as an offset or size, operands are reals so anything negative, non-finite or past the end of a u64 is invalid
+fn dict_offset(base: u64, operand: f64)->Result<u64, FromFileErr<(),()>>{
+	if !operand.is_finite() || operand < 0.0{return Err(FromFileErr::InvalidData(()));}
+	base.checked_add(operand as u64).ok_or(FromFileErr::InvalidData(()))
+}
+
+///The private DICT of a font (or a font DICT in a CID-keyed font) along with its local subroutines
+#[derive(Debug, Clone, Default)]
+pub struct CffPrivate{
+	pub dict: CffDict,
+	pub local_subrs: Option<CffIndex>,
+}
+impl CffPrivate{
+	///Reads a private DICT given the operands of the `Private` operator (size and offset from `base`)
+	fn from_file_with_range<F>(f: &mut F, base: u64, range: Option<&[f64]>, cff2: bool, store: Option<&ItemVariationStore>)->Result<Option<Self>, FromFileErr<(),()>> where F: Read, F: Seek{
+		let (size, offset) = match range{
+			Some([size, offset]) => (unwrap_or_ret!(dict_offset(0, *size)), unwrap_or_ret!(dict_offset(base, *offset))),
+			Some(_) => return Err(FromFileErr::InvalidData(())),
+			None => return Ok(None),
+		};
+		if f.seek(SeekFrom::Start(offset)).is_err(){return Err(FromFileErr::EOF);}
+		let size = match u32::try_from(size){
+			Ok(s) => s,
+			Err(_) => return Err(FromFileErr::InvalidData(())),
+		};
+		let data: Box<[u8]> = unwrap_or_ret!(bounded_array_from_file(f, size, 1, u64::MAX));
+		let dict = unwrap_or_ret!(CffDict::parse_with_store(&data, store));
+		let local_subrs = match dict.get(CffDict::SUBRS){
+			Some([subrs, ..]) => {
+				// the offset to the subroutines is relative to the start of the private DICT
+				if f.seek(SeekFrom::Start(unwrap_or_ret!(dict_offset(offset, *subrs)))).is_err()
+				{return Err(FromFileErr::EOF);}
+				Some(unwrap_or_ret!(if cff2{CffIndex::from_file_cff2(f)}else{CffIndex::from_file(f)}))
+			},
+			_ => None,
+		};
+		Ok(Some(Self{dict, local_subrs}))
+	}
+	pub fn default_width_x(&self)->f32{self.dict.get_number(CffDict::DEFAULT_WIDTH_X, 0.0) as f32}
+	pub fn nominal_width_x(&self)->f32{self.dict.get_number(CffDict::NOMINAL_WIDTH_X, 0.0) as f32}
+}
+
+///A font DICT of a CID-keyed font
+#[derive(Debug, Clone, Default)]
+pub struct CffFontDict{
+	pub dict: CffDict,
+	pub private: Option<CffPrivate>,
+}
+
+///Maps glyph ids to their SID (or CID in CID-keyed fonts)
+#[derive(Debug)]
+pub enum CffCharset{
+	///One of the predefined charsets (0 ISOAdobe, 1 Expert, 2 ExpertSubset)
+	Predefined(u32),
+	///The SID of each glyph, `.notdef` (glyph 0) is implied
+	Format0(Box<[u16]>),
+	///Ranges of consecutive SIDs as (first, number left)
+	Format1(Box<[(u16, u16)]>),
+	///Ranges of consecutive SIDs as (first, number left)
+	Format2(Box<[(u16, u16)]>),
+}
+impl CffCharset{
+	fn from_file_with_glyphs<F>(f: &mut F, num_glyphs: usize)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		let format = unwrap_or_ret!(u8::from_file(f));
+		let count = num_glyphs.saturating_sub(1);
+		Ok(match format{
+			0 => Self::Format0(unwrap_or_ret!(array_from_file(f, count))),
+			1 | 2 => {
+				let mut ranges = Vec::new();
+				let mut covered = 0;
+				while covered < count{
+					let first = unwrap_or_ret!(u16::from_file(f));
+					let left = if format == 1{unwrap_or_ret!(u8::from_file(f)) as u16}else{unwrap_or_ret!(u16::from_file(f))};
+					ranges.push((first, left));
+					covered += left as usize + 1;
+				}
+				if format == 1{Self::Format1(ranges.into())}else{Self::Format2(ranges.into())}
+			},
+			_ => return Err(FromFileErr::InvalidData(())),
+		})
+	}
+	///The SID (or CID) of `glyph_id`
+	pub fn sid(&self, glyph_id: u16)->Option<u16>{
+		if glyph_id == 0{return Some(0);}
+		let index = glyph_id as usize - 1;
+		match self{
+			// ISOAdobe maps glyph ids directly onto the first 229 SIDs
+			Self::Predefined(0) => if glyph_id < 229{Some(glyph_id)}else{None},
+			Self::Predefined(_) => None,
+			Self::Format0(sids) => sids.get(index).copied(),
+			Self::Format1(ranges) | Self::Format2(ranges) => {
+				let mut start = 0usize;
+				for (first, left) in ranges.iter(){
+					if index <= start + *left as usize{return Some(first + (index - start) as u16);}
+					start += *left as usize + 1;
+				}
+				None
+			},
+		}
+	}
+	///The glyph with the SID (or CID) `sid`
+	pub fn glyph_id(&self, sid: u16, num_glyphs: u16)->Option<u16>{
+		(0..num_glyphs).find(|g|self.sid(*g) == Some(sid))
+	}
+}
+
+///Maps glyph ids to an index in the FDArray
+#[derive(Debug)]
+pub enum FdSelect{
+	///The font DICT index of each glyph
+	Format0(Box<[u8]>),
+	///Ranges as (first glyph, font DICT index) followed by the sentinel glyph id
+	Format3(Box<[(u16, u8)]>, u16),
+	///Ranges as (first glyph, font DICT index) followed by the sentinel glyph id
+	Format4(Box<[(u32, u16)]>, u32),
+}
+impl FdSelect{
+	pub(crate) fn from_file_with_glyphs<F>(f: &mut F, num_glyphs: usize)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		Ok(match unwrap_or_ret!(u8::from_file(f)){
+			0 => Self::Format0(unwrap_or_ret!(array_from_file(f, num_glyphs))),
+			3 => {
+				let count = unwrap_or_ret!(u16::from_file(f));
+				if count as u64 * 3 > bytes_left(f){return Err(FromFileErr::InvalidData(()));}
+				let mut ranges = Vec::with_capacity(count as usize);
+				for _ in 0..count{ranges.push((unwrap_or_ret!(u16::from_file(f)), unwrap_or_ret!(u8::from_file(f))));}
+				Self::Format3(ranges.into(), unwrap_or_ret!(u16::from_file(f)))
+			},
+			4 => {
+				let count = unwrap_or_ret!(u32::from_file(f));
+				// each range is a uint32 and a uint16
+				if count as u64 * 6 > bytes_left(f){return Err(FromFileErr::InvalidData(()));}
+				let mut ranges = Vec::with_capacity(count as usize);
+				for _ in 0..count{ranges.push((unwrap_or_ret!(u32::from_file(f)), unwrap_or_ret!(u16::from_file(f))));}
+				Self::Format4(ranges.into(), unwrap_or_ret!(u32::from_file(f)))
+			},
+			_ => return Err(FromFileErr::InvalidData(())),
+		})
+	}
+	pub fn font_dict_index(&self, glyph_id: u16)->Option<usize>{match self{
+		Self::Format0(fds) => fds.get(glyph_id as usize).map(|fd|*fd as usize),
+		Self::Format3(ranges, sentinel) => {
+			if glyph_id >= *sentinel{return None;}
+			let i = ranges.partition_point(|(first, _)|*first <= glyph_id);
+			if i == 0{None}else{Some(ranges[i - 1].1 as usize)}
+		},
+		Self::Format4(ranges, sentinel) => {
+			if glyph_id as u32 >= *sentinel{return None;}
+			let i = ranges.partition_point(|(first, _)|*first <= glyph_id as u32);
+			if i == 0{None}else{Some(ranges[i - 1].1 as usize)}
+		},
+	}}
+}
+
+///The `CFF ` table containing a single font with Type 2 charstrings
+#[derive(Debug)]
+pub struct CffTable{
+	pub header: CffHeader,
+	pub name_index: CffIndex,
+	pub top_dict_index: CffIndex,
+	pub string_index: CffIndex,
+	pub global_subr_index: CffIndex,
+	///The parsed Top DICT of the first (and only) font
+	pub top_dict: CffDict,
+	pub char_strings: CffIndex,
+	pub charset: CffCharset,
+	///The private DICT (for fonts which aren't CID-keyed)
+	pub private: Option<CffPrivate>,
+	///The font DICTs (for CID-keyed fonts)
+	pub fd_array: Box<[CffFontDict]>,
+	pub fd_select: Option<FdSelect>,
+}
+impl_from_file!(CffTable, (), (), f, {
+	let start = f.stream_position().unwrap();
+	let header = unwrap_or_ret!(CffHeader::from_file(f));
+	if header.major != 1{return Err(FromFileErr::InvalidData(()));}
+	if f.seek(SeekFrom::Start(start + header.hdr_size as u64)).is_err(){return Err(FromFileErr::EOF);}
+	let name_index = unwrap_or_ret!(CffIndex::from_file(f));
+	let top_dict_index = unwrap_or_ret!(CffIndex::from_file(f));
+	let string_index = unwrap_or_ret!(CffIndex::from_file(f));
+	let global_subr_index = unwrap_or_ret!(CffIndex::from_file(f));
+	let top_dict = match top_dict_index.get(0){
+		Some(data) => unwrap_or_ret!(CffDict::parse(data)),
+		None => return Err(FromFileErr::InvalidData(())),
+	};
+	if top_dict.get_number(CffDict::CHARSTRING_TYPE, 2.0) != 2.0{return Err(FromFileErr::InvalidData(()));}
+
+	let char_strings = match top_dict.get(CffDict::CHAR_STRINGS){
+		Some([offset]) => {
+			if f.seek(SeekFrom::Start(unwrap_or_ret!(dict_offset(start, *offset)))).is_err(){return Err(FromFileErr::EOF);}
+			unwrap_or_ret!(CffIndex::from_file(f))
+		},
+		_ => return Err(FromFileErr::InvalidData(())),
+	};
+	let charset = match top_dict.get_number(CffDict::CHARSET, 0.0){
+		v if (0.0..=2.0).contains(&v) => CffCharset::Predefined(v as u32),
+		offset => {
+			if f.seek(SeekFrom::Start(unwrap_or_ret!(dict_offset(start, offset)))).is_err(){return Err(FromFileErr::EOF);}
+			unwrap_or_ret!(CffCharset::from_file_with_glyphs(f, char_strings.len()))
+		},
+	};
+	let private = unwrap_or_ret!(CffPrivate::from_file_with_range(f, start, top_dict.get(CffDict::PRIVATE), false, None));
+
+	let mut fd_array = Vec::new();
+	let mut fd_select = None;
+	if top_dict.get(CffDict::ROS).is_some(){
+		let (fd_array_offset, fd_select_offset) = match (top_dict.get(CffDict::FD_ARRAY), top_dict.get(CffDict::FD_SELECT)){
+			(Some([a]), Some([s])) => (unwrap_or_ret!(dict_offset(start, *a)), unwrap_or_ret!(dict_offset(start, *s))),
+			_ => return Err(FromFileErr::InvalidData(())),
+		};
+		if f.seek(SeekFrom::Start(fd_array_offset)).is_err(){return Err(FromFileErr::EOF);}
+		let font_dicts = unwrap_or_ret!(CffIndex::from_file(f));
+		for i in 0..font_dicts.len(){
+			let dict = unwrap_or_ret!(CffDict::parse(font_dicts.get(i).unwrap()));
+			let private = unwrap_or_ret!(CffPrivate::from_file_with_range(f, start, dict.get(CffDict::PRIVATE), false, None));
+			fd_array.push(CffFontDict{dict, private});
+		}
+		if f.seek(SeekFrom::Start(fd_select_offset)).is_err(){return Err(FromFileErr::EOF);}
+		fd_select = Some(unwrap_or_ret!(FdSelect::from_file_with_glyphs(f, char_strings.len())));
+	}
+
+	Ok(Self{
+		header,
+		name_index,
+		top_dict_index,
+		string_index,
+		global_subr_index,
+		top_dict,
+		char_strings,
+		charset,
+		private,
+		fd_array: fd_array.into(),
+		fd_select,
+	})
+});
+impl CffTable{
+	///The number of standard strings, SIDs above this index into the String INDEX
+	pub const STANDARD_STRING_COUNT: u16 = 391;
+
+	pub fn num_glyphs(&self)->u16{self.char_strings.len() as u16}
+	pub fn is_cid_keyed(&self)->bool{self.fd_select.is_some()}
+	///The PostScript name of the font
+	pub fn font_name(&self)->Option<String>{self.name_index.get(0).map(|n|String::from_utf8_lossy(n).into_owned())}
+	///A custom (non standard) string from the String INDEX
+	pub fn custom_string(&self, sid: u16)->Option<&[u8]>{
+		self.string_index.get(sid.checked_sub(Self::STANDARD_STRING_COUNT)? as usize)
+	}
+	///The transform from charstring units to font units as [a, b, c, d, e, f]
+	pub fn font_matrix(&self)->[f64; 6]{match self.top_dict.get(CffDict::FONT_MATRIX){
+		Some([a, b, c, d, e, f]) => [*a, *b, *c, *d, *e, *f],
+		_ => [0.001, 0.0, 0.0, 0.001, 0.0, 0.0],
+	}}
+	///The private DICT that applies to `glyph_id`
+	pub fn private_for_glyph(&self, glyph_id: u16)->Option<&CffPrivate>{match &self.fd_select{
+		Some(fd_select) => self.fd_array.get(fd_select.font_dict_index(glyph_id)?)?.private.as_ref(),
+		None => self.private.as_ref(),
+	}}
+
+	///Runs the glyph's charstring giving the outline and advance width
+	pub fn get_glyph(&self, glyph_id: u16)->Result<(Outline, f32), FromFileErr<(),()>>{
+		self.get_glyph_inner(glyph_id, true)
+	}
+	fn get_glyph_inner(&self, glyph_id: u16, allow_seac: bool)->Result<(Outline, f32), FromFileErr<(),()>>{
+		let charstring = match self.char_strings.get(glyph_id as usize){
+			Some(c) => c,
+			None => return Err(FromFileErr::InvalidData(())),
+		};
+		let private = self.private_for_glyph(glyph_id);
+		let mut interpreter = CharstringInterpreter::new(
+			&self.global_subr_index,
+			private.and_then(|p|p.local_subrs.as_ref()),
+		);
+		unwrap_or_ret!(interpreter.run(charstring, 0));
+		let width = match interpreter.width{
+			Some(w) => w + private.map_or(0.0, |p|p.nominal_width_x()),
+			None => private.map_or(0.0, |p|p.default_width_x()),
+		};
+		let mut outline = interpreter.finish();
+		if let Some(seac) = interpreter.seac{
+			// the components of an accented character can't themselves be accented characters
+			if !allow_seac{return Err(FromFileErr::InvalidData(()));}
+			// accented characters built from two glyphs picked using the standard encoding
+			let find = |code: f32|{
+				let sid = *STANDARD_ENCODING.get(code as usize)?;
+				if sid == 0{return None;}
+				self.charset.glyph_id(sid, self.num_glyphs())
+			};
+			let (base, accent) = match (find(seac.base_char), find(seac.accent_char)){
+				(Some(b), Some(a)) => (b, a),
+				_ => return Err(FromFileErr::InvalidData(())),
+			};
+			outline = unwrap_or_ret!(self.get_glyph_inner(base, false)).0;
+			let mut accent = unwrap_or_ret!(self.get_glyph_inner(accent, false)).0;
+			accent.transform(&Transform::translate(seac.adx, seac.ady));
+			outline.extend(&accent);
+		}
+		Ok((outline, width))
+	}
+	///The glyph's outline in charstring units (which are font units unless the font matrix says otherwise)
+	pub fn get_outline(&self, glyph_id: u16)->Result<Outline, FromFileErr<(),()>>{
+		Ok(unwrap_or_ret!(self.get_glyph(glyph_id)).0)
+	}
+	///The advance width encoded in the glyph's charstring
+	pub fn get_advance_width(&self, glyph_id: u16)->Result<f32, FromFileErr<(),()>>{
+		Ok(unwrap_or_ret!(self.get_glyph(glyph_id)).1)
+	}
+}
+
+#[derive(Debug,FromFile)]
+pub struct Cff2Header{
+	///Format major version (set to 2)
+	pub major_version: u8,
+	///Format minor version (set to 0)
+	pub minor_version: u8,
+	///Header size (bytes)
+	pub header_size: u8,
+	///Length of Top DICT structure in bytes
+	pub top_dict_length: u16,
+}
+
+///The `CFF2` table containing a single, possibly variable, font with CFF2 charstrings
+#[derive(Debug)]
+pub struct Cff2Table{
+	pub header: Cff2Header,
+	pub top_dict: CffDict,
+	pub global_subr_index: CffIndex,
+	pub char_strings: CffIndex,
+	pub variation_store: Option<ItemVariationStore>,
+	///The font DICTs, every CFF2 font has at least one
+	pub fd_array: Box<[CffFontDict]>,
+	///Which font DICT applies to each glyph, if absent every glyph uses the first one
+	pub fd_select: Option<FdSelect>,
+}
+impl_from_file!(Cff2Table, (), (), f, {
+	let start = f.stream_position().unwrap();
+	let header = unwrap_or_ret!(Cff2Header::from_file(f));
+	if header.major_version != 2{return Err(FromFileErr::InvalidData(()));}
+	if f.seek(SeekFrom::Start(start + header.header_size as u64)).is_err(){return Err(FromFileErr::EOF);}
+	let top_dict_data: Box<[u8]> = unwrap_or_ret!(array_from_file(f, header.top_dict_length as usize));
+	let top_dict = unwrap_or_ret!(CffDict::parse(&top_dict_data));
+	let global_subr_index = unwrap_or_ret!(CffIndex::from_file_cff2(f));
+
+	let char_strings = match top_dict.get(CffDict::CHAR_STRINGS){
+		Some([offset]) => {
+			if f.seek(SeekFrom::Start(unwrap_or_ret!(dict_offset(start, *offset)))).is_err(){return Err(FromFileErr::EOF);}
+			unwrap_or_ret!(CffIndex::from_file_cff2(f))
+		},
+		_ => return Err(FromFileErr::InvalidData(())),
+	};
+	let variation_store = match top_dict.get(CffDict::VSTORE){
+		Some([offset]) => {
+			// the store is preceded by its length
+			if f.seek(SeekFrom::Start(unwrap_or_ret!(dict_offset(start + 2, *offset)))).is_err(){return Err(FromFileErr::EOF);}
+			Some(unwrap_or_ret!(ItemVariationStore::from_file(f)))
+		},
+		_ => None,
+	};
+	let mut fd_array = Vec::new();
+	match top_dict.get(CffDict::FD_ARRAY){
+		Some([offset]) => {
+			if f.seek(SeekFrom::Start(unwrap_or_ret!(dict_offset(start, *offset)))).is_err(){return Err(FromFileErr::EOF);}
+			let font_dicts = unwrap_or_ret!(CffIndex::from_file_cff2(f));
+			for i in 0..font_dicts.len(){
+				let dict = unwrap_or_ret!(CffDict::parse(font_dicts.get(i).unwrap()));
+				let private = unwrap_or_ret!(CffPrivate::from_file_with_range(
+					f, start, dict.get(CffDict::PRIVATE), true, variation_store.as_ref()
+				));
+				fd_array.push(CffFontDict{dict, private});
+			}
+		},
+		_ => return Err(FromFileErr::InvalidData(())),
+	}
+	let fd_select = match top_dict.get(CffDict::FD_SELECT){
+		Some([offset]) => {
+			if f.seek(SeekFrom::Start(unwrap_or_ret!(dict_offset(start, *offset)))).is_err(){return Err(FromFileErr::EOF);}
+			Some(unwrap_or_ret!(FdSelect::from_file_with_glyphs(f, char_strings.len())))
+		},
+		_ => None,
+	};
+	Ok(Self{
+		header,
+		top_dict,
+		global_subr_index,
+		char_strings,
+		variation_store,
+		fd_array: fd_array.into(),
+		fd_select,
+	})
+});
+impl Cff2Table{
+	pub fn num_glyphs(&self)->u16{self.char_strings.len() as u16}
+	///The transform from charstring units to font units as [a, b, c, d, e, f]
+	pub fn font_matrix(&self)->[f64; 6]{match self.top_dict.get(CffDict::FONT_MATRIX){
+		Some([a, b, c, d, e, f]) => [*a, *b, *c, *d, *e, *f],
+		_ => [0.001, 0.0, 0.0, 0.001, 0.0, 0.0],
+	}}
+	///The private DICT that applies to `glyph_id`
+	pub fn private_for_glyph(&self, glyph_id: u16)->Option<&CffPrivate>{
+		let fd = match &self.fd_select{
+			Some(fd_select) => fd_select.font_dict_index(glyph_id)?,
+			None => 0,
+		};
+		self.fd_array.get(fd)?.private.as_ref()
+	}
+	///The glyph's outline at the normalized variation coordinates `coords` (one per axis in `fvar` order, an empty slice gives the default instance)
+	pub fn get_outline(&self, glyph_id: u16, coords: &[f32])->Result<Outline, FromFileErr<(),()>>{
+		let charstring = match self.char_strings.get(glyph_id as usize){
+			Some(c) => c,
+			None => return Err(FromFileErr::InvalidData(())),
+		};
+		let private = self.private_for_glyph(glyph_id);
+		let vsindex = private.map_or(0.0, |p|p.dict.get_number(CffDict::VSINDEX, 0.0)) as u16;
+		let mut interpreter = CharstringInterpreter::new_cff2(
+			&self.global_subr_index,
+			private.and_then(|p|p.local_subrs.as_ref()),
+			self.variation_store.as_ref(),
+			coords,
+			vsindex,
+		);
+		unwrap_or_ret!(interpreter.run(charstring, 0));
+		Ok(interpreter.finish())
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Seac{
+	adx: f32,
+	ady: f32,
+	base_char: f32,
+	accent_char: f32,
+}
+
+///The SID of the glyph for each character code in the Standard Encoding
+const STANDARD_ENCODING: [u16; 256] = {
+	let mut rv = [0u16; 256];
+	let mut i = 32;
+	while i < 127{rv[i] = i as u16 - 31; i += 1;}
+	let mut i = 161;
+	while i < 176{rv[i] = i as u16 - 65; i += 1;}
+	let high: [(usize, u16); 39] = [
+		(177, 111), (178, 112), (179, 113), (180, 114), (182, 115), (183, 116), (184, 117), (185, 118),
+		(186, 119), (187, 120), (188, 121), (189, 122), (191, 123), (193, 124), (194, 125), (195, 126),
+		(196, 127), (197, 128), (198, 129), (199, 130), (200, 131), (202, 132), (203, 133), (205, 134),
+		(206, 135), (207, 136), (208, 137), (225, 138), (227, 139), (232, 140), (233, 141), (234, 142),
+		(235, 143), (241, 144), (245, 145), (248, 146), (249, 147), (250, 148), (251, 149),
+	];
+	let mut i = 0;
+	while i < high.len(){rv[high[i].0] = high[i].1; i += 1;}
+	rv
+};
+
+///Executes Type 2 charstrings building up an outline
+pub(crate) struct CharstringInterpreter<'a>{
+	global_subrs: &'a CffIndex,
+	local_subrs: Option<&'a CffIndex>,
+	stack: Vec<f32>,
+	transient: [f32; 32],
+	outline: Outline,
+	pos: Point,
+	open: bool,
+	num_stems: usize,
+	seen_stack_clearing: bool,
+	width: Option<f32>,
+	seac: Option<Seac>,
+	random_seed: u32,
+	max_stack: usize,
+	cff2: bool,
+	variation_store: Option<&'a ItemVariationStore>,
+	coords: &'a [f32],
+	vsindex: u16,
+	scalars: Option<Vec<f32>>,
+	///How many more operators and operands may be run, subroutine calls can fan out so depth alone doesn't bound the work
+	budget: usize,
+}
+impl<'a> CharstringInterpreter<'a>{
+	pub(crate) const MAX_SUBR_DEPTH: usize = 10;
+	///The most operators and operands run for one glyph
+	pub(crate) const MAX_OPERATIONS: usize = 1_000_000;
+
+	pub(crate) fn new(global_subrs: &'a CffIndex, local_subrs: Option<&'a CffIndex>)->Self{Self{
+		global_subrs,
+		local_subrs,
+		stack: Vec::new(),
+		transient: [0.0; 32],
+		outline: Outline::new(),
+		pos: Point::default(),
+		open: false,
+		num_stems: 0,
+		seen_stack_clearing: false,
+		width: None,
+		seac: None,
+		random_seed: 1,
+		max_stack: 48,
+		cff2: false,
+		variation_store: None,
+		coords: &[],
+		vsindex: 0,
+		scalars: None,
+		budget: Self::MAX_OPERATIONS,
+	}}
+	///An interpreter for CFF2 charstrings which have no width and may use `blend` with the normalized coordinates `coords`
+	pub(crate) fn new_cff2(
+		global_subrs: &'a CffIndex,
+		local_subrs: Option<&'a CffIndex>,
+		variation_store: Option<&'a ItemVariationStore>,
+		coords: &'a [f32],
+		vsindex: u16,
+	)->Self{Self{
+		seen_stack_clearing: true,
+		max_stack: 513,
+		cff2: true,
+		variation_store,
+		coords,
+		vsindex,
+		..Self::new(global_subrs, local_subrs)
+	}}
+	pub(crate) fn finish(&mut self)->Outline{
+		self.close_path();
+		std::mem::take(&mut self.outline)
+	}
+	fn close_path(&mut self){
+		if self.open{self.outline.close();}
+		self.open = false;
+	}
+	fn move_to(&mut self, dx: f32, dy: f32){
+		self.close_path();
+		self.pos = Point::new(self.pos.x + dx, self.pos.y + dy);
+		self.outline.move_to(self.pos);
+		self.open = true;
+	}
+	fn line_to(&mut self, dx: f32, dy: f32){
+		self.pos = Point::new(self.pos.x + dx, self.pos.y + dy);
+		self.outline.line_to(self.pos);
+	}
+	fn curve_to(&mut self, dx1: f32, dy1: f32, dx2: f32, dy2: f32, dx3: f32, dy3: f32){
+		let c0 = Point::new(self.pos.x + dx1, self.pos.y + dy1);
+		let c1 = Point::new(c0.x + dx2, c0.y + dy2);
+		self.pos = Point::new(c1.x + dx3, c1.y + dy3);
+		self.outline.curve_to(c0, c1, self.pos);
+	}
+	fn pop(&mut self)->Result<f32, FromFileErr<(),()>>{
+		self.stack.pop().ok_or(FromFileErr::InvalidData(()))
+	}
+	///Takes the width from the bottom of the stack if the first stack clearing operator has an extra argument
+	fn take_width(&mut self, has_extra: bool){
+		if !self.seen_stack_clearing && has_extra && !self.stack.is_empty(){
+			self.width = Some(self.stack.remove(0));
+		}
+		self.seen_stack_clearing = true;
+	}
+	fn arg(&self, i: usize)->Result<f32, FromFileErr<(),()>>{
+		self.stack.get(i).copied().ok_or(FromFileErr::InvalidData(()))
+	}
+	///Applies the `blend` operator: each of the n default values on the stack have their n*k deltas added weighted by the region scalars
+	fn blend(&mut self)->Result<(), FromFileErr<(),()>>{
+		let n = unwrap_or_ret!(self.pop()) as usize;
+		if self.scalars.is_none(){
+			self.scalars = Some(match self.variation_store{
+				Some(store) => match store.scalars(self.vsindex, self.coords){
+					Some(s) => s,
+					None => return Err(FromFileErr::InvalidData(())),
+				},
+				None => Vec::new(),
+			});
+		}
+		let scalars = self.scalars.as_ref().unwrap();
+		let k = scalars.len();
+		let start = match n.checked_mul(k + 1).and_then(|c|self.stack.len().checked_sub(c)){
+			Some(s) => s,
+			None => return Err(FromFileErr::InvalidData(())),
+		};
+		for i in 0..n{
+			let deltas = &self.stack[start + n + i * k..start + n + (i + 1) * k];
+			let delta: f32 = deltas.iter().zip(scalars.iter()).map(|(d, s)|d * s).sum();
+			self.stack[start + i] += delta;
+		}
+		self.stack.truncate(start + n);
+		Ok(())
+	}
+
+	///Runs `code` returning `true` once the end of the glyph has been reached
+	pub(crate) fn run(&mut self, code: &[u8], depth: usize)->Result<bool, FromFileErr<(),()>>{
+		if depth > Self::MAX_SUBR_DEPTH{return Err(FromFileErr::InvalidData(()));}
+		let mut i = 0;
+		let byte = |i: usize|code.get(i).copied().ok_or(FromFileErr::InvalidData(()));
+		while i < code.len(){
+			if self.budget == 0{return Err(FromFileErr::InvalidData(()));}
+			self.budget -= 1;
+			let b0 = code[i];
+			i += 1;
+			match b0{
+				// numbers
+				28 => {
+					let v = i16::from_be_bytes([unwrap_or_ret!(byte(i)), unwrap_or_ret!(byte(i + 1))]);
+					i += 2;
+					self.stack.push(v as f32);
+				},
+				32..=246 => self.stack.push(b0 as f32 - 139.0),
+				247..=250 => {
+					self.stack.push((b0 as f32 - 247.0) * 256.0 + unwrap_or_ret!(byte(i)) as f32 + 108.0);
+					i += 1;
+				},
+				251..=254 => {
+					self.stack.push(-(b0 as f32 - 251.0) * 256.0 - unwrap_or_ret!(byte(i)) as f32 - 108.0);
+					i += 1;
+				},
+				255 => {
+					let v = i32::from_be_bytes([
+						unwrap_or_ret!(byte(i)), unwrap_or_ret!(byte(i + 1)), unwrap_or_ret!(byte(i + 2)), unwrap_or_ret!(byte(i + 3)),
+					]);
+					i += 4;
+					self.stack.push(v as f32 / 65536.0);
+				},
+				// hstem, vstem, hstemhm, vstemhm
+				1 | 3 | 18 | 23 => {
+					self.take_width(self.stack.len() % 2 == 1);
+					self.num_stems += self.stack.len() / 2;
+					self.stack.clear();
+				},
+				// hintmask, cntrmask
+				19 | 20 => {
+					self.take_width(self.stack.len() % 2 == 1);
+					// any arguments are an implied vstem
+					self.num_stems += self.stack.len() / 2;
+					self.stack.clear();
+					i += self.num_stems.div_ceil(8);
+				},
+				// rmoveto
+				21 => {
+					self.take_width(self.stack.len() > 2);
+					self.move_to(unwrap_or_ret!(self.arg(0)), unwrap_or_ret!(self.arg(1)));
+					self.stack.clear();
+				},
+				// hmoveto
+				22 => {
+					self.take_width(self.stack.len() > 1);
+					self.move_to(unwrap_or_ret!(self.arg(0)), 0.0);
+					self.stack.clear();
+				},
+				// vmoveto
+				4 => {
+					self.take_width(self.stack.len() > 1);
+					self.move_to(0.0, unwrap_or_ret!(self.arg(0)));
+					self.stack.clear();
+				},
+				// rlineto
+				5 => {
+					if self.stack.len() < 2{return Err(FromFileErr::InvalidData(()));}
+					for j in (0..self.stack.len() - 1).step_by(2){self.line_to(self.stack[j], self.stack[j + 1]);}
+					self.stack.clear();
+				},
+				// hlineto, vlineto
+				6 | 7 => {
+					if self.stack.is_empty(){return Err(FromFileErr::InvalidData(()));}
+					let mut horizontal = b0 == 6;
+					for j in 0..self.stack.len(){
+						let d = self.stack[j];
+						if horizontal{self.line_to(d, 0.0)}else{self.line_to(0.0, d)}
+						horizontal = !horizontal;
+					}
+					self.stack.clear();
+				},
+				// rrcurveto
+				8 => {
+					if self.stack.len() < 6{return Err(FromFileErr::InvalidData(()));}
+					for j in (0..self.stack.len() - 5).step_by(6){
+						let s = &self.stack;
+						let (a, b, c, d, e, g) = (s[j], s[j + 1], s[j + 2], s[j + 3], s[j + 4], s[j + 5]);
+						self.curve_to(a, b, c, d, e, g);
+					}
+					self.stack.clear();
+				},
+				// rcurveline
+				24 => {
+					if self.stack.len() < 8{return Err(FromFileErr::InvalidData(()));}
+					let curves = (self.stack.len() - 2) / 6;
+					for j in 0..curves{
+						let s = &self.stack[j * 6..];
+						let (a, b, c, d, e, g) = (s[0], s[1], s[2], s[3], s[4], s[5]);
+						self.curve_to(a, b, c, d, e, g);
+					}
+					let j = curves * 6;
+					self.line_to(self.stack[j], self.stack[j + 1]);
+					self.stack.clear();
+				},
+				// rlinecurve
+				25 => {
+					if self.stack.len() < 8{return Err(FromFileErr::InvalidData(()));}
+					let lines = (self.stack.len() - 6) / 2;
+					for j in 0..lines{self.line_to(self.stack[j * 2], self.stack[j * 2 + 1]);}
+					let s = &self.stack[lines * 2..];
+					let (a, b, c, d, e, g) = (s[0], s[1], s[2], s[3], s[4], s[5]);
+					self.curve_to(a, b, c, d, e, g);
+					self.stack.clear();
+				},
+				// vvcurveto
+				26 => {
+					let mut j = 0;
+					let mut dx1 = 0.0;
+					if self.stack.len() % 4 == 1{dx1 = self.stack[0]; j = 1;}
+					while j + 4 <= self.stack.len(){
+						let s = &self.stack[j..];
+						let (a, b, c, d) = (s[0], s[1], s[2], s[3]);
+						self.curve_to(dx1, a, b, c, 0.0, d);
+						dx1 = 0.0;
+						j += 4;
+					}
+					self.stack.clear();
+				},
+				// hhcurveto
+				27 => {
+					let mut j = 0;
+					let mut dy1 = 0.0;
+					if self.stack.len() % 4 == 1{dy1 = self.stack[0]; j = 1;}
+					while j + 4 <= self.stack.len(){
+						let s = &self.stack[j..];
+						let (a, b, c, d) = (s[0], s[1], s[2], s[3]);
+						self.curve_to(a, dy1, b, c, d, 0.0);
+						dy1 = 0.0;
+						j += 4;
+					}
+					self.stack.clear();
+				},
+				// vhcurveto, hvcurveto
+				30 | 31 => {
+					let mut horizontal = b0 == 31;
+					let mut j = 0;
+					let len = self.stack.len();
+					while j + 4 <= len{
+						let s = &self.stack[j..];
+						// the final curve may have an extra argument for the last coordinate
+						let last = if len - j == 5{s[4]}else{0.0};
+						let (a, b, c, d) = (s[0], s[1], s[2], s[3]);
+						if horizontal{self.curve_to(a, 0.0, b, c, last, d)}
+						else{self.curve_to(0.0, a, b, c, d, last)}
+						horizontal = !horizontal;
+						j += 4;
+					}
+					self.stack.clear();
+				},
+				// callsubr, callgsubr
+				10 | 29 => {
+					let subrs = if b0 == 10{match self.local_subrs{
+						Some(s) => s,
+						None => return Err(FromFileErr::InvalidData(())),
+					}}else{self.global_subrs};
+					let index = (unwrap_or_ret!(self.pop()) as i32).checked_add(subrs.subr_bias());
+					let subr = match index.and_then(|i|usize::try_from(i).ok()).and_then(|i|subrs.get(i)){
+						Some(s) => s,
+						None => return Err(FromFileErr::InvalidData(())),
+					};
+					if unwrap_or_ret!(self.run(subr, depth + 1)){return Ok(true);}
+				},
+				// return
+				11 => return Ok(false),
+				// endchar
+				14 => {
+					self.take_width(self.stack.len() == 1 || self.stack.len() == 5);
+					if self.stack.len() == 4{self.seac = Some(Seac{
+						adx: self.stack[0],
+						ady: self.stack[1],
+						base_char: self.stack[2],
+						accent_char: self.stack[3],
+					});}
+					self.stack.clear();
+					self.close_path();
+					return Ok(true);
+				},
+				12 => {
+					let op = unwrap_or_ret!(byte(i));
+					i += 1;
+					unwrap_or_ret!(self.escape_operator(op));
+				},
+				// vsindex
+				15 if self.cff2 => {
+					self.vsindex = unwrap_or_ret!(self.pop()) as u16;
+					self.scalars = None;
+					self.stack.clear();
+				},
+				// blend
+				16 if self.cff2 => unwrap_or_ret!(self.blend()),
+				_ => return Err(FromFileErr::InvalidData(())),
+			}
+			if self.stack.len() > self.max_stack{return Err(FromFileErr::InvalidData(()));}
+		}
+		Ok(false)
+	}
+
+	fn escape_operator(&mut self, op: u8)->Result<(), FromFileErr<(),()>>{
+		match op{
+			// and, or
+			3 | 4 => {
+				let b = unwrap_or_ret!(self.pop()) != 0.0;
+				let a = unwrap_or_ret!(self.pop()) != 0.0;
+				self.stack.push(if op == 3{a && b}else{a || b} as u8 as f32);
+			},
+			// not
+			5 => {
+				let a = unwrap_or_ret!(self.pop());
+				self.stack.push((a == 0.0) as u8 as f32);
+			},
+			// abs
+			9 => {
+				let a = unwrap_or_ret!(self.pop());
+				self.stack.push(a.abs());
+			},
+			// add, sub, div, mul
+			10 | 11 | 12 | 24 => {
+				let b = unwrap_or_ret!(self.pop());
+				let a = unwrap_or_ret!(self.pop());
+				self.stack.push(match op{10 => a + b, 11 => a - b, 12 => a / b, _ => a * b});
+			},
+			// neg
+			14 => {
+				let a = unwrap_or_ret!(self.pop());
+				self.stack.push(-a);
+			},
+			// eq
+			15 => {
+				let b = unwrap_or_ret!(self.pop());
+				let a = unwrap_or_ret!(self.pop());
+				self.stack.push((a == b) as u8 as f32);
+			},
+			// drop
+			18 => {unwrap_or_ret!(self.pop());},
+			// put
+			20 => {
+				let i = unwrap_or_ret!(self.pop()) as usize;
+				let v = unwrap_or_ret!(self.pop());
+				match self.transient.get_mut(i){
+					Some(t) => *t = v,
+					None => return Err(FromFileErr::InvalidData(())),
+				}
+			},
+			// get
+			21 => {
+				let i = unwrap_or_ret!(self.pop()) as usize;
+				match self.transient.get(i){
+					Some(t) => self.stack.push(*t),
+					None => return Err(FromFileErr::InvalidData(())),
+				}
+			},
+			// ifelse
+			22 => {
+				let v2 = unwrap_or_ret!(self.pop());
+				let v1 = unwrap_or_ret!(self.pop());
+				let s2 = unwrap_or_ret!(self.pop());
+				let s1 = unwrap_or_ret!(self.pop());
+				self.stack.push(if v1 <= v2{s1}else{s2});
+			},
+			// random
+			23 => {
+				// a deterministic xorshift so outlines are reproducible
+				self.random_seed ^= self.random_seed << 13;
+				self.random_seed ^= self.random_seed >> 17;
+				self.random_seed ^= self.random_seed << 5;
+				self.stack.push((self.random_seed % 65535 + 1) as f32 / 65536.0);
+			},
+			// sqrt
+			26 => {
+				let a = unwrap_or_ret!(self.pop());
+				self.stack.push(a.sqrt());
+			},
+			// dup
+			27 => {
+				let a = unwrap_or_ret!(self.pop());
+				self.stack.push(a);
+				self.stack.push(a);
+			},
+			// exch
+			28 => {
+				let b = unwrap_or_ret!(self.pop());
+				let a = unwrap_or_ret!(self.pop());
+				self.stack.push(b);
+				self.stack.push(a);
+			},
+			// index
+			29 => {
+				let i = unwrap_or_ret!(self.pop());
+				let len = self.stack.len();
+				if len == 0{return Err(FromFileErr::InvalidData(()));}
+				// a negative index copies the top element
+				let i = if i < 0.0{0}else{i as usize};
+				if i >= len{return Err(FromFileErr::InvalidData(()));}
+				self.stack.push(self.stack[len - 1 - i]);
+			},
+			// roll
+			30 => {
+				let j = unwrap_or_ret!(self.pop()) as i32;
+				let n = unwrap_or_ret!(self.pop()) as usize;
+				if n > self.stack.len(){return Err(FromFileErr::InvalidData(()));}
+				if n > 0{
+					let start = self.stack.len() - n;
+					let shift = j.rem_euclid(n as i32) as usize;
+					self.stack[start..].rotate_right(shift);
+				}
+			},
+			// hflex
+			34 => {
+				if self.stack.len() != 7{return Err(FromFileErr::InvalidData(()));}
+				let s = self.stack.clone();
+				let start_y = self.pos.y;
+				self.curve_to(s[0], 0.0, s[1], s[2], s[3], 0.0);
+				self.curve_to(s[4], 0.0, s[5], start_y - self.pos.y, s[6], 0.0);
+				self.stack.clear();
+			},
+			// flex
+			35 => {
+				if self.stack.len() != 13{return Err(FromFileErr::InvalidData(()));}
+				let s = self.stack.clone();
+				self.curve_to(s[0], s[1], s[2], s[3], s[4], s[5]);
+				self.curve_to(s[6], s[7], s[8], s[9], s[10], s[11]);
+				self.stack.clear();
+			},
+			// hflex1
+			36 => {
+				if self.stack.len() != 9{return Err(FromFileErr::InvalidData(()));}
+				let s = self.stack.clone();
+				let start_y = self.pos.y;
+				self.curve_to(s[0], s[1], s[2], s[3], s[4], 0.0);
+				let dy = start_y - (self.pos.y + s[7]);
+				self.curve_to(s[5], 0.0, s[6], s[7], s[8], dy);
+				self.stack.clear();
+			},
+			// flex1
+			37 => {
+				if self.stack.len() != 11{return Err(FromFileErr::InvalidData(()));}
+				let s = self.stack.clone();
+				let start = self.pos;
+				let dx: f32 = s[0] + s[2] + s[4] + s[6] + s[8];
+				let dy: f32 = s[1] + s[3] + s[5] + s[7] + s[9];
+				self.curve_to(s[0], s[1], s[2], s[3], s[4], s[5]);
+				let (last_x, last_y) = if dx.abs() > dy.abs(){
+					(s[10], start.y - (self.pos.y + s[7] + s[9]))
+				}else{
+					(start.x - (self.pos.x + s[6] + s[8]), s[10])
+				};
+				self.curve_to(s[6], s[7], s[8], s[9], last_x, last_y);
+				self.stack.clear();
+			},
+			_ => return Err(FromFileErr::InvalidData(())),
+		}
+		Ok(())
+	}
+}
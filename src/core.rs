@@ -1,8 +1,10 @@
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{Read, Seek, SeekFrom, Write};
 
-use macros::FromFile;
+use macros::{FromFile, ToFile};
 
 use crate::char_sets::{MacOsRoman, Utf16, CharSetStr, Utf8, Utf16BMPOnly};
+use crate::cff::{CffTable, Cff2Table};
+use crate::outline::{ContourPoint, Outline, Point, Transform};
 
 #[derive(Debug)]
 pub enum FromFileErr<InvalidData,OtherType>{
@@ -58,7 +60,15 @@ impl_from_file!(u8, (), (), f, {
 	}
 });
 
-fn array_from_file<F, T, I, O>(f: &mut F, count: usize)->Result<Box<[T]>, FromFileErr<I, O>> where
+impl_from_file!(i8, (), (), f, {Ok(unwrap_or_ret!(u8::from_file(f)) as i8)});
+impl_from_file!(i16, (), (), f, {Ok(unwrap_or_ret!(u16::from_file(f)) as i16)});
+impl_from_file!(i32, (), (), f, {Ok(unwrap_or_ret!(u32::from_file(f)) as i32)});
+impl_from_file!(u64, (), (), f, {
+	Ok((unwrap_or_ret!(u32::from_file(f)) as u64) << 32 | unwrap_or_ret!(u32::from_file(f)) as u64)
+});
+impl_from_file!(i64, (), (), f, {Ok(unwrap_or_ret!(u64::from_file(f)) as i64)});
+
+pub(crate) fn array_from_file<F, T, I, O>(f: &mut F, count: usize)->Result<Box<[T]>, FromFileErr<I, O>> where
 	F: Read,
 	F: Seek,
 	T: FromFile<I, O>,
@@ -67,25 +77,189 @@ fn array_from_file<F, T, I, O>(f: &mut F, count: usize)->Result<Box<[T]>, FromFi
 	for _ in 0..count{buf.push(unwrap_or_ret!(T::from_file(f)))}
 	Ok(buf.into())
 }
+///The number of bytes from the current position to the end of `f`
+pub(crate) fn bytes_left<F>(f: &mut F)->u64 where F: Seek{
+	let Ok(pos) = f.stream_position() else{return 0};
+	let end = f.seek(SeekFrom::End(0)).unwrap_or(pos);
+	let _ = f.seek(SeekFrom::Start(pos));
+	end.saturating_sub(pos)
+}
+///Reads `count` items of `item_size` bytes, the count is rejected before anything is allocated if the items don't fit in `limit` bytes or in the rest of `f`
+pub(crate) fn bounded_array_from_file<F, T>(f: &mut F, count: u32, item_size: u64, limit: u64)->Result<Box<[T]>, FromFileErr<(),()>> where
+	F: Read,
+	F: Seek,
+	T: FromFile<(),()>,
+{
+	if count as u64 * item_size > limit.min(bytes_left(f)){return Err(FromFileErr::InvalidData(()));}
+	array_from_file(f, count as usize)
+}
+
+macro_rules! impl_to_file {($type: ty, $self: ident, $f: ident, $body: block) => {
+	impl ToFile for $type{
+		fn to_file<F>(&$self, $f: &mut F)->Result<(), std::io::Error> where
+			F: Write,
+			F: Seek
+		$body
+	}
+};}
+///Writes a value in the same layout `FromFile` reads it from
+pub trait ToFile{
+	fn to_file<F>(&self, f: &mut F)->Result<(), std::io::Error> where
+		F: Write,
+		F: Seek
+	;
+}
+macro_rules! impl_to_file_be_bytes {($($type: ty),*) => {$(
+	impl_to_file!($type, self, f, {f.write_all(&self.to_be_bytes())});
+)*};}
+impl_to_file_be_bytes!(u8, i8, u16, i16, u32, i32, u64, i64);
+
+fn invalid_data(message: &str)->std::io::Error{std::io::Error::new(std::io::ErrorKind::InvalidData, message)}
+
+///Writes `items` which are counted by a field holding `count`, the two have to agree for the output to be read back
+pub(crate) fn array_to_file<F, T>(f: &mut F, items: &[T], count: usize)->Result<(), std::io::Error> where
+	F: Write,
+	F: Seek,
+	T: ToFile,
+{
+	if items.len() != count{return Err(invalid_data("array length does not match its count"));}
+	for item in items{unwrap_or_ret!(item.to_file(f))}
+	Ok(())
+}
+///Writes zeros until the stream is `offset` bytes past `start`
+fn pad_to<F>(f: &mut F, start: u64, offset: u64)->Result<(), std::io::Error> where F: Write, F: Seek{
+	let pos = unwrap_or_ret!(f.stream_position());
+	if pos > start + offset{return Err(invalid_data("data overlaps an earlier part of the table"));}
+	f.write_all(&vec![0; (start + offset - pos) as usize])
+}
+
 #[derive(Debug,FromFile)]
 pub struct OTTF{
 	pub table_directory: TableDirectory,
 }
 
+impl OTTF{
+	///The record of the table with the tag `tag` if the font has one
+	pub fn get_table_record(&self, tag: &str)->Option<&TableRecord>{
+		self.table_directory.table_records.iter().find(|r|r.table_tag.data == tag)
+	}
+	///Reads the table with the tag `tag`, `None` if the font doesn't have it or the tag isn't supported
+	pub fn load_table<F>(&self, f: &mut F, tag: &str)->Result<Option<Table>, FromFileErr<(),()>> where F: Read, F: Seek{
+		match self.get_table_record(tag).map(|r|r.get_table(f)){
+			None | Some(Err(FromFileErr::Other(_))) => Ok(None),
+			Some(Ok(t)) => Ok(Some(t)),
+			Some(Err(FromFileErr::EOF)) => Err(FromFileErr::EOF),
+			Some(Err(FromFileErr::InvalidData(_))) => Err(FromFileErr::InvalidData(())),
+		}
+	}
+}
+
+///A TrueType Collection (`ttcf`) holding several fonts which can share tables, table offsets are from the beginning of the collection file
 #[derive(Debug)]
+pub struct FontCollection{
+	///Major version of the TTC Header, = 1 or 2.
+	pub major_version: u16,
+	///Minor version of the TTC Header, = 0.
+	pub minor_version: u16,
+	///Number of fonts in TTC
+	pub num_fonts: u32,
+	///Array of offsets to the TableDirectory for each font from the beginning of the file
+	pub table_directory_offsets: Box<[Offset32]>,
+	///Version (=2) Tag indicating that a DSIG table exists, 0x44534947 ('DSIG') (null if no signature)
+	pub dsig_tag: Option<u32>,
+	///Version (=2) The length (in bytes) of the DSIG table (null if no signature)
+	pub dsig_length: Option<u32>,
+	///Version (=2) The offset (in bytes) of the DSIG table from the beginning of the TTC file (null if no signature)
+	pub dsig_offset: Option<u32>,
+	fonts: Box<[OTTF]>,
+}
+impl FontCollection{
+	///The value of ttcTag
+	pub const TAG: u32 = 0x74746366;
+	const DSIG_TAG: u32 = 0x44534947;
+
+	///The font at each table directory offset
+	pub fn fonts(&self)->&[OTTF]{&self.fonts}
+	///The table records used by more than one font, with the index of each font using it
+	pub fn shared_tables(&self)->Vec<(&TableRecord, Vec<usize>)>{
+		let mut rv: Vec<(&TableRecord, Vec<usize>)> = Vec::new();
+		for (i, font) in self.fonts.iter().enumerate(){
+			for record in font.table_directory.table_records.iter(){
+				match rv.iter_mut().find(|(r, _)|r.table_tag.data == record.table_tag.data && r.offset == record.offset && r.length == record.length){
+					Some((_, users)) => users.push(i),
+					None => rv.push((record, vec![i])),
+				}
+			}
+		}
+		rv.retain(|(_, users)|users.len() > 1);
+		rv
+	}
+	///Reads the signature of the whole collection, `None` if there isn't one
+	pub fn load_dsig<F>(&self, f: &mut F)->Result<Option<DSIGTable>, FromFileErr<(),()>> where F: Read, F: Seek{
+		match (self.dsig_tag, self.dsig_offset){
+			(Some(Self::DSIG_TAG), Some(offset)) if offset != 0 => {
+				if f.seek(SeekFrom::Start(offset as u64)).is_err(){return Err(FromFileErr::EOF);}
+				Ok(Some(unwrap_or_ret!(DSIGTable::from_file(f))))
+			},
+			_ => Ok(None),
+		}
+	}
+}
+impl_from_file!(FontCollection, (), (), f, {
+	val_or_ret!(unwrap_or_ret!(u32::from_file(f)), |tag|tag == Self::TAG, ());
+	let major_version = unwrap_or_ret!(u16::from_file(f));
+	let minor_version = unwrap_or_ret!(u16::from_file(f));
+	let num_fonts = unwrap_or_ret!(u32::from_file(f));
+	let table_directory_offsets: Box<[Offset32]> = unwrap_or_ret!(bounded_array_from_file(f, num_fonts, 4, u64::MAX));
+	macro_rules! v2_field {() => {if major_version >= 2{Some(unwrap_or_ret!(u32::from_file(f)))}else{None}};}
+	let dsig_tag = v2_field!();
+	let dsig_length = v2_field!();
+	let dsig_offset = v2_field!();
+	let file_length = f.stream_position().unwrap_or(0) + bytes_left(f);
+	// directories don't overlap so together they fit in the file, otherwise one directory could be parsed for every offset
+	let mut directory_bytes = 0u64;
+	let mut fonts = Vec::with_capacity(table_directory_offsets.len());
+	for offset in table_directory_offsets.iter(){
+		if f.seek(SeekFrom::Start(*offset as u64)).is_err(){return Err(FromFileErr::EOF);}
+		let font = unwrap_or_ret!(OTTF::from_file(f));
+		directory_bytes += TableDirectory::HEADER_SIZE + font.table_directory.num_tables as u64 * TableRecord::SIZE;
+		if directory_bytes > file_length{return Err(FromFileErr::InvalidData(()));}
+		fonts.push(font);
+	}
+	Ok(Self{
+		major_version,
+		minor_version,
+		num_fonts,
+		table_directory_offsets,
+		dsig_tag,
+		dsig_length,
+		dsig_offset,
+		fonts: fonts.into(),
+	})
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SFNTVer{
 	TrueType,
 	CFF,
 	Unknown(u32),
 }
-impl SFNTVer{fn from_u32(v: u32)->Self{match v{
-	0x00010000 => Self::TrueType,
-	0x4F54544F => Self::CFF,
-	v => Self::Unknown(v),
-}}}
+impl SFNTVer{
+	pub(crate) fn from_u32(v: u32)->Self{match v{
+		0x00010000 => Self::TrueType,
+		0x4F54544F => Self::CFF,
+		v => Self::Unknown(v),
+	}}
+	pub(crate) fn to_u32(self)->u32{match self{
+		Self::TrueType => 0x00010000,
+		Self::CFF => 0x4F54544F,
+		Self::Unknown(v) => v,
+	}}
+}
 impl_from_file!(SFNTVer, (), (), f, {Ok(SFNTVer::from_u32(unwrap_or_ret!(u32::from_file(f))))});
+impl_to_file!(SFNTVer, self, f, {self.to_u32().to_file(f)});
 
-#[derive(Debug,FromFile)]
+#[derive(Debug,FromFile,ToFile)]
 pub struct TableDirectory{
 	/// 0x00010000 or 0x4F54544F ('OTTO')
 	pub sfnt_version: SFNTVer,
@@ -101,8 +275,20 @@ pub struct TableDirectory{
 	#[from_file_count(num_tables)]
 	pub table_records: Box<[TableRecord]>,
 }
+impl TableDirectory{
+	///The size of the directory before its table records
+	pub const HEADER_SIZE: u64 = 12;
+	///The `search_range`, `entry_selector` and `range_shift` a directory of `num_tables` tables has
+	pub fn search_params(num_tables: u16)->(u16, u16, u16){
+		if num_tables == 0{return (0, 0, 0);}
+		let entry_selector = 15 - num_tables.leading_zeros() as u16;
+		let search_range = (1u32 << entry_selector) * 16;
+		let range_shift = num_tables as u32 * 16 - search_range;
+		(search_range as u16, entry_selector, range_shift as u16)
+	}
+}
 
-#[derive(Debug,FromFile)]
+#[derive(Debug,FromFile,ToFile)]
 pub struct TableRecord{
 	///Table identifier.
 	pub table_tag: Tag,
@@ -114,8 +300,8 @@ pub struct TableRecord{
 	pub length: u32,
 }
 
-macro_rules! get_table{($table: expr, $table_type: ty, $f: ident) => {
-	Ok($table(match <$table_type>::from_file($f){
+macro_rules! get_table{($table: expr, $read: expr) => {
+	Ok($table(match $read{
 		Ok(v) => v,
 		Err(e) => match e{
 			FromFileErr::EOF => return Err(FromFileErr::EOF),
@@ -123,16 +309,41 @@ macro_rules! get_table{($table: expr, $table_type: ty, $f: ident) => {
 			FromFileErr::Other(_) => unreachable!(),
 		},
 	}).into())
+}; ($table: expr, $table_type: ty, $f: ident) => {
+	get_table!($table, <$table_type>::from_file($f))
 };}
 impl TableRecord{
-	pub fn get_table<T>(&mut self, f: &mut T)->Result<Table, FromFileErr<(),Box<[u8]>>> where T:Read, T:Seek{
+	///The size of a record in the table directory
+	pub const SIZE: u64 = 16;
+	pub fn get_table<T>(&self, f: &mut T)->Result<Table, FromFileErr<(),Box<[u8]>>> where T:Read, T:Seek{
 		let cur = f.seek(SeekFrom::Current(0));
 		if f.seek(SeekFrom::Start(self.offset as u64)).is_err()
 		{return Err(FromFileErr::EOF);}
 
 		let rv = match self.table_tag.data.as_str(){
 			"DSIG" => get_table!(Table::DSIG, DSIGTable, f),
-			"name" => get_table!(Table::Name, NameTable, f),
+			"name" => get_table!(Table::Name, NameTable::from_file_with_length(f, self.length)),
+			"head" => get_table!(Table::Head, HeadTable, f),
+			"cmap" => get_table!(Table::Cmap, CmapTable, f),
+			"loca" => Ok(Table::Loca(LocaTable{table_absolute: self.offset as u64, length: self.length})),
+			"glyf" => Ok(Table::Glyf(GlyfTable{table_absolute: self.offset as u64, length: self.length})),
+			"CFF " => get_table!(|t|Table::CFF(Box::new(t)), CffTable, f),
+			"CFF2" => get_table!(|t|Table::CFF2(Box::new(t)), Cff2Table, f),
+			"hhea" => get_table!(Table::Hhea, HheaTable, f),
+			"hmtx" => Ok(Table::Hmtx(HmtxTable{table_absolute: self.offset as u64, length: self.length})),
+			"vhea" => get_table!(Table::Vhea, VheaTable, f),
+			"vmtx" => Ok(Table::Vmtx(VmtxTable{table_absolute: self.offset as u64, length: self.length})),
+			"VORG" => get_table!(Table::VORG, VorgTable, f),
+			"maxp" => get_table!(Table::Maxp, MaxpTable, f),
+			"OS/2" => get_table!(Table::OS2, Os2Table::from_file_with_length(f, self.length)),
+			"post" => get_table!(Table::Post, PostTable, f),
+			"GSUB" => get_table!(Table::GSUB, GsubTable::from_file_with_length(f, self.length)),
+			"GPOS" => get_table!(Table::GPOS, GposTable::from_file_with_length(f, self.length)),
+			"GDEF" => get_table!(Table::GDEF, GdefTable, f),
+			"kern" => get_table!(Table::Kern, KernTable, f),
+			"cvt " => get_table!(Table::Cvt, CvtTable::from_file_with_length(f, self.length)),
+			"fpgm" => get_table!(Table::Fpgm, FpgmTable::from_file_with_length(f, self.length)),
+			"prep" => get_table!(Table::Prep, PrepTable::from_file_with_length(f, self.length)),
 			_ => Err(FromFileErr::Other([].into())),
 		};
 		f.seek(SeekFrom::Start(cur.unwrap())).unwrap();
@@ -154,14 +365,55 @@ impl_from_file!(Tag, (), (), f, {
 		char::from(val_or_ret!(unwrap_or_ret!(u8::from_file(f)), in_range, ())),
 	])})
 });
+impl_to_file!(Tag, self, f, {
+	let data = self.data.as_bytes();
+	if data.len() != 4 || data.iter().any(|c|!(0x20..=0x7E).contains(c)){return Err(invalid_data("a tag must be 4 printable ASCII characters"));}
+	f.write_all(data)
+});
 
-type Offset32 = u32;
-type Offset16 = u16;
+pub type Offset32 = u32;
+pub type Offset16 = u16;
+///int16 that describes a quantity in font design units.
+pub type FWord = i16;
+///uint16 that describes a quantity in font design units.
+pub type UFWord = u16;
+///Date and time represented in number of seconds since 12:00 midnight, January 1, 1904, UTC.
+pub type LongDateTime = i64;
+
+///32-bit signed fixed-point number (16.16)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fixed(pub i32);
+impl Fixed{
+	pub fn to_f64(&self)->f64{self.0 as f64 / 65536.0}
+}
+impl_from_file!(Fixed, (), (), f, {Ok(Self(unwrap_or_ret!(i32::from_file(f))))});
+impl_to_file!(Fixed, self, f, {self.0.to_file(f)});
 
 #[derive(Debug)]
 pub enum Table{
 	Name(NameTable),
 	DSIG(DSIGTable),
+	Head(HeadTable),
+	Cmap(CmapTable),
+	Loca(LocaTable),
+	Glyf(GlyfTable),
+	CFF(Box<CffTable>),
+	CFF2(Box<Cff2Table>),
+	Hhea(HheaTable),
+	Hmtx(HmtxTable),
+	Vhea(VheaTable),
+	Vmtx(VmtxTable),
+	VORG(VorgTable),
+	Maxp(MaxpTable),
+	OS2(Os2Table),
+	Post(PostTable),
+	GSUB(GsubTable),
+	GPOS(GposTable),
+	GDEF(GdefTable),
+	Kern(KernTable),
+	Cvt(CvtTable),
+	Fpgm(FpgmTable),
+	Prep(PrepTable),
 }
 
 #[derive(Debug)]
@@ -179,35 +431,67 @@ pub struct NameTable{
 	pub lang_tag_count: Option<u16>,
 	///Version (=1) The language-tag records where langTagCount is the number of records.
 	pub lang_tag_record: Option<Box<[LangTagRecord]>>,
+	///The string storage up to the end of the last string any record points to
+	pub storage: Box<[u8]>,
 }
-impl_from_file!(NameTable, (), (), f, {
-	let start = f.seek(SeekFrom::Current(0)).unwrap();
-	let version = unwrap_or_ret!(u16::from_file(f));
-	let count = unwrap_or_ret!(u16::from_file(f));
-	let storage_offset = unwrap_or_ret!(Offset16::from_file(f));
-	let name_records = unwrap_or_ret!(array_from_file(f, count as usize));
-	let lang_tag_count = if version == 0{None}else{Some(unwrap_or_ret!(u16::from_file(f)))};
-	return Ok(Self{
-		version,
-		count,
-		storage_offset,
-		storage_absolute: start + (storage_offset as u64),
-		name_records,
-		lang_tag_count,
-		lang_tag_record: match lang_tag_count{
+impl_from_file!(NameTable, (), (), f, {Self::from_file_with_length(f, 0)});
+impl NameTable{
+	///Reads the table along with all of its storage up to the `length` given by its table record, so it can be written back out unchanged
+	pub fn from_file_with_length<F>(f: &mut F, length: u32)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		let start = f.stream_position().unwrap();
+		let version = unwrap_or_ret!(u16::from_file(f));
+		let count = unwrap_or_ret!(u16::from_file(f));
+		let storage_offset = unwrap_or_ret!(Offset16::from_file(f));
+		let name_records: Box<[NameRecord]> = unwrap_or_ret!(array_from_file(f, count as usize));
+		let lang_tag_count = if version == 0{None}else{Some(unwrap_or_ret!(u16::from_file(f)))};
+		let lang_tag_record: Option<Box<[LangTagRecord]>> = match lang_tag_count{
 			Some(count)=>{Some(unwrap_or_ret!(array_from_file(f, count as usize)))},
 			None=>None
+		};
+		// the storage runs to the end of the table, or to the end of the last string if that is further
+		let storage_len = name_records.iter().map(|r|r.string_offset as usize + r.length as usize)
+			.chain(lang_tag_record.iter().flat_map(|r|r.iter()).map(|r|r.lang_tag_offset as usize + r.length as usize))
+			.chain(std::iter::once((length as usize).saturating_sub(storage_offset as usize)))
+			.max().unwrap_or(0);
+		let storage_absolute = start + (storage_offset as u64);
+		if f.seek(SeekFrom::Start(storage_absolute)).is_err(){return Err(FromFileErr::EOF);}
+		Ok(Self{
+			version,
+			count,
+			storage_offset,
+			storage_absolute,
+			name_records,
+			lang_tag_count,
+			lang_tag_record,
+			storage: unwrap_or_ret!(bounded_array_from_file(f, storage_len as u32, 1, u64::MAX)),
+		})
+	}
+}
+impl_to_file!(NameTable, self, f, {
+	let start = unwrap_or_ret!(f.stream_position());
+	unwrap_or_ret!(self.version.to_file(f));
+	unwrap_or_ret!(self.count.to_file(f));
+	unwrap_or_ret!(self.storage_offset.to_file(f));
+	unwrap_or_ret!(array_to_file(f, &self.name_records, self.count as usize));
+	match (self.lang_tag_count, &self.lang_tag_record){
+		(Some(count), Some(records)) => {
+			unwrap_or_ret!(count.to_file(f));
+			unwrap_or_ret!(array_to_file(f, records, count as usize));
 		},
-	});
+		(None, None) => {},
+		_ => return Err(invalid_data("lang_tag_count and lang_tag_record must both be present or absent")),
+	}
+	unwrap_or_ret!(pad_to(f, start, self.storage_offset as u64));
+	f.write_all(&self.storage)
 });
-#[derive(Debug,FromFile)]
+#[derive(Debug,FromFile,ToFile)]
 pub struct LangTagRecord{
 	///Language-tag string length (in bytes)
 	pub length: u16,
 	///Language-tag string offset from start of storage area (in bytes).
 	pub lang_tag_offset: Offset16,
 }
-#[derive(Debug,FromFile)]
+#[derive(Debug,FromFile,ToFile)]
 pub struct NameRecord{
 	///Platform ID.
 	pub platform_id: u16,
@@ -305,7 +589,7 @@ impl NameRecord{
 	}
 }
 
-#[derive(Debug,FromFile)]
+#[derive(Debug)]
 pub struct DSIGTable{
 	///Version number of the DSIG table (0x00000001)
 	pub version: u32,
@@ -316,10 +600,41 @@ pub struct DSIGTable{
 	/// Bits 1-7: Reserved (Set to 0)
 	pub flags: u16,
 	///Array of signature records
-	#[from_file_count(num_signatures)]
 	pub signature_records: Box<[SignatureRecord]>,
+	///The signature block each signature record points to
+	pub signature_blocks: Box<[SignatureBlock]>,
 }
-#[derive(Debug,FromFile)]
+impl_from_file!(DSIGTable, (), (), f, {
+	let start = unwrap_or_ret!(f.stream_position().map_err(|_|FromFileErr::EOF));
+	let version = unwrap_or_ret!(u32::from_file(f));
+	let num_signatures = unwrap_or_ret!(u16::from_file(f));
+	let flags = unwrap_or_ret!(u16::from_file(f));
+	let signature_records: Box<[SignatureRecord]> = unwrap_or_ret!(array_from_file(f, num_signatures as usize));
+	let mut signature_blocks = Vec::with_capacity(signature_records.len());
+	for record in signature_records.iter(){
+		if f.seek(SeekFrom::Start(start + record.signature_block_offset as u64)).is_err(){return Err(FromFileErr::EOF);}
+		signature_blocks.push(unwrap_or_ret!(SignatureBlock::from_file(f)));
+	}
+	Ok(Self{version, num_signatures, flags, signature_records, signature_blocks: signature_blocks.into()})
+});
+impl_to_file!(DSIGTable, self, f, {
+	let start = unwrap_or_ret!(f.stream_position());
+	unwrap_or_ret!(self.version.to_file(f));
+	unwrap_or_ret!(self.num_signatures.to_file(f));
+	unwrap_or_ret!(self.flags.to_file(f));
+	unwrap_or_ret!(array_to_file(f, &self.signature_records, self.num_signatures as usize));
+	if self.signature_blocks.len() != self.signature_records.len(){return Err(invalid_data("every signature record needs a signature block"));}
+	// blocks are written in the order they are stored in, records may share a block
+	let mut blocks: Vec<(u32, &SignatureBlock)> = self.signature_records.iter().map(|r|r.signature_block_offset).zip(self.signature_blocks.iter()).collect();
+	blocks.sort_by_key(|(offset, _)|*offset);
+	blocks.dedup_by_key(|(offset, _)|*offset);
+	for (offset, block) in blocks{
+		unwrap_or_ret!(pad_to(f, start, offset as u64));
+		unwrap_or_ret!(block.to_file(f));
+	}
+	Ok(())
+});
+#[derive(Debug,FromFile,ToFile)]
 pub struct SignatureRecord{
 	///Format of the signature
 	pub format: u32,
@@ -328,14 +643,3960 @@ pub struct SignatureRecord{
 	///Offset to the signature block from the beginning of the table
 	pub signature_block_offset: Offset32
 }
+#[derive(Debug,FromFile,ToFile)]
+pub struct SignatureBlock{
+	///Reserved — must be zero.
+	pub reserved1: u16,
+	///Reserved — must be zero.
+	pub reserved2: u16,
+	///Length (in bytes) of the PKCS#7 packet in the signature field.
+	pub signature_length: u32,
+	///PKCS#7 packet
+	#[from_file_count(signature_length)]
+	pub signature: Box<[u8]>,
+}
 
-pub fn calc_table_checksum<T>(table: T, length: u32) -> u32 where T: Fn(usize) -> u32{
-	let mut sum = 0u32;
-	let endptr = ((length+3) & !3) / 4;
-	let mut i = 0;
-	while i < endptr as usize {
-		sum += table(i);
-		i+=1;
+#[derive(Debug,FromFile,ToFile)]
+pub struct HeadTable{
+	///Major version number of the font header table — set to 1.
+	pub major_version: u16,
+	///Minor version number of the font header table — set to 0.
+	pub minor_version: u16,
+	///Set by font manufacturer.
+	pub font_revision: Fixed,
+	///To compute: set it to 0, sum the entire font as uint32, then store 0xB1B0AFBA - sum.
+	/// If the font is used as a component in a font collection file, the value of this field will be invalidated by changes to the file structure and font table directory, and must be ignored.
+	pub checksum_adjustment: u32,
+	///Set to 0x5F0F3CF5.
+	pub magic_number: u32,
+	///Bit 0: Baseline for font at y=0.
+	/// Bit 1: Left sidebearing point at x=0 (relevant only for TrueType rasterizers)
+	/// Bit 2: Instructions may depend on point size.
+	/// Bit 3: Force ppem to integer values for all internal scaler math; may use fractional ppem sizes if this bit is clear.
+	/// Bit 4: Instructions may alter advance width (the advance widths might not scale linearly).
+	/// Bit 5-10: Not used in OpenType (Set to 0)
+	/// Bit 11: Font data is “lossless” as a result of having been subjected to optimizing transformation and/or compression.
+	/// Bit 12: Font converted (produce compatible metrics).
+	/// Bit 13: Font optimized for ClearType™.
+	/// Bit 14: Last Resort font.
+	/// Bit 15: Reserved, set to 0.
+	pub flags: u16,
+	///Set to a value from 16 to 16384. Any value in this range is valid. In fonts that have TrueType outlines, a power of 2 is recommended as this allows performance optimization in some rasterizers.
+	pub units_per_em: u16,
+	///Number of seconds since 12:00 midnight that started January 1st, 1904, in GMT/UTC time zone.
+	pub created: LongDateTime,
+	///Number of seconds since 12:00 midnight that started January 1st, 1904, in GMT/UTC time zone.
+	pub modified: LongDateTime,
+	///Minimum x coordinate across all glyph bounding boxes.
+	pub x_min: i16,
+	///Minimum y coordinate across all glyph bounding boxes.
+	pub y_min: i16,
+	///Maximum x coordinate across all glyph bounding boxes.
+	pub x_max: i16,
+	///Maximum y coordinate across all glyph bounding boxes.
+	pub y_max: i16,
+	///Bit 0: Bold (if set to 1);
+	/// Bit 1: Italic (if set to 1)
+	/// Bit 2: Underline (if set to 1)
+	/// Bit 3: Outline (if set to 1)
+	/// Bit 4: Shadow (if set to 1)
+	/// Bit 5: Condensed (if set to 1)
+	/// Bit 6: Extended (if set to 1)
+	/// Bits 7-15: Reserved (set to 0).
+	pub mac_style: u16,
+	///Smallest readable size in pixels.
+	pub lowest_rec_ppem: u16,
+	///Deprecated (Set to 2).
+	pub font_direction_hint: i16,
+	///0 for short offsets (Offset16), 1 for long (Offset32).
+	pub index_to_loc_format: i16,
+	///0 for current format.
+	pub glyph_data_format: i16,
+}
+impl HeadTable{
+	///The value `magic_number` must be set to
+	pub const MAGIC_NUMBER: u32 = 0x5F0F3CF5;
+	pub fn is_bold(&self)->bool{self.mac_style & 1 != 0}
+	pub fn is_italic(&self)->bool{self.mac_style & (1 << 1) != 0}
+	///whether the `loca` table uses 32 bit offsets
+	pub fn long_loca_offsets(&self)->bool{self.index_to_loc_format == 1}
+	///`created` as seconds since the unix epoch
+	pub fn created_unix(&self)->i64{self.created.wrapping_sub(Self::EPOCH_DIFFERENCE)}
+	///`modified` as seconds since the unix epoch
+	pub fn modified_unix(&self)->i64{self.modified.wrapping_sub(Self::EPOCH_DIFFERENCE)}
+	///seconds between 1904-01-01 and 1970-01-01
+	const EPOCH_DIFFERENCE: i64 = 2_082_844_800;
+}
+
+///24-bit unsigned integer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Uint24(pub u32);
+impl_from_file!(Uint24, (), (), f, {
+	Ok(Self((unwrap_or_ret!(u8::from_file(f)) as u32) << 16 | unwrap_or_ret!(u16::from_file(f)) as u32))
+});
+
+#[derive(Debug)]
+pub struct CmapTable{
+	///Table version number (0).
+	pub version: u16,
+	///Number of encoding tables that follow.
+	pub num_tables: u16,
+	///Encoding records where num_tables is the number of records.
+	pub encoding_records: Box<[EncodingRecord]>,
+	///The subtable pointed to by each encoding record (in the same order as `encoding_records`).
+	pub subtables: Box<[CmapSubtable]>,
+}
+impl_from_file!(CmapTable, (), (), f, {
+	let start = f.stream_position().unwrap();
+	let version = unwrap_or_ret!(u16::from_file(f));
+	let num_tables = unwrap_or_ret!(u16::from_file(f));
+	let encoding_records: Box<[EncodingRecord]> = unwrap_or_ret!(array_from_file(f, num_tables as usize));
+	let mut subtables = Vec::with_capacity(num_tables as usize);
+	for record in encoding_records.iter(){
+		if f.seek(SeekFrom::Start(start + record.subtable_offset as u64)).is_err()
+		{return Err(FromFileErr::EOF);}
+		subtables.push(unwrap_or_ret!(CmapSubtable::from_file(f)));
 	}
-	return sum;
+	Ok(Self{version, num_tables, encoding_records, subtables: subtables.into()})
+});
+impl CmapTable{
+	///Order in which (platform id, encoding id) pairs are preferred when mapping unicode characters
+	const UNICODE_PREFERENCE: [(u16, u16); 9] = [
+		(3, 10), (0, 6), (0, 4), (3, 1), (0, 3), (0, 2), (0, 1), (0, 0), (3, 0),
+	];
+	///The subtable best suited to mapping unicode code points (along with its encoding record)
+	pub fn unicode_subtable(&self)->Option<(&EncodingRecord, &CmapSubtable)>{
+		for (platform_id, encoding_id) in Self::UNICODE_PREFERENCE{
+			for (record, subtable) in self.encoding_records.iter().zip(self.subtables.iter()){
+				if record.platform_id == platform_id
+				&& record.encoding_id == encoding_id
+				&& !matches!(subtable, CmapSubtable::Format14(_))
+				{return Some((record, subtable));}
+			}
+		}
+		None
+	}
+	///The glyph id for `chr` or `None` if it is not mapped
+	pub fn char_to_glyph(&self, chr: char)->Option<u16>{
+		let (record, subtable) = self.unicode_subtable()?;
+		match subtable.glyph_id(chr as u32){
+			// symbol fonts map their characters into the private use area at 0xF000
+			None if record.platform_id == 3 && record.encoding_id == 0 && (chr as u32) < 0x100 =>
+				subtable.glyph_id(chr as u32 | 0xF000),
+			v => v,
+		}
+	}
+	///The glyph id for `chr` followed by the variation selector `selector` (using the format 14 subtable)
+	pub fn char_variant_to_glyph(&self, chr: char, selector: char)->Option<u16>{
+		for subtable in self.subtables.iter(){if let CmapSubtable::Format14(vs) = subtable{
+			return match vs.lookup(chr as u32, selector as u32){
+				VariationLookup::Default => self.char_to_glyph(chr),
+				VariationLookup::Glyph(g) => Some(g),
+				VariationLookup::NotFound => None,
+			};
+		}}
+		None
+	}
+	///Iterates over every (character, glyph id) pair in the preferred unicode subtable
+	pub fn mappings(&self)->impl Iterator<Item=(char, u16)> + '_{
+		self.unicode_subtable().into_iter().flat_map(|(_, s)|s.mappings()).filter_map(
+			|(c, g)|char::from_u32(c).map(|c|(c, g))
+		)
+	}
+}
+
+#[derive(Debug,FromFile)]
+pub struct EncodingRecord{
+	///Platform ID.
+	pub platform_id: u16,
+	///Platform-specific encoding ID.
+	pub encoding_id: u16,
+	///Byte offset from beginning of table to the subtable for this encoding.
+	pub subtable_offset: Offset32,
+}
+
+#[derive(Debug)]
+pub enum CmapSubtable{
+	Format0(CmapFormat0),
+	Format2(CmapFormat2),
+	Format4(CmapFormat4),
+	Format6(CmapFormat6),
+	Format8(CmapFormat8),
+	Format10(CmapFormat10),
+	Format12(CmapFormat12),
+	Format13(CmapFormat13),
+	Format14(CmapFormat14),
+	Unknown(u16),
+}
+impl_from_file!(CmapSubtable, (), (), f, {
+	let format = unwrap_or_ret!(u16::from_file(f));
+	f.seek(SeekFrom::Current(-2)).unwrap();
+	Ok(match format{
+		0 => Self::Format0(unwrap_or_ret!(CmapFormat0::from_file(f))),
+		2 => Self::Format2(unwrap_or_ret!(CmapFormat2::from_file(f))),
+		4 => Self::Format4(unwrap_or_ret!(CmapFormat4::from_file(f))),
+		6 => Self::Format6(unwrap_or_ret!(CmapFormat6::from_file(f))),
+		8 => Self::Format8(unwrap_or_ret!(CmapFormat8::from_file(f))),
+		10 => Self::Format10(unwrap_or_ret!(CmapFormat10::from_file(f))),
+		12 => Self::Format12(unwrap_or_ret!(CmapFormat12::from_file(f))),
+		13 => Self::Format13(unwrap_or_ret!(CmapFormat13::from_file(f))),
+		14 => Self::Format14(unwrap_or_ret!(CmapFormat14::from_file(f))),
+		v => Self::Unknown(v),
+	})
+});
+impl CmapSubtable{
+	///The language field of the subtable (only meaningful for Macintosh platform subtables)
+	pub fn language(&self)->Option<u32>{match self{
+		Self::Format0(s) => Some(s.language as u32),
+		Self::Format2(s) => Some(s.language as u32),
+		Self::Format4(s) => Some(s.language as u32),
+		Self::Format6(s) => Some(s.language as u32),
+		Self::Format8(s) => Some(s.language),
+		Self::Format10(s) => Some(s.language),
+		Self::Format12(s) => Some(s.language),
+		Self::Format13(s) => Some(s.language),
+		Self::Format14(_) | Self::Unknown(_) => None,
+	}}
+	///Maps a character code to a glyph id, `None` is returned for unmapped codes (those that map to glyph 0)
+	pub fn glyph_id(&self, code: u32)->Option<u16>{
+		let glyph = match self{
+			Self::Format0(s) => s.glyph_id_array.get(code as usize).map(|g|*g as u16),
+			Self::Format2(s) => s.glyph_id(code),
+			Self::Format4(s) => s.glyph_id(code),
+			Self::Format6(s) => code.checked_sub(s.first_code as u32)
+				.and_then(|i|s.glyph_id_array.get(i as usize)).copied(),
+			Self::Format8(s) => sequential_groups_lookup(&s.groups, code),
+			Self::Format10(s) => code.checked_sub(s.start_char_code)
+				.and_then(|i|s.glyph_id_array.get(i as usize)).copied(),
+			Self::Format12(s) => sequential_groups_lookup(&s.groups, code),
+			Self::Format13(s) => s.groups.iter()
+				.find(|g|g.start_char_code <= code && code <= g.end_char_code)
+				.and_then(|g|u16::try_from(g.glyph_id).ok()),
+			Self::Format14(_) | Self::Unknown(_) => None,
+		};
+		glyph.filter(|g|*g != 0)
+	}
+	///Ranges (inclusive) of character codes that might be mapped by this subtable
+	fn code_ranges(&self)->Vec<(u32, u32)>{match self{
+		Self::Format0(_) => vec![(0, 255)],
+		Self::Format2(s) => {
+			let mut rv = Vec::new();
+			for (high, key) in s.sub_header_keys.iter().enumerate(){
+				let header = &s.sub_headers[(key/8) as usize];
+				if header.entry_count == 0{continue;}
+				let last = header.first_code as u32 + header.entry_count as u32 - 1;
+				if *key == 0{
+					let high = high as u32;
+					if header.first_code as u32 <= high && high <= last{rv.push((high, high));}
+				}else{
+					let high = (high as u32) << 8;
+					rv.push((high | header.first_code as u32, high | last.min(0xFF)));
+				}
+			}
+			rv
+		},
+		Self::Format4(s) => s.start_code.iter().zip(s.end_code.iter())
+			.filter(|(start, end)|start <= end)
+			.map(|(start, end)|(*start as u32, *end as u32)).collect(),
+		Self::Format6(s) => if s.entry_count == 0{vec![]}else{
+			vec![(s.first_code as u32, s.first_code as u32 + s.entry_count as u32 - 1)]
+		},
+		Self::Format8(s) => s.groups.iter().map(|g|(g.start_char_code, g.end_char_code)).collect(),
+		Self::Format10(s) => if s.num_chars == 0{vec![]}else{
+			vec![(s.start_char_code, s.start_char_code.saturating_add(s.num_chars - 1))]
+		},
+		Self::Format12(s) => s.groups.iter().map(|g|(g.start_char_code, g.end_char_code)).collect(),
+		Self::Format13(s) => s.groups.iter().map(|g|(g.start_char_code, g.end_char_code)).collect(),
+		Self::Format14(_) | Self::Unknown(_) => vec![],
+	}}
+	///Iterates over every (character code, glyph id) pair mapped by this subtable
+	pub fn mappings(&self)->CmapIter<'_>{
+		let mut ranges = self.code_ranges();
+		ranges.sort();
+		CmapIter{subtable: self, ranges, range: 0, next: None}
+	}
+}
+
+pub struct CmapIter<'a>{
+	subtable: &'a CmapSubtable,
+	ranges: Vec<(u32, u32)>,
+	range: usize,
+	next: Option<u32>,
+}
+impl Iterator for CmapIter<'_>{
+	type Item = (u32, u16);
+	fn next(&mut self)->Option<Self::Item>{
+		while let Some(&(start, end)) = self.ranges.get(self.range){
+			let code = self.next.unwrap_or(start).max(start);
+			if code > end{
+				self.range += 1;
+				continue;
+			}
+			self.next = code.checked_add(1);
+			if self.next.is_none(){self.range += 1;}
+			if let Some(glyph) = self.subtable.glyph_id(code){return Some((code, glyph));}
+		}
+		None
+	}
+}
+
+fn sequential_groups_lookup(groups: &[SequentialMapGroup], code: u32)->Option<u16>{
+	let i = groups.partition_point(|g|g.end_char_code < code);
+	let group = groups.get(i)?;
+	if code < group.start_char_code{return None;}
+	u16::try_from(group.start_glyph_id.checked_add(code - group.start_char_code)?).ok()
+}
+
+///Format 0: Byte encoding table
+#[derive(Debug)]
+pub struct CmapFormat0{
+	///Format number is set to 0.
+	pub format: u16,
+	///This is the length in bytes of the subtable.
+	pub length: u16,
+	///For requirements on use of the language field, see “Use of the language field in 'cmap' subtables” in this document.
+	pub language: u16,
+	///An array that maps character codes to glyph index values.
+	pub glyph_id_array: Box<[u8]>,
+}
+impl_from_file!(CmapFormat0, (), (), f, {Ok(Self{
+	format: unwrap_or_ret!(u16::from_file(f)),
+	length: unwrap_or_ret!(u16::from_file(f)),
+	language: unwrap_or_ret!(u16::from_file(f)),
+	glyph_id_array: unwrap_or_ret!(array_from_file(f, 256)),
+})});
+
+///Format 2: High-byte mapping through table
+#[derive(Debug)]
+pub struct CmapFormat2{
+	///Format number is set to 2.
+	pub format: u16,
+	///This is the length in bytes of the subtable.
+	pub length: u16,
+	///For requirements on use of the language field, see “Use of the language field in 'cmap' subtables” in this document.
+	pub language: u16,
+	///Array that maps high bytes to subHeaders: value is subHeader index × 8.
+	pub sub_header_keys: Box<[u16]>,
+	///Variable-length array of SubHeader records.
+	pub sub_headers: Box<[CmapSubHeader]>,
+	///Variable-length array containing subarrays used for mapping the low byte of 2-byte characters.
+	pub glyph_id_array: Box<[u16]>,
+}
+impl_from_file!(CmapFormat2, (), (), f, {
+	let format = unwrap_or_ret!(u16::from_file(f));
+	let length = unwrap_or_ret!(u16::from_file(f));
+	let language = unwrap_or_ret!(u16::from_file(f));
+	let sub_header_keys: Box<[u16]> = unwrap_or_ret!(array_from_file(f, 256));
+	let sub_header_count = sub_header_keys.iter().map(|k|k/8).max().unwrap() as usize + 1;
+	let sub_headers = unwrap_or_ret!(array_from_file(f, sub_header_count));
+	let header_size = 6 + 512 + sub_header_count * 8;
+	let glyph_count = (length as usize).saturating_sub(header_size) / 2;
+	Ok(Self{
+		format,
+		length,
+		language,
+		sub_header_keys,
+		sub_headers,
+		glyph_id_array: unwrap_or_ret!(array_from_file(f, glyph_count)),
+	})
+});
+impl CmapFormat2{
+	pub fn glyph_id(&self, code: u32)->Option<u16>{
+		if code > 0xFFFF{return None;}
+		let (sub_header_index, low) = if code < 0x100 && self.sub_header_keys[code as usize] == 0{
+			(0, code as u16)
+		}else{
+			let key = self.sub_header_keys[(code >> 8) as usize];
+			// a high byte with a key of 0 is a single byte character so can't start a two byte one
+			if key == 0{return None;}
+			((key / 8) as usize, (code & 0xFF) as u16)
+		};
+		let header = &self.sub_headers[sub_header_index];
+		if low < header.first_code || low - header.first_code >= header.entry_count{return None;}
+		// id_range_offset is relative to its own position within the sub header array
+		let id_range_offset_pos = sub_header_index * 8 + 6;
+		let array_start = self.sub_headers.len() * 8;
+		let index = (id_range_offset_pos + header.id_range_offset as usize).checked_sub(array_start)? / 2
+			+ (low - header.first_code) as usize;
+		let glyph = *self.glyph_id_array.get(index)?;
+		if glyph == 0{None}else{Some((glyph as i32 + header.id_delta as i32) as u16)}
+	}
+}
+#[derive(Debug,FromFile)]
+pub struct CmapSubHeader{
+	///First valid low byte for this SubHeader.
+	pub first_code: u16,
+	///Number of valid low bytes for this SubHeader.
+	pub entry_count: u16,
+	///See text below.
+	pub id_delta: i16,
+	///See text below.
+	pub id_range_offset: u16,
+}
+
+///Format 4: Segment mapping to delta values
+#[derive(Debug)]
+pub struct CmapFormat4{
+	///Format number is set to 4.
+	pub format: u16,
+	///This is the length in bytes of the subtable.
+	pub length: u16,
+	///For requirements on use of the language field, see “Use of the language field in 'cmap' subtables” in this document.
+	pub language: u16,
+	///2 × segCount.
+	pub seg_count_x2: u16,
+	///Maximum power of 2 less than or equal to segCount, times 2 ((2**floor(log2(segCount))) * 2, where “**” is an exponentiation operator)
+	pub search_range: u16,
+	///Log2 of the maximum power of 2 less than or equal to numTables (log2(searchRange/2), which is equal to floor(log2(segCount)))
+	pub entry_selector: u16,
+	///segCount times 2, minus searchRange ((segCount * 2) - searchRange)
+	pub range_shift: u16,
+	///End characterCode for each segment, last=0xFFFF.
+	pub end_code: Box<[u16]>,
+	///Set to 0.
+	pub reserved_pad: u16,
+	///Start character code for each segment.
+	pub start_code: Box<[u16]>,
+	///Delta for all character codes in segment.
+	pub id_delta: Box<[i16]>,
+	///Offsets into glyphIdArray or 0
+	pub id_range_offsets: Box<[u16]>,
+	///Glyph index array (arbitrary length)
+	pub glyph_id_array: Box<[u16]>,
+}
+impl_from_file!(CmapFormat4, (), (), f, {
+	let format = unwrap_or_ret!(u16::from_file(f));
+	let length = unwrap_or_ret!(u16::from_file(f));
+	let language = unwrap_or_ret!(u16::from_file(f));
+	let seg_count_x2 = unwrap_or_ret!(u16::from_file(f));
+	let seg_count = (seg_count_x2 / 2) as usize;
+	let search_range = unwrap_or_ret!(u16::from_file(f));
+	let entry_selector = unwrap_or_ret!(u16::from_file(f));
+	let range_shift = unwrap_or_ret!(u16::from_file(f));
+	let end_code = unwrap_or_ret!(array_from_file(f, seg_count));
+	let reserved_pad = unwrap_or_ret!(u16::from_file(f));
+	let start_code = unwrap_or_ret!(array_from_file(f, seg_count));
+	let id_delta = unwrap_or_ret!(array_from_file(f, seg_count));
+	let id_range_offsets = unwrap_or_ret!(array_from_file(f, seg_count));
+	let glyph_count = (length as usize).saturating_sub(16 + seg_count * 8) / 2;
+	Ok(Self{
+		format,
+		length,
+		language,
+		seg_count_x2,
+		search_range,
+		entry_selector,
+		range_shift,
+		end_code,
+		reserved_pad,
+		start_code,
+		id_delta,
+		id_range_offsets,
+		glyph_id_array: unwrap_or_ret!(array_from_file(f, glyph_count)),
+	})
+});
+impl CmapFormat4{
+	pub fn glyph_id(&self, code: u32)->Option<u16>{
+		if code > 0xFFFF{return None;}
+		let code = code as u16;
+		let seg = self.end_code.partition_point(|end|*end < code);
+		if seg >= self.end_code.len() || self.start_code[seg] > code{return None;}
+		let delta = self.id_delta[seg] as u16;
+		match self.id_range_offsets[seg]{
+			0 => Some(code.wrapping_add(delta)),
+			offset => {
+				// the offset is relative to the position of the offset itself within id_range_offsets
+				let index = (seg + (offset / 2) as usize + (code - self.start_code[seg]) as usize)
+					.checked_sub(self.id_range_offsets.len())?;
+				match *self.glyph_id_array.get(index)?{
+					0 => None,
+					glyph => Some(glyph.wrapping_add(delta)),
+				}
+			},
+		}
+	}
+}
+
+///Format 6: Trimmed table mapping
+#[derive(Debug,FromFile)]
+pub struct CmapFormat6{
+	///Format number is set to 6.
+	pub format: u16,
+	///This is the length in bytes of the subtable.
+	pub length: u16,
+	///For requirements on use of the language field, see “Use of the language field in 'cmap' subtables” in this document.
+	pub language: u16,
+	///First character code of subrange.
+	pub first_code: u16,
+	///Number of character codes in subrange.
+	pub entry_count: u16,
+	///Array of glyph index values for character codes in the range.
+	#[from_file_count(entry_count)]
+	pub glyph_id_array: Box<[u16]>,
+}
+
+///Format 8: mixed 16-bit and 32-bit coverage
+#[derive(Debug)]
+pub struct CmapFormat8{
+	///Subtable format; set to 8.
+	pub format: u16,
+	///Reserved; set to 0
+	pub reserved: u16,
+	///Byte length of this subtable (including the header)
+	pub length: u32,
+	///For requirements on use of the language field, see “Use of the language field in 'cmap' subtables” in this document.
+	pub language: u32,
+	///Tightly packed array of bits (8K bytes total) indicating whether the particular 16-bit (index) value is the start of a 32-bit character code
+	pub is32: Box<[u8]>,
+	///Number of groupings which follow
+	pub num_groups: u32,
+	///Array of SequentialMapGroup records.
+	pub groups: Box<[SequentialMapGroup]>,
+}
+impl CmapFormat8{
+	const HEADER_SIZE: u64 = 8208;
+}
+impl_from_file!(CmapFormat8, (), (), f, {
+	let format = unwrap_or_ret!(u16::from_file(f));
+	let reserved = unwrap_or_ret!(u16::from_file(f));
+	let length = unwrap_or_ret!(u32::from_file(f));
+	let language = unwrap_or_ret!(u32::from_file(f));
+	let is32 = unwrap_or_ret!(array_from_file(f, 8192));
+	let num_groups = unwrap_or_ret!(u32::from_file(f));
+	Ok(Self{
+		format,
+		reserved,
+		length,
+		language,
+		is32,
+		num_groups,
+		groups: unwrap_or_ret!(bounded_array_from_file(f, num_groups, 12, (length as u64).saturating_sub(Self::HEADER_SIZE))),
+	})
+});
+
+///Format 10: Trimmed array
+#[derive(Debug)]
+pub struct CmapFormat10{
+	///Subtable format; set to 10.
+	pub format: u16,
+	///Reserved; set to 0
+	pub reserved: u16,
+	///Byte length of this subtable (including the header)
+	pub length: u32,
+	///For requirements on use of the language field, see “Use of the language field in 'cmap' subtables” in this document.
+	pub language: u32,
+	///First character code covered
+	pub start_char_code: u32,
+	///Number of character codes covered
+	pub num_chars: u32,
+	///Array of glyph indices for the character codes covered
+	pub glyph_id_array: Box<[u16]>,
+}
+impl CmapFormat10{
+	const HEADER_SIZE: u64 = 20;
+}
+impl_from_file!(CmapFormat10, (), (), f, {
+	let format = unwrap_or_ret!(u16::from_file(f));
+	let reserved = unwrap_or_ret!(u16::from_file(f));
+	let length = unwrap_or_ret!(u32::from_file(f));
+	let language = unwrap_or_ret!(u32::from_file(f));
+	let start_char_code = unwrap_or_ret!(u32::from_file(f));
+	let num_chars = unwrap_or_ret!(u32::from_file(f));
+	Ok(Self{
+		format,
+		reserved,
+		length,
+		language,
+		start_char_code,
+		num_chars,
+		glyph_id_array: unwrap_or_ret!(bounded_array_from_file(f, num_chars, 2, (length as u64).saturating_sub(Self::HEADER_SIZE))),
+	})
+});
+
+///Format 12: Segmented coverage
+#[derive(Debug)]
+pub struct CmapFormat12{
+	///Subtable format; set to 12.
+	pub format: u16,
+	///Reserved; set to 0
+	pub reserved: u16,
+	///Byte length of this subtable (including the header)
+	pub length: u32,
+	///For requirements on use of the language field, see “Use of the language field in 'cmap' subtables” in this document.
+	pub language: u32,
+	///Number of groupings which follow
+	pub num_groups: u32,
+	///Array of SequentialMapGroup records.
+	pub groups: Box<[SequentialMapGroup]>,
+}
+impl CmapFormat12{
+	const HEADER_SIZE: u64 = 16;
+}
+impl_from_file!(CmapFormat12, (), (), f, {
+	let format = unwrap_or_ret!(u16::from_file(f));
+	let reserved = unwrap_or_ret!(u16::from_file(f));
+	let length = unwrap_or_ret!(u32::from_file(f));
+	let language = unwrap_or_ret!(u32::from_file(f));
+	let num_groups = unwrap_or_ret!(u32::from_file(f));
+	Ok(Self{
+		format,
+		reserved,
+		length,
+		language,
+		num_groups,
+		groups: unwrap_or_ret!(bounded_array_from_file(f, num_groups, 12, (length as u64).saturating_sub(Self::HEADER_SIZE))),
+	})
+});
+#[derive(Debug,FromFile)]
+pub struct SequentialMapGroup{
+	///First character code in this group; note that if this group is for one or more 16-bit character codes (which is determined from the is32 array), this 32-bit value will have the high 16-bits set to zero
+	pub start_char_code: u32,
+	///Last character code in this group; same condition as listed above for the startCharCode
+	pub end_char_code: u32,
+	///Glyph index corresponding to the starting character code
+	pub start_glyph_id: u32,
+}
+
+///Format 13: Many-to-one range mappings
+#[derive(Debug)]
+pub struct CmapFormat13{
+	///Subtable format; set to 13.
+	pub format: u16,
+	///Reserved; set to 0
+	pub reserved: u16,
+	///Byte length of this subtable (including the header)
+	pub length: u32,
+	///For requirements on use of the language field, see “Use of the language field in 'cmap' subtables” in this document.
+	pub language: u32,
+	///Number of groupings which follow
+	pub num_groups: u32,
+	///Array of ConstantMapGroup records.
+	pub groups: Box<[ConstantMapGroup]>,
+}
+impl CmapFormat13{
+	const HEADER_SIZE: u64 = 16;
+}
+impl_from_file!(CmapFormat13, (), (), f, {
+	let format = unwrap_or_ret!(u16::from_file(f));
+	let reserved = unwrap_or_ret!(u16::from_file(f));
+	let length = unwrap_or_ret!(u32::from_file(f));
+	let language = unwrap_or_ret!(u32::from_file(f));
+	let num_groups = unwrap_or_ret!(u32::from_file(f));
+	Ok(Self{
+		format,
+		reserved,
+		length,
+		language,
+		num_groups,
+		groups: unwrap_or_ret!(bounded_array_from_file(f, num_groups, 12, (length as u64).saturating_sub(Self::HEADER_SIZE))),
+	})
+});
+#[derive(Debug,FromFile)]
+pub struct ConstantMapGroup{
+	///First character code in this group
+	pub start_char_code: u32,
+	///Last character code in this group
+	pub end_char_code: u32,
+	///Glyph index to be used for all the characters in the group’s range.
+	pub glyph_id: u32,
+}
+
+///Format 14: Unicode Variation Sequences
+#[derive(Debug)]
+pub struct CmapFormat14{
+	///Subtable format. Set to 14.
+	pub format: u16,
+	///Byte length of this subtable (including this header)
+	pub length: u32,
+	///Number of variation Selector Records
+	pub num_var_selector_records: u32,
+	///Array of VariationSelector records.
+	pub var_selector: Box<[VariationSelector]>,
+	///The default UVS table for each variation selector record (if it has one)
+	pub default_uvs: Box<[Option<Box<[UnicodeRange]>>]>,
+	///The non-default UVS table for each variation selector record (if it has one)
+	pub non_default_uvs: Box<[Option<Box<[UVSMapping]>>]>,
+}
+impl_from_file!(CmapFormat14, (), (), f, {
+	let start = f.stream_position().unwrap();
+	let format = unwrap_or_ret!(u16::from_file(f));
+	let length = unwrap_or_ret!(u32::from_file(f));
+	let num_var_selector_records = unwrap_or_ret!(u32::from_file(f));
+	let var_selector: Box<[VariationSelector]> = unwrap_or_ret!(bounded_array_from_file(f, num_var_selector_records, 11, (length as u64).saturating_sub(10)));
+	let mut default_uvs = Vec::with_capacity(var_selector.len());
+	let mut non_default_uvs = Vec::with_capacity(var_selector.len());
+	for selector in var_selector.iter(){
+		default_uvs.push(if selector.default_uvs_offset == 0{None}else{
+			if f.seek(SeekFrom::Start(start + selector.default_uvs_offset as u64)).is_err()
+			{return Err(FromFileErr::EOF);}
+			let count = unwrap_or_ret!(u32::from_file(f));
+			let limit = (length as u64).saturating_sub(selector.default_uvs_offset as u64 + 4);
+			Some(unwrap_or_ret!(bounded_array_from_file(f, count, 4, limit)))
+		});
+		non_default_uvs.push(if selector.non_default_uvs_offset == 0{None}else{
+			if f.seek(SeekFrom::Start(start + selector.non_default_uvs_offset as u64)).is_err()
+			{return Err(FromFileErr::EOF);}
+			let count = unwrap_or_ret!(u32::from_file(f));
+			let limit = (length as u64).saturating_sub(selector.non_default_uvs_offset as u64 + 4);
+			Some(unwrap_or_ret!(bounded_array_from_file(f, count, 5, limit)))
+		});
+	}
+	Ok(Self{
+		format,
+		length,
+		num_var_selector_records,
+		var_selector,
+		default_uvs: default_uvs.into(),
+		non_default_uvs: non_default_uvs.into(),
+	})
+});
+///The result of looking up a variation sequence in a format 14 subtable
+#[derive(Debug, PartialEq, Eq)]
+pub enum VariationLookup{
+	///The sequence is not supported by the font
+	NotFound,
+	///The sequence should use the glyph from the font's default unicode subtable
+	Default,
+	///The sequence maps to a specific glyph
+	Glyph(u16),
+}
+impl CmapFormat14{
+	pub fn lookup(&self, code: u32, selector: u32)->VariationLookup{
+		let Some(i) = self.var_selector.iter().position(|s|s.var_selector.0 == selector)
+		else{return VariationLookup::NotFound;};
+		if let Some(ranges) = &self.default_uvs[i]{
+			if ranges.iter().any(|r|
+				r.start_unicode_value.0 <= code && code <= r.start_unicode_value.0 + r.additional_count as u32
+			){return VariationLookup::Default;}
+		}
+		if let Some(mappings) = &self.non_default_uvs[i]{
+			if let Some(m) = mappings.iter().find(|m|m.unicode_value.0 == code){
+				return VariationLookup::Glyph(m.glyph_id);
+			}
+		}
+		VariationLookup::NotFound
+	}
+}
+#[derive(Debug,FromFile)]
+pub struct VariationSelector{
+	///Variation selector
+	pub var_selector: Uint24,
+	///Offset from the start of the format 14 subtable to Default UVS Table. May be 0.
+	pub default_uvs_offset: Offset32,
+	///Offset from the start of the format 14 subtable to Non-Default UVS Table. May be 0.
+	pub non_default_uvs_offset: Offset32,
+}
+#[derive(Debug,FromFile)]
+pub struct UnicodeRange{
+	///First value in this range
+	pub start_unicode_value: Uint24,
+	///Number of additional values in this range
+	pub additional_count: u8,
+}
+#[derive(Debug,FromFile)]
+pub struct UVSMapping{
+	///Base Unicode value of the UVS
+	pub unicode_value: Uint24,
+	///Glyph ID of the UVS
+	pub glyph_id: u16,
+}
+
+#[derive(Debug)]
+pub struct MaxpTable{
+	///0x00005000 for version 0.5 (CFF outlines), 0x00010000 for version 1.0 (TrueType outlines)
+	pub version: u32,
+	///The number of glyphs in the font.
+	pub num_glyphs: u16,
+	///Version (=1.0) Maximum points in a non-composite glyph.
+	pub max_points: Option<u16>,
+	///Version (=1.0) Maximum contours in a non-composite glyph.
+	pub max_contours: Option<u16>,
+	///Version (=1.0) Maximum points in a composite glyph.
+	pub max_composite_points: Option<u16>,
+	///Version (=1.0) Maximum contours in a composite glyph.
+	pub max_composite_contours: Option<u16>,
+	///Version (=1.0) 1 if instructions do not use the twilight zone (Z0), or 2 if instructions do use Z0; should be set to 2 in most cases.
+	pub max_zones: Option<u16>,
+	///Version (=1.0) Maximum points used in Z0.
+	pub max_twilight_points: Option<u16>,
+	///Version (=1.0) Number of Storage Area locations.
+	pub max_storage: Option<u16>,
+	///Version (=1.0) Number of FDEFs, equal to the highest function number + 1.
+	pub max_function_defs: Option<u16>,
+	///Version (=1.0) Number of IDEFs.
+	pub max_instruction_defs: Option<u16>,
+	///Version (=1.0) Maximum stack depth across Font Program ('fpgm' table), CVT Program ('prep' table) and all glyph instructions (in the 'glyf' table).
+	pub max_stack_elements: Option<u16>,
+	///Version (=1.0) Maximum byte count for glyph instructions.
+	pub max_size_of_instructions: Option<u16>,
+	///Version (=1.0) Maximum number of components referenced at “top level” for any composite glyph.
+	pub max_component_elements: Option<u16>,
+	///Version (=1.0) Maximum levels of recursion; 1 for simple components.
+	pub max_component_depth: Option<u16>,
+}
+impl MaxpTable{
+	pub const VERSION_0_5: u32 = 0x00005000;
+	pub const VERSION_1_0: u32 = 0x00010000;
+}
+impl_from_file!(MaxpTable, (), (), f, {
+	let version = unwrap_or_ret!(u32::from_file(f));
+	let num_glyphs = unwrap_or_ret!(u16::from_file(f));
+	let v1 = version >= Self::VERSION_1_0;
+	macro_rules! v1_field {() => {if v1{Some(unwrap_or_ret!(u16::from_file(f)))}else{None}};}
+	Ok(Self{
+		version,
+		num_glyphs,
+		max_points: v1_field!(),
+		max_contours: v1_field!(),
+		max_composite_points: v1_field!(),
+		max_composite_contours: v1_field!(),
+		max_zones: v1_field!(),
+		max_twilight_points: v1_field!(),
+		max_storage: v1_field!(),
+		max_function_defs: v1_field!(),
+		max_instruction_defs: v1_field!(),
+		max_stack_elements: v1_field!(),
+		max_size_of_instructions: v1_field!(),
+		max_component_elements: v1_field!(),
+		max_component_depth: v1_field!(),
+	})
+});
+
+#[derive(Debug)]
+pub struct Os2Table{
+	///Table version number (0 to 5)
+	pub version: u16,
+	///The Average Character Width parameter specifies the arithmetic average of the escapement (width) of all non-zero width glyphs in the font.
+	pub x_avg_char_width: FWord,
+	///Indicates the visual weight (degree of blackness or thickness of strokes) of the characters in the font. Values from 1 to 1000 are valid.
+	pub us_weight_class: u16,
+	///Indicates a relative change from the normal aspect ratio (width to height ratio) as specified by a font designer for the glyphs in a font.
+	pub us_width_class: u16,
+	///Indicates font embedding licensing rights for the font.
+	pub fs_type: u16,
+	///The recommended horizontal size in font design units for subscripts for this font.
+	pub y_subscript_x_size: FWord,
+	///The recommended vertical size in font design units for subscripts for this font.
+	pub y_subscript_y_size: FWord,
+	///The recommended horizontal offset in font design units for subscripts for this font.
+	pub y_subscript_x_offset: FWord,
+	///The recommended vertical offset in font design units from the baseline for subscripts for this font.
+	pub y_subscript_y_offset: FWord,
+	///The recommended horizontal size in font design units for superscripts for this font.
+	pub y_superscript_x_size: FWord,
+	///The recommended vertical size in font design units for superscripts for this font.
+	pub y_superscript_y_size: FWord,
+	///The recommended horizontal offset in font design units for superscripts for this font.
+	pub y_superscript_x_offset: FWord,
+	///The recommended vertical offset in font design units from the baseline for superscripts for this font.
+	pub y_superscript_y_offset: FWord,
+	///Thickness of the strikeout stroke in font design units.
+	pub y_strikeout_size: FWord,
+	///The position of the top of the strikeout stroke relative to the baseline in font design units.
+	pub y_strikeout_position: FWord,
+	///This parameter is a classification of font-family design.
+	pub s_family_class: i16,
+	///This 10-byte series of numbers is used to describe the visual characteristics of a given typeface.
+	pub panose: Box<[u8]>,
+	///Unicode Character Range bits 0–31
+	pub ul_unicode_range1: u32,
+	///Unicode Character Range bits 32–63
+	pub ul_unicode_range2: u32,
+	///Unicode Character Range bits 64–95
+	pub ul_unicode_range3: u32,
+	///Unicode Character Range bits 96–127
+	pub ul_unicode_range4: u32,
+	///The four-character identifier for the vendor of the given type face, it is kept as bytes as many fonts pad it with NULs
+	pub ach_vend_id: [u8; 4],
+	///Contains information concerning the nature of the font patterns
+	pub fs_selection: u16,
+	///The minimum Unicode index (character code) in this font, according to the 'cmap' subtable for platform ID 3 and platform- specific encoding ID 0 or 1.
+	pub us_first_char_index: u16,
+	///The maximum Unicode index (character code) in this font, according to the 'cmap' subtable for platform ID 3 and encoding ID 0 or 1.
+	pub us_last_char_index: u16,
+	///The typographic ascender for this font. `None` for version 0 tables that end before it.
+	pub s_typo_ascender: Option<FWord>,
+	///The typographic descender for this font. `None` for version 0 tables that end before it.
+	pub s_typo_descender: Option<FWord>,
+	///The typographic line gap for this font. `None` for version 0 tables that end before it.
+	pub s_typo_line_gap: Option<FWord>,
+	///The “Windows ascender” metric. This should be used to specify the height above the baseline for a clipping region. `None` for version 0 tables that end before it.
+	pub us_win_ascent: Option<UFWord>,
+	///The “Windows descender” metric. This should be used to specify the vertical extent below the baseline for a clipping region. `None` for version 0 tables that end before it.
+	pub us_win_descent: Option<UFWord>,
+	///Version (>=1) Code Page Character Range bits 0–31
+	pub ul_code_page_range1: Option<u32>,
+	///Version (>=1) Code Page Character Range bits 32–63
+	pub ul_code_page_range2: Option<u32>,
+	///Version (>=2) This metric specifies the distance between the baseline and the approximate height of non-ascending lowercase letters measured in FUnits.
+	pub sx_height: Option<FWord>,
+	///Version (>=2) This metric specifies the distance between the baseline and the approximate height of uppercase letters measured in FUnits.
+	pub s_cap_height: Option<FWord>,
+	///Version (>=2) This is the Unicode code point, in UTF-16 encoding, of a character that can be used for a default glyph if a requested character is not supported in the font.
+	pub us_default_char: Option<u16>,
+	///Version (>=2) This is the Unicode code point, in UTF-16 encoding, of a character that can be used as a default break character.
+	pub us_break_char: Option<u16>,
+	///Version (>=2) The maximum length of a target glyph context for any feature in this font.
+	pub us_max_context: Option<u16>,
+	///Version (>=5) This field is used for fonts with multiple optical styles. This value is the lower value of the size range for which this font has been designed, in TWIPs (twentieths of a point).
+	pub us_lower_optical_point_size: Option<u16>,
+	///Version (>=5) This field is used for fonts with multiple optical styles. This value is the upper value of the size range for which this font has been designed, in TWIPs (twentieths of a point).
+	pub us_upper_optical_point_size: Option<u16>,
+}
+impl_from_file!(Os2Table, (), (), f, {Self::from_file_with_length(f, u32::MAX)});
+impl Os2Table{
+	///Size of the original version 0 table which ends after `us_last_char_index`
+	const SHORT_VERSION_0_SIZE: u32 = 68;
+
+	///Reads the table from the `length` given by its table record, version 0 tables may be short and end after `us_last_char_index`
+	pub fn from_file_with_length<F>(f: &mut F, length: u32)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		let version = unwrap_or_ret!(u16::from_file(f));
+		let short = version == 0 && length <= Self::SHORT_VERSION_0_SIZE;
+		macro_rules! field {($type: ty) => {unwrap_or_ret!(<$type>::from_file(f))};}
+		macro_rules! versioned_field {($min_version: expr, $type: ty) => {
+			if version >= $min_version{Some(unwrap_or_ret!(<$type>::from_file(f)))}else{None}
+		};}
+		macro_rules! long_field {($type: ty) => {if short{None}else{Some(field!($type))}};}
+		Ok(Self{
+			version,
+			x_avg_char_width: field!(FWord),
+			us_weight_class: field!(u16),
+			us_width_class: field!(u16),
+			fs_type: field!(u16),
+			y_subscript_x_size: field!(FWord),
+			y_subscript_y_size: field!(FWord),
+			y_subscript_x_offset: field!(FWord),
+			y_subscript_y_offset: field!(FWord),
+			y_superscript_x_size: field!(FWord),
+			y_superscript_y_size: field!(FWord),
+			y_superscript_x_offset: field!(FWord),
+			y_superscript_y_offset: field!(FWord),
+			y_strikeout_size: field!(FWord),
+			y_strikeout_position: field!(FWord),
+			s_family_class: field!(i16),
+			panose: unwrap_or_ret!(array_from_file(f, 10)),
+			ul_unicode_range1: field!(u32),
+			ul_unicode_range2: field!(u32),
+			ul_unicode_range3: field!(u32),
+			ul_unicode_range4: field!(u32),
+			ach_vend_id: field!(u32).to_be_bytes(),
+			fs_selection: field!(u16),
+			us_first_char_index: field!(u16),
+			us_last_char_index: field!(u16),
+			s_typo_ascender: long_field!(FWord),
+			s_typo_descender: long_field!(FWord),
+			s_typo_line_gap: long_field!(FWord),
+			us_win_ascent: long_field!(UFWord),
+			us_win_descent: long_field!(UFWord),
+			ul_code_page_range1: versioned_field!(1, u32),
+			ul_code_page_range2: versioned_field!(1, u32),
+			sx_height: versioned_field!(2, FWord),
+			s_cap_height: versioned_field!(2, FWord),
+			us_default_char: versioned_field!(2, u16),
+			us_break_char: versioned_field!(2, u16),
+			us_max_context: versioned_field!(2, u16),
+			us_lower_optical_point_size: versioned_field!(5, u16),
+			us_upper_optical_point_size: versioned_field!(5, u16),
+		})
+	}
+	///The vendor identifier as text, bytes that aren't printable ASCII are replaced with U+FFFD
+	pub fn vendor_id(&self)->String{
+		self.ach_vend_id.iter().map(|b|if (0x20..=0x7E).contains(b){*b as char}else{char::REPLACEMENT_CHARACTER}).collect()
+	}
+}
+
+///The embedding licensing rights given by bits 0-3 of `fs_type`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbeddingPermission{
+	///Fonts with this setting indicate that they may be embedded and permanently installed on the remote system by an application.
+	Installable,
+	///Fonts that have only this bit set must not be modified, embedded or exchanged in any manner without first obtaining explicit permission of the legal owner.
+	Restricted,
+	///Fonts with this bit set indicate that they may be embedded within documents but must only be installed temporarily on the remote system.
+	PreviewAndPrint,
+	///Fonts with this bit set indicate that they may be embedded in documents, but must only be installed temporarily on the remote system.
+	Editable,
+}
+
+///The visual weight given by `us_weight_class`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightClass{
+	Thin,
+	ExtraLight,
+	Light,
+	Normal,
+	Medium,
+	SemiBold,
+	Bold,
+	ExtraBold,
+	Black,
+	Other(u16),
+}
+
+///The relative width given by `us_width_class`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WidthClass{
+	UltraCondensed,
+	ExtraCondensed,
+	Condensed,
+	SemiCondensed,
+	Normal,
+	SemiExpanded,
+	Expanded,
+	ExtraExpanded,
+	UltraExpanded,
+	Other(u16),
+}
+impl WidthClass{
+	///The width as a percentage of normal
+	pub fn percentage(&self)->Option<f32>{match self{
+		Self::UltraCondensed => Some(50.0),
+		Self::ExtraCondensed => Some(62.5),
+		Self::Condensed => Some(75.0),
+		Self::SemiCondensed => Some(87.5),
+		Self::Normal => Some(100.0),
+		Self::SemiExpanded => Some(112.5),
+		Self::Expanded => Some(125.0),
+		Self::ExtraExpanded => Some(150.0),
+		Self::UltraExpanded => Some(200.0),
+		Self::Other(_) => None,
+	}}
+}
+
+impl Os2Table{
+	///Bit 0: Font contains italic or oblique glyphs, otherwise they are upright.
+	pub const ITALIC: u16 = 1 << 0;
+	///Bit 1: glyphs are underscored.
+	pub const UNDERSCORE: u16 = 1 << 1;
+	///Bit 2: glyphs have their foreground and background reversed.
+	pub const NEGATIVE: u16 = 1 << 2;
+	///Bit 3: Outline (hollow) glyphs, otherwise they are solid.
+	pub const OUTLINED: u16 = 1 << 3;
+	///Bit 4: glyphs are overstruck.
+	pub const STRIKEOUT: u16 = 1 << 4;
+	///Bit 5: glyphs are emboldened.
+	pub const BOLD: u16 = 1 << 5;
+	///Bit 6: glyphs are in the standard weight/style for the font.
+	pub const REGULAR: u16 = 1 << 6;
+	///Bit 7: If set, it is strongly recommended that applications use OS/2.sTypoAscender - OS/2.sTypoDescender + OS/2.sTypoLineGap as the default line spacing for this font.
+	pub const USE_TYPO_METRICS: u16 = 1 << 7;
+	///Bit 8: The font has 'name' table strings consistent with a weight/width/slope family without requiring use of name IDs 21 and 22.
+	pub const WWS: u16 = 1 << 8;
+	///Bit 9: Font contains oblique glyphs.
+	pub const OBLIQUE: u16 = 1 << 9;
+
+	pub fn embedding_permission(&self)->EmbeddingPermission{
+		// the least restrictive permission is used if more than one is set
+		let bits = self.fs_type & 0xF;
+		if bits & 0x8 != 0{EmbeddingPermission::Editable}
+		else if bits & 0x4 != 0{EmbeddingPermission::PreviewAndPrint}
+		else if bits & 0x2 != 0{EmbeddingPermission::Restricted}
+		else{EmbeddingPermission::Installable}
+	}
+	///Bit 8 of `fs_type`: the font may not be subsetted prior to embedding
+	pub fn no_subsetting(&self)->bool{self.fs_type & 0x0100 != 0}
+	///Bit 9 of `fs_type`: only bitmaps contained in the font may be embedded
+	pub fn bitmap_embedding_only(&self)->bool{self.fs_type & 0x0200 != 0}
+
+	///Whether the flag (one of the associated constants of this type) is set in `fs_selection`
+	pub fn has_selection_flag(&self, flag: u16)->bool{self.fs_selection & flag != 0}
+	pub fn is_italic(&self)->bool{self.has_selection_flag(Self::ITALIC)}
+	pub fn is_bold(&self)->bool{self.has_selection_flag(Self::BOLD)}
+	pub fn is_regular(&self)->bool{self.has_selection_flag(Self::REGULAR)}
+	pub fn is_oblique(&self)->bool{self.has_selection_flag(Self::OBLIQUE)}
+	pub fn use_typo_metrics(&self)->bool{self.has_selection_flag(Self::USE_TYPO_METRICS)}
+
+	pub fn weight_class(&self)->WeightClass{match self.us_weight_class{
+		100 => WeightClass::Thin,
+		200 => WeightClass::ExtraLight,
+		300 => WeightClass::Light,
+		400 => WeightClass::Normal,
+		500 => WeightClass::Medium,
+		600 => WeightClass::SemiBold,
+		700 => WeightClass::Bold,
+		800 => WeightClass::ExtraBold,
+		900 => WeightClass::Black,
+		v => WeightClass::Other(v),
+	}}
+	pub fn width_class(&self)->WidthClass{match self.us_width_class{
+		1 => WidthClass::UltraCondensed,
+		2 => WidthClass::ExtraCondensed,
+		3 => WidthClass::Condensed,
+		4 => WidthClass::SemiCondensed,
+		5 => WidthClass::Normal,
+		6 => WidthClass::SemiExpanded,
+		7 => WidthClass::Expanded,
+		8 => WidthClass::ExtraExpanded,
+		9 => WidthClass::UltraExpanded,
+		v => WidthClass::Other(v),
+	}}
+
+	///Whether bit `bit` (0-127) of the unicode ranges is set
+	pub fn has_unicode_range(&self, bit: u8)->bool{
+		let range = match bit / 32{
+			0 => self.ul_unicode_range1,
+			1 => self.ul_unicode_range2,
+			2 => self.ul_unicode_range3,
+			3 => self.ul_unicode_range4,
+			_ => return false,
+		};
+		range & (1 << (bit % 32)) != 0
+	}
+	///The indices of every set unicode range bit
+	pub fn unicode_ranges(&self)->Vec<u8>{(0..128).filter(|b|self.has_unicode_range(*b)).collect()}
+	///Whether bit `bit` (0-63) of the code page ranges is set (always `false` for version 0 tables)
+	pub fn has_code_page(&self, bit: u8)->bool{
+		let range = match bit / 32{
+			0 => self.ul_code_page_range1,
+			1 => self.ul_code_page_range2,
+			_ => None,
+		};
+		range.is_some_and(|r|r & (1 << (bit % 32)) != 0)
+	}
+}
+
+#[derive(Debug)]
+pub struct PostTable{
+	///0x00010000 for version 1.0, 0x00020000 for version 2.0, 0x00025000 for version 2.5 (deprecated), 0x00030000 for version 3.0
+	pub version: u32,
+	///Italic angle in counter-clockwise degrees from the vertical. Zero for upright text, negative for text that leans to the right (forward).
+	pub italic_angle: Fixed,
+	///Suggested y-coordinate of the top of the underline.
+	pub underline_position: FWord,
+	///Suggested values for the underline thickness. In general, the underline thickness should match the thickness of the underscore character (U+005F LOW LINE), and should also match the strikeout thickness, which is specified in the OS/2 table.
+	pub underline_thickness: FWord,
+	///Set to 0 if the font is proportionally spaced, non-zero if the font is not proportionally spaced (i.e. monospaced).
+	pub is_fixed_pitch: u32,
+	///Minimum memory usage when an OpenType font is downloaded.
+	pub min_mem_type42: u32,
+	///Maximum memory usage when an OpenType font is downloaded.
+	pub max_mem_type42: u32,
+	///Minimum memory usage when an OpenType font is downloaded as a Type 1 font.
+	pub min_mem_type1: u32,
+	///Maximum memory usage when an OpenType font is downloaded as a Type 1 font.
+	pub max_mem_type1: u32,
+	///Version (=2.0 or =2.5) Number of glyphs (this should be the same as numGlyphs in 'maxp' table).
+	pub num_glyphs: Option<u16>,
+	///Version (=2.0) Array of indices into the string data. See below for details.
+	pub glyph_name_index: Option<Box<[u16]>>,
+	///Version (=2.0) The names stored as Pascal strings in the string data
+	pub names: Option<Box<[String]>>,
+	///Version (=2.5) Difference between the graphic index and the standard order of the glyph.
+	pub offset: Option<Box<[i8]>>,
+}
+impl PostTable{
+	pub const VERSION_1_0: u32 = 0x00010000;
+	pub const VERSION_2_0: u32 = 0x00020000;
+	pub const VERSION_2_5: u32 = 0x00025000;
+	pub const VERSION_3_0: u32 = 0x00030000;
+
+	pub fn is_fixed_pitch(&self)->bool{self.is_fixed_pitch != 0}
+	///The PostScript name of the glyph if the table has glyph names
+	pub fn glyph_name(&self, glyph_id: u16)->Option<&str>{match self.version{
+		Self::VERSION_1_0 => MAC_GLYPH_NAMES.get(glyph_id as usize).copied(),
+		Self::VERSION_2_0 => {
+			let index = *self.glyph_name_index.as_ref()?.get(glyph_id as usize)? as usize;
+			if index < MAC_GLYPH_NAMES.len(){Some(MAC_GLYPH_NAMES[index])}
+			else{self.names.as_ref()?.get(index - MAC_GLYPH_NAMES.len()).map(|s|s.as_str())}
+		},
+		Self::VERSION_2_5 => {
+			let offset = *self.offset.as_ref()?.get(glyph_id as usize)?;
+			MAC_GLYPH_NAMES.get(usize::try_from(glyph_id as i32 + offset as i32).ok()?).copied()
+		},
+		_ => None,
+	}}
+}
+impl_from_file!(PostTable, (), (), f, {
+	let version = unwrap_or_ret!(u32::from_file(f));
+	let italic_angle = unwrap_or_ret!(Fixed::from_file(f));
+	let underline_position = unwrap_or_ret!(FWord::from_file(f));
+	let underline_thickness = unwrap_or_ret!(FWord::from_file(f));
+	let is_fixed_pitch = unwrap_or_ret!(u32::from_file(f));
+	let min_mem_type42 = unwrap_or_ret!(u32::from_file(f));
+	let max_mem_type42 = unwrap_or_ret!(u32::from_file(f));
+	let min_mem_type1 = unwrap_or_ret!(u32::from_file(f));
+	let max_mem_type1 = unwrap_or_ret!(u32::from_file(f));
+	let num_glyphs = if version == Self::VERSION_2_0 || version == Self::VERSION_2_5
+	{Some(unwrap_or_ret!(u16::from_file(f)))}else{None};
+	let mut glyph_name_index = None;
+	let mut names = None;
+	let mut offset = None;
+	if version == Self::VERSION_2_0{
+		let indices: Box<[u16]> = unwrap_or_ret!(array_from_file(f, num_glyphs.unwrap() as usize));
+		// only as many strings as are referenced are read as the table length is unknown here
+		let count = indices.iter().max().map_or(0, |m|(*m as usize + 1).saturating_sub(MAC_GLYPH_NAMES.len()));
+		let mut strings = Vec::with_capacity(count);
+		for _ in 0..count{
+			let length = unwrap_or_ret!(u8::from_file(f));
+			let bytes: Box<[u8]> = unwrap_or_ret!(array_from_file(f, length as usize));
+			strings.push(if bytes.is_empty(){String::new()}else{match CharSetStr::<MacOsRoman>::from_bytes(&bytes){
+				Ok(s) => s.to_string(),
+				Err(_) => return Err(FromFileErr::InvalidData(())),
+			}});
+		}
+		glyph_name_index = Some(indices);
+		names = Some(strings.into());
+	}else if version == Self::VERSION_2_5{
+		offset = Some(unwrap_or_ret!(array_from_file(f, num_glyphs.unwrap() as usize)));
+	}
+	Ok(Self{
+		version,
+		italic_angle,
+		underline_position,
+		underline_thickness,
+		is_fixed_pitch,
+		min_mem_type42,
+		max_mem_type42,
+		min_mem_type1,
+		max_mem_type1,
+		num_glyphs,
+		glyph_name_index,
+		names,
+		offset,
+	})
+});
+
+///The names of the 258 glyphs in the standard Macintosh glyph ordering
+pub const MAC_GLYPH_NAMES: [&str; 258] = [
+	".notdef", ".null", "nonmarkingreturn", "space", "exclam", "quotedbl", "numbersign", "dollar",
+	"percent", "ampersand", "quotesingle", "parenleft", "parenright", "asterisk", "plus", "comma",
+	"hyphen", "period", "slash", "zero", "one", "two", "three", "four",
+	"five", "six", "seven", "eight", "nine", "colon", "semicolon", "less",
+	"equal", "greater", "question", "at", "A", "B", "C", "D",
+	"E", "F", "G", "H", "I", "J", "K", "L",
+	"M", "N", "O", "P", "Q", "R", "S", "T",
+	"U", "V", "W", "X", "Y", "Z", "bracketleft", "backslash",
+	"bracketright", "asciicircum", "underscore", "grave", "a", "b", "c", "d",
+	"e", "f", "g", "h", "i", "j", "k", "l",
+	"m", "n", "o", "p", "q", "r", "s", "t",
+	"u", "v", "w", "x", "y", "z", "braceleft", "bar",
+	"braceright", "asciitilde", "Adieresis", "Aring", "Ccedilla", "Eacute", "Ntilde", "Odieresis",
+	"Udieresis", "aacute", "agrave", "acircumflex", "adieresis", "atilde", "aring", "ccedilla",
+	"eacute", "egrave", "ecircumflex", "edieresis", "iacute", "igrave", "icircumflex", "idieresis",
+	"ntilde", "oacute", "ograve", "ocircumflex", "odieresis", "otilde", "uacute", "ugrave",
+	"ucircumflex", "udieresis", "dagger", "degree", "cent", "sterling", "section", "bullet",
+	"paragraph", "germandbls", "registered", "copyright", "trademark", "acute", "dieresis", "notequal",
+	"AE", "Oslash", "infinity", "plusminus", "lessequal", "greaterequal", "yen", "mu",
+	"partialdiff", "summation", "product", "pi", "integral", "ordfeminine", "ordmasculine", "Omega",
+	"ae", "oslash", "questiondown", "exclamdown", "logicalnot", "radical", "florin", "approxequal",
+	"Delta", "guillemotleft", "guillemotright", "ellipsis", "nonbreakingspace", "Agrave", "Atilde", "Otilde",
+	"OE", "oe", "endash", "emdash", "quotedblleft", "quotedblright", "quoteleft", "quoteright",
+	"divide", "lozenge", "ydieresis", "Ydieresis", "fraction", "currency", "guilsinglleft", "guilsinglright",
+	"fi", "fl", "daggerdbl", "periodcentered", "quotesinglbase", "quotedblbase", "perthousand", "Acircumflex",
+	"Ecircumflex", "Aacute", "Edieresis", "Egrave", "Iacute", "Icircumflex", "Idieresis", "Igrave",
+	"Oacute", "Ocircumflex", "apple", "Ograve", "Uacute", "Ucircumflex", "Ugrave", "dotlessi",
+	"circumflex", "tilde", "macron", "breve", "dotaccent", "ring", "cedilla", "hungarumlaut",
+	"ogonek", "caron", "Lslash", "lslash", "Scaron", "scaron", "Zcaron", "zcaron",
+	"brokenbar", "Eth", "eth", "Yacute", "yacute", "Thorn", "thorn", "minus",
+	"multiply", "onesuperior", "twosuperior", "threesuperior", "onehalf", "onequarter", "threequarters", "franc",
+	"Gbreve", "gbreve", "Idotaccent", "Scedilla", "scedilla", "Cacute", "cacute", "Ccaron",
+	"ccaron", "dcroat",
+];
+
+#[derive(Debug,FromFile)]
+pub struct HheaTable{
+	///Major version number of the horizontal header table — set to 1.
+	pub major_version: u16,
+	///Minor version number of the horizontal header table — set to 0.
+	pub minor_version: u16,
+	///Typographic ascent—see remarks below.
+	pub ascender: FWord,
+	///Typographic descent—see remarks below.
+	pub descender: FWord,
+	///Typographic line gap. Negative lineGap values are treated as zero in some legacy platform implementations.
+	pub line_gap: FWord,
+	///Maximum advance width value in 'hmtx' table.
+	pub advance_width_max: UFWord,
+	///Minimum left sidebearing value in 'hmtx' table for glyphs with contours (empty glyphs should be ignored).
+	pub min_left_side_bearing: FWord,
+	///Minimum right sidebearing value; calculated as min(aw - (lsb + xMax - xMin)) for glyphs with contours (empty glyphs should be ignored).
+	pub min_right_side_bearing: FWord,
+	///Max(lsb + (xMax - xMin)).
+	pub x_max_extent: FWord,
+	///Used to calculate the slope of the cursor (rise/run); 1 for vertical.
+	pub caret_slope_rise: i16,
+	///0 for vertical.
+	pub caret_slope_run: i16,
+	///The amount by which a slanted highlight on a glyph needs to be shifted to produce the best appearance. Set to 0 for non-slanted fonts
+	pub caret_offset: i16,
+	///set to 0
+	pub reserved0: i16,
+	///set to 0
+	pub reserved1: i16,
+	///set to 0
+	pub reserved2: i16,
+	///set to 0
+	pub reserved3: i16,
+	///0 for current format.
+	pub metric_data_format: i16,
+	///Number of hMetric entries in 'hmtx' table
+	pub number_of_h_metrics: u16,
+}
+
+#[derive(Debug,FromFile)]
+pub struct LongHorMetric{
+	///Advance width, in font design units.
+	pub advance_width: UFWord,
+	///Glyph left side bearing, in font design units.
+	pub lsb: FWord,
+}
+
+///Reads the (advance, side bearing) of a glyph from a `hmtx` or `vmtx` style table where only the first `num_long_metrics` glyphs have an advance
+fn read_long_metric<F>(f: &mut F, table_absolute: u64, length: u32, num_long_metrics: u16, glyph_id: u16)->Result<(u16, i16), FromFileErr<(),()>> where F: Read, F: Seek{
+	if num_long_metrics == 0{return Err(FromFileErr::InvalidData(()));}
+	let num_bearings = (length as u64).saturating_sub(num_long_metrics as u64 * 4) / 2;
+	if glyph_id >= num_long_metrics && (glyph_id - num_long_metrics) as u64 >= num_bearings
+	{return Err(FromFileErr::InvalidData(()));}
+	let pos = f.stream_position().unwrap();
+	let rv = (||{
+		// glyphs past the long metrics use the last advance
+		let advance_index = glyph_id.min(num_long_metrics - 1) as u64;
+		if f.seek(SeekFrom::Start(table_absolute + advance_index * 4)).is_err(){return Err(FromFileErr::EOF);}
+		let advance = unwrap_or_ret!(u16::from_file(f));
+		let bearing_pos = if glyph_id < num_long_metrics{table_absolute + glyph_id as u64 * 4 + 2}else{
+			table_absolute + num_long_metrics as u64 * 4 + (glyph_id - num_long_metrics) as u64 * 2
+		};
+		if f.seek(SeekFrom::Start(bearing_pos)).is_err(){return Err(FromFileErr::EOF);}
+		Ok((advance, unwrap_or_ret!(i16::from_file(f))))
+	})();
+	let _ = f.seek(SeekFrom::Start(pos));
+	rv
+}
+
+///The `hmtx` table is read lazily as its layout is dependent on `hhea.number_of_h_metrics`
+#[derive(Debug)]
+pub struct HmtxTable{
+	table_absolute: u64,
+	///Length of the table in bytes
+	pub length: u32,
+}
+impl HmtxTable{
+	///The number of glyphs with metrics in this table
+	pub fn num_glyphs(&self, hhea: &HheaTable)->u32{
+		let long = hhea.number_of_h_metrics as u32;
+		long + self.length.saturating_sub(long * 4) / 2
+	}
+	pub fn get_metric<F>(&self, f: &mut F, hhea: &HheaTable, glyph_id: u16)->Result<LongHorMetric, FromFileErr<(),()>> where F: Read, F: Seek{
+		let (advance_width, lsb) = unwrap_or_ret!(read_long_metric(f, self.table_absolute, self.length, hhea.number_of_h_metrics, glyph_id));
+		Ok(LongHorMetric{advance_width, lsb})
+	}
+	pub fn advance_width<F>(&self, f: &mut F, hhea: &HheaTable, glyph_id: u16)->Result<u16, FromFileErr<(),()>> where F: Read, F: Seek{
+		Ok(unwrap_or_ret!(self.get_metric(f, hhea, glyph_id)).advance_width)
+	}
+	pub fn left_side_bearing<F>(&self, f: &mut F, hhea: &HheaTable, glyph_id: u16)->Result<i16, FromFileErr<(),()>> where F: Read, F: Seek{
+		Ok(unwrap_or_ret!(self.get_metric(f, hhea, glyph_id)).lsb)
+	}
+}
+
+#[derive(Debug,FromFile)]
+pub struct VheaTable{
+	///Version number of the vertical header table; 0x00010000 for version 1.0 or 0x00011000 for version 1.1
+	pub version: Fixed,
+	///Version 1.0: Distance in font design units from the centerline to the previous line’s descent. Version 1.1: The vertical typographic ascender for this font.
+	pub ascent: FWord,
+	///Version 1.0: Distance in font design units from the centerline to the next line’s ascent. Version 1.1: The vertical typographic descender for this font.
+	pub descent: FWord,
+	///Reserved; set to 0 in version 1.0. Version 1.1: The vertical typographic gap for this font.
+	pub line_gap: FWord,
+	///The maximum advance height measurement in font design units found in the font. This value must be consistent with the entries in the vertical metrics table.
+	pub advance_height_max: UFWord,
+	///The minimum top sidebearing measurement found in the font, in font design units. This value must be consistent with the entries in the vertical metrics table.
+	pub min_top_side_bearing: FWord,
+	///The minimum bottom sidebearing measurement found in the font, in font design units. This value must be consistent with the entries in the vertical metrics table.
+	pub min_bottom_side_bearing: FWord,
+	///Defined as yMaxExtent = max(tsb + (yMax - yMin)).
+	pub y_max_extent: FWord,
+	///The value of the caretSlopeRise field divided by the value of the caretSlopeRun field determines the slope of the caret. A value of 0 for the rise and a value of 1 for the run specifies a horizontal caret. A value of 1 for the rise and a value of 0 for the run specifies a vertical caret.
+	pub caret_slope_rise: i16,
+	///See the caretSlopeRise field. Value = 1 for nonslanted vertical fonts.
+	pub caret_slope_run: i16,
+	///The amount by which the highlight on a slanted glyph needs to be shifted away from the glyph in order to produce the best appearance. Set value equal to 0 for nonslanted fonts.
+	pub caret_offset: i16,
+	///Set to 0.
+	pub reserved0: i16,
+	///Set to 0.
+	pub reserved1: i16,
+	///Set to 0.
+	pub reserved2: i16,
+	///Set to 0.
+	pub reserved3: i16,
+	///Set to 0.
+	pub metric_data_format: i16,
+	///Number of advance heights in the vertical metrics table.
+	pub num_of_long_ver_metrics: u16,
+}
+
+#[derive(Debug,FromFile)]
+pub struct LongVerMetric{
+	///The advance height of the glyph. Unsigned integer in font design units.
+	pub advance_height: UFWord,
+	///The top sidebearing of the glyph. Signed integer in font design units.
+	pub top_side_bearing: FWord,
+}
+
+///The `vmtx` table is read lazily as its layout is dependent on `vhea.num_of_long_ver_metrics`
+#[derive(Debug)]
+pub struct VmtxTable{
+	table_absolute: u64,
+	///Length of the table in bytes
+	pub length: u32,
+}
+impl VmtxTable{
+	///The number of glyphs with metrics in this table
+	pub fn num_glyphs(&self, vhea: &VheaTable)->u32{
+		let long = vhea.num_of_long_ver_metrics as u32;
+		long + self.length.saturating_sub(long * 4) / 2
+	}
+	pub fn get_metric<F>(&self, f: &mut F, vhea: &VheaTable, glyph_id: u16)->Result<LongVerMetric, FromFileErr<(),()>> where F: Read, F: Seek{
+		let (advance_height, top_side_bearing) = unwrap_or_ret!(read_long_metric(f, self.table_absolute, self.length, vhea.num_of_long_ver_metrics, glyph_id));
+		Ok(LongVerMetric{advance_height, top_side_bearing})
+	}
+	pub fn vertical_advance<F>(&self, f: &mut F, vhea: &VheaTable, glyph_id: u16)->Result<u16, FromFileErr<(),()>> where F: Read, F: Seek{
+		Ok(unwrap_or_ret!(self.get_metric(f, vhea, glyph_id)).advance_height)
+	}
+	pub fn top_side_bearing<F>(&self, f: &mut F, vhea: &VheaTable, glyph_id: u16)->Result<i16, FromFileErr<(),()>> where F: Read, F: Seek{
+		Ok(unwrap_or_ret!(self.get_metric(f, vhea, glyph_id)).top_side_bearing)
+	}
+}
+
+#[derive(Debug,FromFile)]
+pub struct VorgTable{
+	///Major version (starting at 1). Set to 1.
+	pub major_version: u16,
+	///Minor version (starting at 0). Set to 0.
+	pub minor_version: u16,
+	///The y coordinate of a glyph’s vertical origin, in the font’s design coordinate system, to be used if no entry is present for the glyph in the vertOriginYMetrics array.
+	pub default_vert_origin_y: i16,
+	///Number of elements in the vertOriginYMetrics array.
+	pub num_vert_origin_y_metrics: u16,
+	///Array of VertOriginYMetrics records, sorted by glyph ID.
+	#[from_file_count(num_vert_origin_y_metrics)]
+	pub vert_origin_y_metrics: Box<[VertOriginYMetrics]>,
+}
+impl VorgTable{
+	///The y coordinate of the glyph's vertical origin
+	pub fn vertical_origin_y(&self, glyph_id: u16)->i16{
+		match self.vert_origin_y_metrics.binary_search_by_key(&glyph_id, |m|m.glyph_index){
+			Ok(i) => self.vert_origin_y_metrics[i].vert_origin_y,
+			Err(_) => self.default_vert_origin_y,
+		}
+	}
+}
+#[derive(Debug,FromFile)]
+pub struct VertOriginYMetrics{
+	///Glyph index.
+	pub glyph_index: u16,
+	///Y coordinate, in the font’s design coordinate system, of the glyph’s vertical origin.
+	pub vert_origin_y: i16,
+}
+
+///16-bit signed fixed number with the low 14 bits of fraction (2.14).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct F2Dot14(pub i16);
+impl F2Dot14{
+	pub fn to_f32(&self)->f32{self.0 as f32 / 16384.0}
+}
+impl_from_file!(F2Dot14, (), (), f, {Ok(Self(unwrap_or_ret!(i16::from_file(f))))});
+
+///The `loca` table is read lazily as its format is dependent on `head.index_to_loc_format`
+#[derive(Debug)]
+pub struct LocaTable{
+	table_absolute: u64,
+	///Length of the table in bytes
+	pub length: u32,
+}
+impl LocaTable{
+	///The number of glyphs described by this table (this is one less than the number of offsets)
+	pub fn num_glyphs(&self, head: &HeadTable)->u32{
+		(self.length / if head.long_loca_offsets(){4}else{2}).saturating_sub(1)
+	}
+	fn get_offset<F>(&self, f: &mut F, head: &HeadTable, index: u32)->Result<u32, FromFileErr<(),()>> where F: Read, F: Seek{
+		let long = head.long_loca_offsets();
+		let pos = self.table_absolute + index as u64 * if long{4}else{2};
+		if f.seek(SeekFrom::Start(pos)).is_err(){return Err(FromFileErr::EOF);}
+		// short offsets are stored divided by two
+		Ok(if long{unwrap_or_ret!(u32::from_file(f))}else{unwrap_or_ret!(u16::from_file(f)) as u32 * 2})
+	}
+	///The (offset, length) of the glyph in the `glyf` table or `None` if the glyph id is out of range
+	pub fn glyph_range<F>(&self, f: &mut F, head: &HeadTable, glyph_id: u16)->Result<Option<(u32, u32)>, FromFileErr<(),()>> where F: Read, F: Seek{
+		if glyph_id as u32 >= self.num_glyphs(head){return Ok(None);}
+		let pos = f.stream_position().unwrap();
+		let start = self.get_offset(f, head, glyph_id as u32);
+		let end = self.get_offset(f, head, glyph_id as u32 + 1);
+		let _ = f.seek(SeekFrom::Start(pos));
+		let (start, end) = (unwrap_or_ret!(start), unwrap_or_ret!(end));
+		if end < start{return Err(FromFileErr::InvalidData(()));}
+		Ok(Some((start, end - start)))
+	}
+}
+
+///The `glyf` table is read lazily, one glyph at a time, using the offsets in the `loca` table
+#[derive(Debug)]
+pub struct GlyfTable{
+	table_absolute: u64,
+	///Length of the table in bytes
+	pub length: u32,
+}
+impl GlyfTable{
+	///The maximum depth of nested composite glyphs that will be resolved
+	pub const MAX_COMPONENT_DEPTH: usize = 64;
+
+	///Reads the glyph with id `glyph_id`, `None` is returned for glyphs without any outline (such as the space glyph)
+	pub fn get_glyph<F>(&self, f: &mut F, loca: &LocaTable, head: &HeadTable, glyph_id: u16)->Result<Option<Glyph>, FromFileErr<(),()>> where F: Read, F: Seek{
+		let (offset, length) = match unwrap_or_ret!(loca.glyph_range(f, head, glyph_id)){
+			Some(v) => v,
+			None => return Err(FromFileErr::InvalidData(())),
+		};
+		if length == 0{return Ok(None);}
+		if offset as u64 + length as u64 > self.length as u64{return Err(FromFileErr::InvalidData(()));}
+		let pos = f.stream_position().unwrap();
+		if f.seek(SeekFrom::Start(self.table_absolute + offset as u64)).is_err()
+		{return Err(FromFileErr::EOF);}
+		let rv = Glyph::from_file(f);
+		let _ = f.seek(SeekFrom::Start(pos));
+		rv.map(Some)
+	}
+
+	///Reads the glyph and resolves any components giving the points of every contour in font units
+	pub fn get_contours<F>(&self, f: &mut F, loca: &LocaTable, head: &HeadTable, glyph_id: u16)->Result<GlyphContours, FromFileErr<(),()>> where F: Read, F: Seek{
+		self.get_contours_inner(f, loca, head, glyph_id, &mut Vec::new())
+	}
+	fn get_contours_inner<F>(&self, f: &mut F, loca: &LocaTable, head: &HeadTable, glyph_id: u16, stack: &mut Vec<u16>)->Result<GlyphContours, FromFileErr<(),()>> where F: Read, F: Seek{
+		// a glyph that (indirectly) contains itself can never be resolved
+		if stack.contains(&glyph_id) || stack.len() >= Self::MAX_COMPONENT_DEPTH
+		{return Err(FromFileErr::InvalidData(()));}
+		let mut rv = GlyphContours{points: Vec::new(), end_pts_of_contours: Vec::new()};
+		let glyph = match unwrap_or_ret!(self.get_glyph(f, loca, head, glyph_id)){
+			Some(g) => g,
+			None => return Ok(rv),
+		};
+		match glyph.data{
+			GlyphData::Simple(simple) => {
+				rv.points = simple.points.iter().map(
+					|p|ContourPoint{x: p.x as f32, y: p.y as f32, on_curve: p.on_curve}
+				).collect();
+				rv.end_pts_of_contours = simple.end_pts_of_contours.into();
+			},
+			GlyphData::Composite(composite) => {
+				stack.push(glyph_id);
+				for component in composite.components.iter(){
+					let child = unwrap_or_ret!(self.get_contours_inner(f, loca, head, component.glyph_index, stack));
+					let transform = Transform{
+						xx: component.transform[0],
+						xy: component.transform[1],
+						yx: component.transform[2],
+						yy: component.transform[3],
+						dx: 0.0,
+						dy: 0.0,
+					};
+					let child_points: Vec<Point> = child.points.iter().map(|p|transform.apply(Point::new(p.x, p.y))).collect();
+					let (dx, dy) = if component.flags & GlyphComponent::ARGS_ARE_XY_VALUES != 0{
+						let offset = Point::new(component.argument1 as f32, component.argument2 as f32);
+						let offset = if component.flags & GlyphComponent::SCALED_COMPONENT_OFFSET != 0
+						&& component.flags & GlyphComponent::UNSCALED_COMPONENT_OFFSET == 0
+						{transform.apply(offset)}else{offset};
+						if component.flags & GlyphComponent::ROUND_XY_TO_GRID != 0
+						{(offset.x.round(), offset.y.round())}else{(offset.x, offset.y)}
+					}else{
+						// point matching: align a point of the glyph so far with a point of the component
+						let parent = match rv.points.get(component.argument1 as usize){
+							Some(p) => *p,
+							None => return Err(FromFileErr::InvalidData(())),
+						};
+						let child_point = match child_points.get(component.argument2 as usize){
+							Some(p) => *p,
+							None => return Err(FromFileErr::InvalidData(())),
+						};
+						(parent.x - child_point.x, parent.y - child_point.y)
+					};
+					let base = rv.points.len();
+					// the last point of every contour has to stay addressable by a u16
+					if base + child.points.len() > u16::MAX as usize + 1{return Err(FromFileErr::InvalidData(()));}
+					rv.points.extend(child_points.iter().zip(child.points.iter()).map(
+						|(p, original)|ContourPoint{x: p.x + dx, y: p.y + dy, on_curve: original.on_curve}
+					));
+					rv.end_pts_of_contours.extend(child.end_pts_of_contours.iter().map(|e|(*e as usize + base) as u16));
+				}
+				stack.pop();
+			},
+		}
+		Ok(rv)
+	}
+
+	///Reads the glyph's outline with all components resolved
+	pub fn get_outline<F>(&self, f: &mut F, loca: &LocaTable, head: &HeadTable, glyph_id: u16)->Result<Outline, FromFileErr<(),()>> where F: Read, F: Seek{
+		Ok(unwrap_or_ret!(self.get_contours(f, loca, head, glyph_id)).to_outline())
+	}
+}
+
+///The points of a glyph with all of its components resolved
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphContours{
+	pub points: Vec<ContourPoint>,
+	///The index of the last point of each contour
+	pub end_pts_of_contours: Vec<u16>,
+}
+impl GlyphContours{
+	pub fn to_outline(&self)->Outline{Outline::from_contours(&self.points, &self.end_pts_of_contours)}
+}
+
+#[derive(Debug)]
+pub struct Glyph{
+	pub header: GlyphHeader,
+	pub data: GlyphData,
+}
+impl_from_file!(Glyph, (), (), f, {
+	let header = unwrap_or_ret!(GlyphHeader::from_file(f));
+	let data = if header.number_of_contours >= 0{
+		GlyphData::Simple(unwrap_or_ret!(SimpleGlyph::from_file_with_contours(f, header.number_of_contours as u16)))
+	}else{
+		GlyphData::Composite(unwrap_or_ret!(CompositeGlyph::from_file(f)))
+	};
+	Ok(Self{header, data})
+});
+
+#[derive(Debug,FromFile)]
+pub struct GlyphHeader{
+	///If the number of contours is greater than or equal to zero, this is a simple glyph. If negative, this is a composite glyph — the value -1 should be used for composite glyphs.
+	pub number_of_contours: i16,
+	///Minimum x for coordinate data.
+	pub x_min: i16,
+	///Minimum y for coordinate data.
+	pub y_min: i16,
+	///Maximum x for coordinate data.
+	pub x_max: i16,
+	///Maximum y for coordinate data.
+	pub y_max: i16,
+}
+
+#[derive(Debug)]
+pub enum GlyphData{
+	Simple(SimpleGlyph),
+	Composite(CompositeGlyph),
+}
+
+#[derive(Debug)]
+pub struct SimpleGlyph{
+	///Array of point indices for the last point of each contour, in increasing numeric order.
+	pub end_pts_of_contours: Box<[u16]>,
+	///Total number of bytes for instructions. If instructionLength is zero, no instructions are present for this glyph, and this field is followed directly by the flags field.
+	pub instruction_length: u16,
+	///Array of instruction byte code for the glyph.
+	pub instructions: Box<[u8]>,
+	///Array of flag elements with repeated flags expanded (one per point).
+	pub flags: Box<[u8]>,
+	///The points with their delta encoded coordinates resolved to absolute coordinates
+	pub points: Box<[GlyphPoint]>,
+}
+impl SimpleGlyph{
+	///Bit 0: If set, the point is on the curve; otherwise, it is off the curve.
+	pub const ON_CURVE_POINT: u8 = 0x01;
+	///Bit 1: If set, the corresponding x-coordinate is 1 byte long, and the sign is determined by the X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR flag. If not set, its interpretation depends on the X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR flag: If that other flag is set, the x-coordinate is the same as the previous x-coordinate, and no element is added to the xCoordinates array. If both flags are not set, the corresponding element in the xCoordinates array is two bytes and interpreted as a signed integer.
+	pub const X_SHORT_VECTOR: u8 = 0x02;
+	///Bit 2: If set, the corresponding y-coordinate is 1 byte long, and the sign is determined by the Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR flag. If not set, its interpretation depends on the Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR flag: If that other flag is set, the y-coordinate is the same as the previous y-coordinate, and no element is added to the yCoordinates array. If both flags are not set, the corresponding element in the yCoordinates array is two bytes and interpreted as a signed integer.
+	pub const Y_SHORT_VECTOR: u8 = 0x04;
+	///Bit 3: If set, the next byte (read as unsigned) specifies the number of additional times this flag byte is to be repeated in the logical flags array — that is, the number of additional logical flag entries inserted after this entry.
+	pub const REPEAT_FLAG: u8 = 0x08;
+	///Bit 4: This flag has two meanings, depending on how the X_SHORT_VECTOR flag is set. If X_SHORT_VECTOR is set, this bit describes the sign of the value, with 1 equalling positive and 0 negative. If X_SHORT_VECTOR is not set and this bit is set, then the current x-coordinate is the same as the previous x-coordinate. If X_SHORT_VECTOR is not set and this bit is also not set, the current x-coordinate is a signed 16-bit delta vector.
+	pub const X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR: u8 = 0x10;
+	///Bit 5: This flag has two meanings, depending on how the Y_SHORT_VECTOR flag is set. If Y_SHORT_VECTOR is set, this bit describes the sign of the value, with 1 equalling positive and 0 negative. If Y_SHORT_VECTOR is not set and this bit is set, then the current y-coordinate is the same as the previous y-coordinate. If Y_SHORT_VECTOR is not set and this bit is also not set, the current y-coordinate is a signed 16-bit delta vector.
+	pub const Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR: u8 = 0x20;
+	///Bit 6: If set, contours in the glyph description may overlap.
+	pub const OVERLAP_SIMPLE: u8 = 0x40;
+
+	pub fn from_file_with_contours<F>(f: &mut F, number_of_contours: u16)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		let end_pts_of_contours: Box<[u16]> = unwrap_or_ret!(array_from_file(f, number_of_contours as usize));
+		let num_points = match end_pts_of_contours.last(){
+			Some(last) => *last as usize + 1,
+			None => 0,
+		};
+		let instruction_length = unwrap_or_ret!(u16::from_file(f));
+		let instructions = unwrap_or_ret!(array_from_file(f, instruction_length as usize));
+
+		let mut flags = Vec::with_capacity(num_points);
+		while flags.len() < num_points{
+			let flag = unwrap_or_ret!(u8::from_file(f));
+			flags.push(flag);
+			if flag & Self::REPEAT_FLAG != 0{
+				for _ in 0..unwrap_or_ret!(u8::from_file(f)){flags.push(flag);}
+			}
+		}
+		// a repeat count can overrun the number of points
+		flags.truncate(num_points);
+
+		let mut xs = Vec::with_capacity(num_points);
+		let mut x = 0i16;
+		for flag in flags.iter(){
+			x = x.wrapping_add(unwrap_or_ret!(Self::read_coordinate(
+				f, *flag, Self::X_SHORT_VECTOR, Self::X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR
+			)));
+			xs.push(x);
+		}
+		let mut points = Vec::with_capacity(num_points);
+		let mut y = 0i16;
+		for (flag, x) in flags.iter().zip(xs){
+			y = y.wrapping_add(unwrap_or_ret!(Self::read_coordinate(
+				f, *flag, Self::Y_SHORT_VECTOR, Self::Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR
+			)));
+			points.push(GlyphPoint{x, y, on_curve: flag & Self::ON_CURVE_POINT != 0});
+		}
+		Ok(Self{
+			end_pts_of_contours,
+			instruction_length,
+			instructions,
+			flags: flags.into(),
+			points: points.into(),
+		})
+	}
+	fn read_coordinate<F>(f: &mut F, flag: u8, short: u8, same_or_positive: u8)->Result<i16, FromFileErr<(),()>> where F: Read, F: Seek{
+		Ok(if flag & short != 0{
+			let v = unwrap_or_ret!(u8::from_file(f)) as i16;
+			if flag & same_or_positive != 0{v}else{-v}
+		}else if flag & same_or_positive != 0{0}else{
+			unwrap_or_ret!(i16::from_file(f))
+		})
+	}
+	pub fn to_outline(&self)->Outline{
+		let points: Vec<ContourPoint> = self.points.iter().map(
+			|p|ContourPoint{x: p.x as f32, y: p.y as f32, on_curve: p.on_curve}
+		).collect();
+		Outline::from_contours(&points, &self.end_pts_of_contours)
+	}
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GlyphPoint{
+	pub x: i16,
+	pub y: i16,
+	pub on_curve: bool,
+}
+
+#[derive(Debug)]
+pub struct CompositeGlyph{
+	pub components: Box<[GlyphComponent]>,
+	///Instructions for the composite glyph (present if any component has WE_HAVE_INSTRUCTIONS set)
+	pub instructions: Option<Box<[u8]>>,
+}
+impl_from_file!(CompositeGlyph, (), (), f, {
+	let mut components = Vec::new();
+	let mut have_instructions = false;
+	loop{
+		let component = unwrap_or_ret!(GlyphComponent::from_file(f));
+		let more = component.flags & GlyphComponent::MORE_COMPONENTS != 0;
+		have_instructions |= component.flags & GlyphComponent::WE_HAVE_INSTRUCTIONS != 0;
+		components.push(component);
+		if !more{break;}
+	}
+	let instructions = if have_instructions{
+		let length = unwrap_or_ret!(u16::from_file(f));
+		Some(unwrap_or_ret!(array_from_file(f, length as usize)))
+	}else{None};
+	Ok(Self{components: components.into(), instructions})
+});
+
+#[derive(Debug)]
+pub struct GlyphComponent{
+	///component flag
+	pub flags: u16,
+	///glyph index of component
+	pub glyph_index: u16,
+	///x-offset for component or point number; type depends on bits 0 and 1 in component flags
+	pub argument1: i32,
+	///y-offset for component or point number; type depends on bits 0 and 1 in component flags
+	pub argument2: i32,
+	///The 2x2 matrix as [xscale, scale01, scale10, yscale] (the identity if no scale is present)
+	pub transform: [f32; 4],
+}
+impl GlyphComponent{
+	///Bit 0: If this is set, the arguments are 16-bit (uint16 or int16); otherwise, they are bytes (uint8 or int8).
+	pub const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+	///Bit 1: If this is set, the arguments are signed xy values; otherwise, they are unsigned point numbers.
+	pub const ARGS_ARE_XY_VALUES: u16 = 0x0002;
+	///Bit 2: If set and ARGS_ARE_XY_VALUES is also set, the xy values are rounded to the nearest grid line. Ignored if ARGS_ARE_XY_VALUES is not set.
+	pub const ROUND_XY_TO_GRID: u16 = 0x0004;
+	///Bit 3: This indicates that there is a simple scale for the component. Otherwise, scale = 1.0.
+	pub const WE_HAVE_A_SCALE: u16 = 0x0008;
+	///Bit 5: Indicates at least one more glyph after this one.
+	pub const MORE_COMPONENTS: u16 = 0x0020;
+	///Bit 6: The x direction will use a different scale from the y direction.
+	pub const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+	///Bit 7: There is a 2 by 2 transformation that will be used to scale the component.
+	pub const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+	///Bit 8: Following the last component are instructions for the composite glyph.
+	pub const WE_HAVE_INSTRUCTIONS: u16 = 0x0100;
+	///Bit 9: If set, this forces the aw and lsb (and rsb) for the composite to be equal to those from this component glyph. This works for hinted and unhinted glyphs.
+	pub const USE_MY_METRICS: u16 = 0x0200;
+	///Bit 10: If set, the components of the compound glyph overlap.
+	pub const OVERLAP_COMPOUND: u16 = 0x0400;
+	///Bit 11: The composite is designed to have the component offset scaled. Ignored if ARGS_ARE_XY_VALUES is not set.
+	pub const SCALED_COMPONENT_OFFSET: u16 = 0x0800;
+	///Bit 12: The composite is designed not to have the component offset scaled. Ignored if ARGS_ARE_XY_VALUES is not set.
+	pub const UNSCALED_COMPONENT_OFFSET: u16 = 0x1000;
+}
+impl_from_file!(GlyphComponent, (), (), f, {
+	let flags = unwrap_or_ret!(u16::from_file(f));
+	let glyph_index = unwrap_or_ret!(u16::from_file(f));
+	let signed = flags & Self::ARGS_ARE_XY_VALUES != 0;
+	let (argument1, argument2) = match (flags & Self::ARG_1_AND_2_ARE_WORDS != 0, signed){
+		(true, true) => (unwrap_or_ret!(i16::from_file(f)) as i32, unwrap_or_ret!(i16::from_file(f)) as i32),
+		(true, false) => (unwrap_or_ret!(u16::from_file(f)) as i32, unwrap_or_ret!(u16::from_file(f)) as i32),
+		(false, true) => (unwrap_or_ret!(i8::from_file(f)) as i32, unwrap_or_ret!(i8::from_file(f)) as i32),
+		(false, false) => (unwrap_or_ret!(u8::from_file(f)) as i32, unwrap_or_ret!(u8::from_file(f)) as i32),
+	};
+	let transform = if flags & Self::WE_HAVE_A_SCALE != 0{
+		let scale = unwrap_or_ret!(F2Dot14::from_file(f)).to_f32();
+		[scale, 0.0, 0.0, scale]
+	}else if flags & Self::WE_HAVE_AN_X_AND_Y_SCALE != 0{
+		let x_scale = unwrap_or_ret!(F2Dot14::from_file(f)).to_f32();
+		[x_scale, 0.0, 0.0, unwrap_or_ret!(F2Dot14::from_file(f)).to_f32()]
+	}else if flags & Self::WE_HAVE_A_TWO_BY_TWO != 0{[
+		unwrap_or_ret!(F2Dot14::from_file(f)).to_f32(),
+		unwrap_or_ret!(F2Dot14::from_file(f)).to_f32(),
+		unwrap_or_ret!(F2Dot14::from_file(f)).to_f32(),
+		unwrap_or_ret!(F2Dot14::from_file(f)).to_f32(),
+	]}else{[1.0, 0.0, 0.0, 1.0]};
+	Ok(Self{flags, glyph_index, argument1, argument2, transform})
+});
+
+///Variation data used to compute adjustments for variable fonts (shared by `CFF2`, `GDEF`, `HVAR` and others)
+#[derive(Debug)]
+pub struct ItemVariationStore{
+	///Format — set to 1
+	pub format: u16,
+	///Offset in bytes from the start of the item variation store to the variation region list.
+	pub variation_region_list_offset: Offset32,
+	///The number of item variation data subtables.
+	pub item_variation_data_count: u16,
+	///Offsets in bytes from the start of the item variation store to each item variation data subtable.
+	pub item_variation_data_offsets: Box<[Offset32]>,
+	pub variation_region_list: VariationRegionList,
+	pub item_variation_data: Box<[ItemVariationData]>,
+}
+impl_from_file!(ItemVariationStore, (), (), f, {
+	let start = f.stream_position().unwrap();
+	let format = unwrap_or_ret!(u16::from_file(f));
+	if format != 1{return Err(FromFileErr::InvalidData(()));}
+	let variation_region_list_offset = unwrap_or_ret!(Offset32::from_file(f));
+	let item_variation_data_count = unwrap_or_ret!(u16::from_file(f));
+	let item_variation_data_offsets: Box<[Offset32]> = unwrap_or_ret!(array_from_file(f, item_variation_data_count as usize));
+	if f.seek(SeekFrom::Start(start + variation_region_list_offset as u64)).is_err()
+	{return Err(FromFileErr::EOF);}
+	let variation_region_list = unwrap_or_ret!(VariationRegionList::from_file(f));
+	let mut item_variation_data = Vec::with_capacity(item_variation_data_offsets.len());
+	for offset in item_variation_data_offsets.iter(){
+		if f.seek(SeekFrom::Start(start + *offset as u64)).is_err(){return Err(FromFileErr::EOF);}
+		item_variation_data.push(unwrap_or_ret!(ItemVariationData::from_file(f)));
+	}
+	Ok(Self{
+		format,
+		variation_region_list_offset,
+		item_variation_data_count,
+		item_variation_data_offsets,
+		variation_region_list,
+		item_variation_data: item_variation_data.into(),
+	})
+});
+impl ItemVariationStore{
+	///The scalar for a region at the normalized coordinates `coords` (missing coordinates are treated as 0)
+	pub fn region_scalar(&self, region: u16, coords: &[f32])->f32{
+		let region = match self.variation_region_list.variation_regions.get(region as usize){
+			Some(r) => r,
+			None => return 0.0,
+		};
+		let mut scalar = 1.0;
+		for (i, axis) in region.region_axes.iter().enumerate(){
+			let (start, peak, end) = (axis.start_coord.to_f32(), axis.peak_coord.to_f32(), axis.end_coord.to_f32());
+			let coord = coords.get(i).copied().unwrap_or(0.0);
+			// invalid or axis independent regions don't affect the scalar
+			if start > peak || peak > end || (start < 0.0 && end > 0.0 && peak != 0.0) || peak == 0.0{continue;}
+			if coord < start || coord > end{return 0.0;}
+			if coord == peak{continue;}
+			scalar *= if coord < peak{(coord - start) / (peak - start)}else{(end - coord) / (end - peak)};
+		}
+		scalar
+	}
+	///The scalar of each region referenced by the item variation data subtable `outer`
+	pub fn scalars(&self, outer: u16, coords: &[f32])->Option<Vec<f32>>{
+		let data = self.item_variation_data.get(outer as usize)?;
+		Some(data.region_indexes.iter().map(|r|self.region_scalar(*r, coords)).collect())
+	}
+	///The interpolated delta for the item `inner` in the subtable `outer`
+	pub fn delta(&self, outer: u16, inner: u16, coords: &[f32])->f32{
+		let data = match self.item_variation_data.get(outer as usize){
+			Some(d) => d,
+			None => return 0.0,
+		};
+		let deltas = match data.delta_sets.get(inner as usize){
+			Some(d) => d,
+			None => return 0.0,
+		};
+		deltas.iter().zip(data.region_indexes.iter())
+			.map(|(delta, region)|*delta as f32 * self.region_scalar(*region, coords)).sum()
+	}
+}
+#[derive(Debug)]
+pub struct VariationRegionList{
+	///The number of variation axes for this font. This must be the same number as axisCount in the 'fvar' table.
+	pub axis_count: u16,
+	///The number of variation region tables in the variation region list. Must be less than 32,768.
+	pub region_count: u16,
+	///Array of variation regions.
+	pub variation_regions: Box<[VariationRegion]>,
+}
+impl_from_file!(VariationRegionList, (), (), f, {
+	let axis_count = unwrap_or_ret!(u16::from_file(f));
+	let region_count = unwrap_or_ret!(u16::from_file(f));
+	let mut variation_regions = Vec::with_capacity(region_count as usize);
+	for _ in 0..region_count{
+		variation_regions.push(VariationRegion{region_axes: unwrap_or_ret!(array_from_file(f, axis_count as usize))});
+	}
+	Ok(Self{axis_count, region_count, variation_regions: variation_regions.into()})
+});
+#[derive(Debug)]
+pub struct VariationRegion{
+	///Array of region axis coordinates records, in the order of axes given in the 'fvar' table.
+	pub region_axes: Box<[RegionAxisCoordinates]>,
+}
+#[derive(Debug,FromFile)]
+pub struct RegionAxisCoordinates{
+	///The region start coordinate value for the current axis.
+	pub start_coord: F2Dot14,
+	///The region peak coordinate value for the current axis.
+	pub peak_coord: F2Dot14,
+	///The region end coordinate value for the current axis.
+	pub end_coord: F2Dot14,
+}
+#[derive(Debug)]
+pub struct ItemVariationData{
+	///The number of delta sets for distinct items.
+	pub item_count: u16,
+	///A packed field: the high bit is a flag (LONG_WORDS) and the low 15 bits are the count of "word" deltas
+	pub word_delta_count: u16,
+	///The number of variation regions referenced.
+	pub region_index_count: u16,
+	///Array of indices into the variation region list for the regions referenced by this item variation data table.
+	pub region_indexes: Box<[u16]>,
+	///Delta-set rows with every delta widened to 32 bits.
+	pub delta_sets: Box<[Box<[i32]>]>,
+}
+impl ItemVariationData{
+	///Flag indicating that "word" deltas are long (int32)
+	pub const LONG_WORDS: u16 = 0x8000;
+	///Count of "word" deltas
+	pub const WORD_DELTA_COUNT_MASK: u16 = 0x7FFF;
+}
+impl_from_file!(ItemVariationData, (), (), f, {
+	let item_count = unwrap_or_ret!(u16::from_file(f));
+	let word_delta_count = unwrap_or_ret!(u16::from_file(f));
+	let region_index_count = unwrap_or_ret!(u16::from_file(f));
+	let region_indexes = unwrap_or_ret!(array_from_file(f, region_index_count as usize));
+	let long = word_delta_count & Self::LONG_WORDS != 0;
+	let words = (word_delta_count & Self::WORD_DELTA_COUNT_MASK) as usize;
+	let mut delta_sets = Vec::with_capacity(item_count as usize);
+	for _ in 0..item_count{
+		let mut row = Vec::with_capacity(region_index_count as usize);
+		for i in 0..region_index_count as usize{row.push(match (i < words, long){
+			(true, true) => unwrap_or_ret!(i32::from_file(f)),
+			(true, false) | (false, true) => unwrap_or_ret!(i16::from_file(f)) as i32,
+			(false, false) => unwrap_or_ret!(i8::from_file(f)) as i32,
+		});}
+		delta_sets.push(row.into());
+	}
+	Ok(Self{
+		item_count,
+		word_delta_count,
+		region_index_count,
+		region_indexes,
+		delta_sets: delta_sets.into(),
+	})
+});
+
+///A reader which reports the end of the file once `left` bytes have been read
+struct ReadBudget<'a, F>{
+	f: &'a mut F,
+	left: u64,
+}
+impl<F> Read for ReadBudget<'_, F> where F: Read{
+	fn read(&mut self, buf: &mut [u8])->std::io::Result<usize>{
+		let len = (buf.len() as u64).min(self.left) as usize;
+		let n = unwrap_or_ret!(self.f.read(&mut buf[..len]));
+		self.left -= n as u64;
+		Ok(n)
+	}
+}
+impl<F> Seek for ReadBudget<'_, F> where F: Seek{
+	fn seek(&mut self, pos: SeekFrom)->std::io::Result<u64>{self.f.seek(pos)}
+}
+
+///Parses a `T` at `base + offset`
+fn from_file_at<F, T>(f: &mut F, base: u64, offset: u32)->Result<T, FromFileErr<(),()>> where
+	F: Read,
+	F: Seek,
+	T: FromFile<(),()>,
+{
+	if f.seek(SeekFrom::Start(base + offset as u64)).is_err(){return Err(FromFileErr::EOF);}
+	T::from_file(f)
+}
+///Reads a uint16 count followed by that many `T`s
+fn counted_array_from_file<F, T>(f: &mut F)->Result<Box<[T]>, FromFileErr<(),()>> where
+	F: Read,
+	F: Seek,
+	T: FromFile<(),()>,
+{
+	let count = unwrap_or_ret!(u16::from_file(f));
+	array_from_file(f, count as usize)
+}
+///Reads a uint16 count followed by that many Offset16s (from `base`) and parses the `T` each points to
+fn offset16_array_from_file<F, T>(f: &mut F, base: u64)->Result<Box<[T]>, FromFileErr<(),()>> where
+	F: Read,
+	F: Seek,
+	T: FromFile<(),()>,
+{
+	let offsets: Box<[Offset16]> = unwrap_or_ret!(counted_array_from_file(f));
+	let mut rv = Vec::with_capacity(offsets.len());
+	for offset in offsets.iter(){rv.push(unwrap_or_ret!(from_file_at(f, base, *offset as u32)));}
+	Ok(rv.into())
+}
+///Like `offset16_array_from_file` but NULL offsets are allowed and give `None`
+fn optional_offset16_array_from_file<F, T>(f: &mut F, base: u64)->Result<Box<[Option<T>]>, FromFileErr<(),()>> where
+	F: Read,
+	F: Seek,
+	T: FromFile<(),()>,
+{
+	let offsets: Box<[Offset16]> = unwrap_or_ret!(counted_array_from_file(f));
+	let mut rv = Vec::with_capacity(offsets.len());
+	for offset in offsets.iter(){rv.push(match offset{
+		0 => None,
+		o => Some(unwrap_or_ret!(from_file_at(f, base, *o as u32))),
+	});}
+	Ok(rv.into())
+}
+///Parses the coverage tables pointed to by `offsets` (from `base`)
+fn coverages_from_file<F>(f: &mut F, base: u64, offsets: &[Offset16])->Result<Box<[Coverage]>, FromFileErr<(),()>> where F: Read, F: Seek{
+	let mut rv = Vec::with_capacity(offsets.len());
+	for offset in offsets.iter(){rv.push(unwrap_or_ret!(from_file_at(f, base, *offset as u32)));}
+	Ok(rv.into())
+}
+
+///The header and lists shared by the `GSUB` and `GPOS` tables where `T` is the lookup subtable type
+#[derive(Debug)]
+pub struct LayoutTable<T>{
+	///Major version of the table, = 1
+	pub major_version: u16,
+	///Minor version of the table, = 0 or 1
+	pub minor_version: u16,
+	///Offset to ScriptList table, from beginning of the table.
+	pub script_list_offset: Offset16,
+	///Offset to FeatureList table, from beginning of the table.
+	pub feature_list_offset: Offset16,
+	///Offset to LookupList table, from beginning of the table.
+	pub lookup_list_offset: Offset16,
+	///Version (>= 1.1) Offset to FeatureVariations table, from beginning of the table (may be NULL).
+	pub feature_variations_offset: Option<Offset32>,
+	pub script_list: ScriptList,
+	pub feature_list: FeatureList,
+	pub lookup_list: LookupList<T>,
+}
+impl<T> FromFile<(),()> for LayoutTable<T> where T: LookupSubtable{
+	fn from_file<F>(f: &mut F)->Result<Self, FromFileErr<(),()>> where Self: Sized, F: Read, F: Seek{
+		let length = bytes_left(f).min(u32::MAX as u64) as u32;
+		Self::from_file_with_length(f, length)
+	}
+}
+impl<T> LayoutTable<T> where T: LookupSubtable{
+	///At most this many times the table's length is read while parsing it, subtables can be shared by any number of offsets so a small table could otherwise expand without limit
+	pub const READ_FACTOR: u64 = 32;
+	///The fewest bytes a table is allowed to read however short it is
+	pub const MIN_READ_BUDGET: u64 = 1 << 20;
+	///Parses a table of `length` bytes
+	pub fn from_file_with_length<F>(f: &mut F, length: u32)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		let mut budget = ReadBudget{f, left: (length as u64 * Self::READ_FACTOR).max(Self::MIN_READ_BUDGET)};
+		let rv = Self::from_file_unbounded(&mut budget);
+		if budget.left == 0{return Err(FromFileErr::InvalidData(()));}
+		rv
+	}
+	fn from_file_unbounded<F>(f: &mut F)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		let start = f.stream_position().unwrap();
+		let major_version = unwrap_or_ret!(u16::from_file(f));
+		let minor_version = unwrap_or_ret!(u16::from_file(f));
+		if major_version != 1{return Err(FromFileErr::InvalidData(()));}
+		let script_list_offset = unwrap_or_ret!(Offset16::from_file(f));
+		let feature_list_offset = unwrap_or_ret!(Offset16::from_file(f));
+		let lookup_list_offset = unwrap_or_ret!(Offset16::from_file(f));
+		let feature_variations_offset = if minor_version >= 1{Some(unwrap_or_ret!(Offset32::from_file(f)))}else{None};
+		// the lists may be NULL in fonts which have no layout data
+		let script_list = match script_list_offset{
+			0 => ScriptList{script_records: [].into(), scripts: [].into()},
+			o => unwrap_or_ret!(from_file_at(f, start, o as u32)),
+		};
+		let feature_list = match feature_list_offset{
+			0 => FeatureList{feature_records: [].into(), features: [].into()},
+			o => unwrap_or_ret!(from_file_at(f, start, o as u32)),
+		};
+		let lookup_list = match lookup_list_offset{
+			0 => LookupList{lookup_offsets: [].into(), lookups: [].into()},
+			o => unwrap_or_ret!(from_file_at(f, start, o as u32)),
+		};
+		Ok(Self{
+			major_version,
+			minor_version,
+			script_list_offset,
+			feature_list_offset,
+			lookup_list_offset,
+			feature_variations_offset,
+			script_list,
+			feature_list,
+			lookup_list,
+		})
+	}
+}
+impl<T> LayoutTable<T>{
+	///The indices of the lookups to apply (in the order they should be applied) for the features `features` in the language system for `script` and `language` (see `ScriptList::lang_sys` for the fallbacks used).
+	///The required feature of the language system is always included.
+	pub fn lookup_indices(&self, script: &str, language: Option<&str>, features: &[&str])->Vec<u16>{
+		match self.script_list.lang_sys(script, language){
+			Some(lang_sys) => self.feature_list.lookup_indices(lang_sys, features),
+			None => Vec::new(),
+		}
+	}
+	///The lookups to apply (in order, along with their index) for the features `features` for `script` and `language`
+	pub fn lookups(&self, script: &str, language: Option<&str>, features: &[&str])->Vec<(u16, &Lookup<T>)>{
+		self.lookup_indices(script, language, features).into_iter()
+			.filter_map(|i|self.lookup_list.lookups.get(i as usize).map(|l|(i, l))).collect()
+	}
+}
+
+#[derive(Debug)]
+pub struct ScriptList{
+	///Array of ScriptRecords, listed alphabetically by script tag
+	pub script_records: Box<[ScriptRecord]>,
+	///The script table pointed to by each script record
+	pub scripts: Box<[Script]>,
+}
+impl_from_file!(ScriptList, (), (), f, {
+	let start = f.stream_position().unwrap();
+	let script_records: Box<[ScriptRecord]> = unwrap_or_ret!(counted_array_from_file(f));
+	let mut scripts = Vec::with_capacity(script_records.len());
+	for record in script_records.iter(){scripts.push(unwrap_or_ret!(from_file_at(f, start, record.script_offset as u32)));}
+	Ok(Self{script_records, scripts: scripts.into()})
+});
+impl ScriptList{
+	///The script with the tag `script`
+	pub fn script(&self, script: &str)->Option<&Script>{
+		self.script_records.iter().position(|r|r.script_tag.data == script).map(|i|&self.scripts[i])
+	}
+	///The language system for `script` and `language`.
+	///If the script isn't present the `DFLT` script is used and if the language isn't present (or is `None`) the script's default language system is used.
+	pub fn lang_sys(&self, script: &str, language: Option<&str>)->Option<&LangSys>{
+		self.script(script).or_else(||self.script("DFLT"))?.lang_sys(language)
+	}
+}
+#[derive(Debug,FromFile)]
+pub struct ScriptRecord{
+	///4-byte script tag identifier
+	pub script_tag: Tag,
+	///Offset to Script table, from beginning of ScriptList
+	pub script_offset: Offset16,
+}
+#[derive(Debug)]
+pub struct Script{
+	///Offset to default LangSys table, from beginning of Script table — may be NULL
+	pub default_lang_sys_offset: Offset16,
+	///Array of LangSysRecords, listed alphabetically by LangSys tag
+	pub lang_sys_records: Box<[LangSysRecord]>,
+	pub default_lang_sys: Option<LangSys>,
+	///The language system pointed to by each language system record
+	pub lang_sys: Box<[LangSys]>,
+}
+impl_from_file!(Script, (), (), f, {
+	let start = f.stream_position().unwrap();
+	let default_lang_sys_offset = unwrap_or_ret!(Offset16::from_file(f));
+	let lang_sys_records: Box<[LangSysRecord]> = unwrap_or_ret!(counted_array_from_file(f));
+	let default_lang_sys = match default_lang_sys_offset{
+		0 => None,
+		o => Some(unwrap_or_ret!(from_file_at(f, start, o as u32))),
+	};
+	let mut lang_sys = Vec::with_capacity(lang_sys_records.len());
+	for record in lang_sys_records.iter(){lang_sys.push(unwrap_or_ret!(from_file_at(f, start, record.lang_sys_offset as u32)));}
+	Ok(Self{default_lang_sys_offset, lang_sys_records, default_lang_sys, lang_sys: lang_sys.into()})
+});
+impl Script{
+	///The language system with the tag `language` or the default language system if there isn't one
+	pub fn lang_sys(&self, language: Option<&str>)->Option<&LangSys>{
+		language.and_then(|l|self.lang_sys_records.iter().position(|r|r.lang_sys_tag.data == l))
+			.map(|i|&self.lang_sys[i]).or(self.default_lang_sys.as_ref())
+	}
+}
+#[derive(Debug,FromFile)]
+pub struct LangSysRecord{
+	///4-byte LangSysTag identifier
+	pub lang_sys_tag: Tag,
+	///Offset to LangSys table, from beginning of Script table
+	pub lang_sys_offset: Offset16,
+}
+#[derive(Debug,FromFile)]
+pub struct LangSys{
+	///= NULL (reserved for an offset to a reordering table)
+	pub lookup_order_offset: Offset16,
+	///Index of a feature required for this language system; if no required features = 0xFFFF
+	pub required_feature_index: u16,
+	///Number of feature index values for this language system — excludes the required feature
+	pub feature_index_count: u16,
+	///Array of indices into the FeatureList, in arbitrary order
+	#[from_file_count(feature_index_count)]
+	pub feature_indices: Box<[u16]>,
+}
+impl LangSys{
+	pub const NO_REQUIRED_FEATURE: u16 = 0xFFFF;
+	pub fn required_feature(&self)->Option<u16>{
+		if self.required_feature_index == Self::NO_REQUIRED_FEATURE{None}else{Some(self.required_feature_index)}
+	}
+}
+
+#[derive(Debug)]
+pub struct FeatureList{
+	///Array of FeatureRecords — zero-based (first feature has FeatureIndex = 0), listed alphabetically by feature tag
+	pub feature_records: Box<[FeatureRecord]>,
+	///The feature table pointed to by each feature record
+	pub features: Box<[Feature]>,
+}
+impl_from_file!(FeatureList, (), (), f, {
+	let start = f.stream_position().unwrap();
+	let feature_records: Box<[FeatureRecord]> = unwrap_or_ret!(counted_array_from_file(f));
+	let mut features = Vec::with_capacity(feature_records.len());
+	for record in feature_records.iter(){features.push(unwrap_or_ret!(from_file_at(f, start, record.feature_offset as u32)));}
+	Ok(Self{feature_records, features: features.into()})
+});
+impl FeatureList{
+	///The indices of the lookups (sorted and without duplicates) used by the features of `lang_sys` with a tag in `features` along with its required feature
+	pub fn lookup_indices(&self, lang_sys: &LangSys, features: &[&str])->Vec<u16>{
+		let mut rv = Vec::new();
+		for index in lang_sys.required_feature().into_iter().chain(lang_sys.feature_indices.iter().copied()){
+			let (record, feature) = match (self.feature_records.get(index as usize), self.features.get(index as usize)){
+				(Some(r), Some(f)) => (r, f),
+				_ => continue,
+			};
+			if Some(index) == lang_sys.required_feature() || features.contains(&record.feature_tag.data.as_str())
+			{rv.extend_from_slice(&feature.lookup_list_indices);}
+		}
+		rv.sort_unstable();
+		rv.dedup();
+		rv
+	}
+}
+#[derive(Debug,FromFile)]
+pub struct FeatureRecord{
+	///4-byte feature identification tag
+	pub feature_tag: Tag,
+	///Offset to Feature table, from beginning of FeatureList
+	pub feature_offset: Offset16,
+}
+#[derive(Debug,FromFile)]
+pub struct Feature{
+	///Offset from start of Feature table to FeatureParams table, if defined for the feature and present, else NULL
+	pub feature_params_offset: Offset16,
+	///Number of LookupList indices for this feature
+	pub lookup_index_count: u16,
+	///Array of indices into the LookupList — zero-based (first lookup is LookupListIndex = 0)
+	#[from_file_count(lookup_index_count)]
+	pub lookup_list_indices: Box<[u16]>,
+}
+
+///A subtable of a lookup in a `GSUB` or `GPOS` table
+pub trait LookupSubtable: Sized{
+	///The lookup type of extension subtables which point to a subtable of another lookup type
+	const EXTENSION_TYPE: u16;
+	fn from_file_with_type<F>(f: &mut F, lookup_type: u16)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek;
+}
+#[derive(Debug)]
+pub struct LookupList<T>{
+	///Array of offsets to Lookup tables, from beginning of LookupList — zero based (first lookup is Lookup index = 0)
+	pub lookup_offsets: Box<[Offset16]>,
+	pub lookups: Box<[Lookup<T>]>,
+}
+impl<T> FromFile<(),()> for LookupList<T> where T: LookupSubtable{
+	fn from_file<F>(f: &mut F)->Result<Self, FromFileErr<(),()>> where Self: Sized, F: Read, F: Seek{
+		let start = f.stream_position().unwrap();
+		let lookup_offsets: Box<[Offset16]> = unwrap_or_ret!(counted_array_from_file(f));
+		let mut lookups = Vec::with_capacity(lookup_offsets.len());
+		for offset in lookup_offsets.iter(){lookups.push(unwrap_or_ret!(from_file_at(f, start, *offset as u32)));}
+		Ok(Self{lookup_offsets, lookups: lookups.into()})
+	}
+}
+#[derive(Debug)]
+pub struct Lookup<T>{
+	///Different enumerations for GSUB and GPOS
+	pub lookup_type: u16,
+	///Lookup qualifiers
+	pub lookup_flag: LookupFlag,
+	///Array of offsets to lookup subtables, from beginning of Lookup table
+	pub subtable_offsets: Box<[Offset16]>,
+	///Index (base 0) into GDEF mark glyph sets structure. This field is only present if the USE_MARK_FILTERING_SET lookup flag is set.
+	pub mark_filtering_set: Option<u16>,
+	///The subtables of this lookup with extension subtables replaced by the subtable they point to
+	pub subtables: Box<[T]>,
+}
+impl<T> FromFile<(),()> for Lookup<T> where T: LookupSubtable{
+	fn from_file<F>(f: &mut F)->Result<Self, FromFileErr<(),()>> where Self: Sized, F: Read, F: Seek{
+		let start = f.stream_position().unwrap();
+		let lookup_type = unwrap_or_ret!(u16::from_file(f));
+		let lookup_flag = unwrap_or_ret!(LookupFlag::from_file(f));
+		let subtable_offsets: Box<[Offset16]> = unwrap_or_ret!(counted_array_from_file(f));
+		let mark_filtering_set = if lookup_flag.has_flag(LookupFlag::USE_MARK_FILTERING_SET)
+		{Some(unwrap_or_ret!(u16::from_file(f)))}else{None};
+		let mut subtables = Vec::with_capacity(subtable_offsets.len());
+		for offset in subtable_offsets.iter(){
+			let sub_start = start + *offset as u64;
+			if f.seek(SeekFrom::Start(sub_start)).is_err(){return Err(FromFileErr::EOF);}
+			subtables.push(if lookup_type == T::EXTENSION_TYPE{
+				let format = unwrap_or_ret!(u16::from_file(f));
+				if format != 1{return Err(FromFileErr::InvalidData(()));}
+				let extension_lookup_type = unwrap_or_ret!(u16::from_file(f));
+				let extension_offset = unwrap_or_ret!(Offset32::from_file(f));
+				if f.seek(SeekFrom::Start(sub_start + extension_offset as u64)).is_err(){return Err(FromFileErr::EOF);}
+				unwrap_or_ret!(T::from_file_with_type(f, extension_lookup_type))
+			}else{unwrap_or_ret!(T::from_file_with_type(f, lookup_type))});
+		}
+		Ok(Self{lookup_type, lookup_flag, subtable_offsets, mark_filtering_set, subtables: subtables.into()})
+	}
+}
+impl<T> Lookup<T>{
+	///Whether `glyph_id` is skipped by this lookup because of its lookup flags and the glyph classes in `gdef`
+	pub fn ignores_glyph(&self, gdef: &GdefTable, glyph_id: u16)->bool{
+		gdef.ignores_glyph(self.lookup_flag, self.mark_filtering_set, glyph_id)
+	}
+}
+///Lookup qualifiers which mostly specify the glyphs a lookup skips
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LookupFlag(pub u16);
+impl LookupFlag{
+	///This bit relates only to the correct processing of GPOS type 3 (cursive attachment) lookups
+	pub const RIGHT_TO_LEFT: u16 = 0x0001;
+	///If set, skips over base glyphs
+	pub const IGNORE_BASE_GLYPHS: u16 = 0x0002;
+	///If set, skips over ligatures
+	pub const IGNORE_LIGATURES: u16 = 0x0004;
+	///If set, skips over all combining marks
+	pub const IGNORE_MARKS: u16 = 0x0008;
+	///If set, indicates that the lookup table structure is followed by a MarkFilteringSet field
+	pub const USE_MARK_FILTERING_SET: u16 = 0x0010;
+	///If not zero, skips over all marks of attachment type different from specified.
+	pub const MARK_ATTACHMENT_TYPE_MASK: u16 = 0xFF00;
+
+	pub fn has_flag(&self, flag: u16)->bool{self.0 & flag != 0}
+	///The mark attachment class marks must have to not be skipped (if not zero)
+	pub fn mark_attachment_type(&self)->u16{(self.0 & Self::MARK_ATTACHMENT_TYPE_MASK) >> 8}
+}
+impl_from_file!(LookupFlag, (), (), f, {Ok(Self(unwrap_or_ret!(u16::from_file(f))))});
+
+///Table of glyph ids where the index of a glyph in the table is its coverage index
+#[derive(Debug)]
+pub enum Coverage{
+	///Array of glyph IDs — in numerical order
+	Format1{glyph_array: Box<[u16]>},
+	///Array of glyph ranges — ordered by startGlyphID
+	Format2{range_records: Box<[RangeRecord]>},
+}
+impl_from_file!(Coverage, (), (), f, {match unwrap_or_ret!(u16::from_file(f)){
+	1 => Ok(Self::Format1{glyph_array: unwrap_or_ret!(counted_array_from_file(f))}),
+	2 => Ok(Self::Format2{range_records: unwrap_or_ret!(counted_array_from_file(f))}),
+	_ => Err(FromFileErr::InvalidData(())),
+}});
+impl Coverage{
+	///The coverage index of `glyph_id` or `None` if it isn't covered
+	pub fn index(&self, glyph_id: u16)->Option<u16>{match self{
+		Self::Format1{glyph_array} => glyph_array.binary_search(&glyph_id).ok().map(|i|i as u16),
+		Self::Format2{range_records} => {
+			let range = range_records.get(range_records.partition_point(|r|r.end_glyph_id < glyph_id))?;
+			if range.start_glyph_id > glyph_id{return None;}
+			Some(range.start_coverage_index.wrapping_add(glyph_id - range.start_glyph_id))
+		},
+	}}
+	pub fn contains(&self, glyph_id: u16)->bool{self.index(glyph_id).is_some()}
+}
+#[derive(Debug,FromFile)]
+pub struct RangeRecord{
+	///First glyph ID in the range
+	pub start_glyph_id: u16,
+	///Last glyph ID in the range
+	pub end_glyph_id: u16,
+	///Coverage Index of first glyph ID in range
+	pub start_coverage_index: u16,
+}
+
+///Table assigning glyphs to classes, glyphs not assigned a class are in class 0
+#[derive(Debug)]
+pub enum ClassDef{
+	Format1{
+		///First glyph ID of the classValueArray
+		start_glyph_id: u16,
+		///Array of class values — one per glyph ID
+		class_value_array: Box<[u16]>,
+	},
+	///Array of ClassRangeRecords — ordered by startGlyphID
+	Format2{class_range_records: Box<[ClassRangeRecord]>},
+}
+impl_from_file!(ClassDef, (), (), f, {match unwrap_or_ret!(u16::from_file(f)){
+	1 => {
+		let start_glyph_id = unwrap_or_ret!(u16::from_file(f));
+		Ok(Self::Format1{start_glyph_id, class_value_array: unwrap_or_ret!(counted_array_from_file(f))})
+	},
+	2 => Ok(Self::Format2{class_range_records: unwrap_or_ret!(counted_array_from_file(f))}),
+	_ => Err(FromFileErr::InvalidData(())),
+}});
+impl ClassDef{
+	///The class of `glyph_id`
+	pub fn class(&self, glyph_id: u16)->u16{match self{
+		Self::Format1{start_glyph_id, class_value_array} => glyph_id.checked_sub(*start_glyph_id)
+			.and_then(|i|class_value_array.get(i as usize)).copied().unwrap_or(0),
+		Self::Format2{class_range_records} => {
+			match class_range_records.get(class_range_records.partition_point(|r|r.end_glyph_id < glyph_id)){
+				Some(r) if r.start_glyph_id <= glyph_id => r.class,
+				_ => 0,
+			}
+		},
+	}}
+	///Parses the class definition at `base + offset` where a NULL offset puts every glyph in class 0
+	fn from_file_at_or_empty<F>(f: &mut F, base: u64, offset: Offset16)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		match offset{
+			0 => Ok(Self::Format2{class_range_records: [].into()}),
+			o => from_file_at(f, base, o as u32),
+		}
+	}
+}
+#[derive(Debug,FromFile)]
+pub struct ClassRangeRecord{
+	///First glyph ID in the range
+	pub start_glyph_id: u16,
+	///Last glyph ID in the range
+	pub end_glyph_id: u16,
+	///Applied to all glyphs in the range
+	pub class: u16,
+}
+
+#[derive(Debug,FromFile)]
+pub struct SequenceLookupRecord{
+	///Index (zero-based) into the input glyph sequence
+	pub sequence_index: u16,
+	///Index (zero-based) into the LookupList
+	pub lookup_list_index: u16,
+}
+///A contextual lookup subtable (`GSUB` type 5 and `GPOS` type 7)
+#[derive(Debug)]
+pub enum SequenceContext{
+	///Simple glyph contexts
+	Format1{
+		coverage: Coverage,
+		///One rule set per covered glyph (in coverage index order)
+		seq_rule_sets: Box<[Option<SequenceRuleSet>]>,
+	},
+	///Class-based glyph contexts where the rule sequences hold classes rather than glyph ids
+	Format2{
+		coverage: Coverage,
+		class_def: ClassDef,
+		///One rule set per class of the first glyph
+		class_seq_rule_sets: Box<[Option<SequenceRuleSet>]>,
+	},
+	///Coverage-based glyph contexts with one coverage table per input glyph
+	Format3{
+		coverages: Box<[Coverage]>,
+		seq_lookup_records: Box<[SequenceLookupRecord]>,
+	},
+}
+impl_from_file!(SequenceContext, (), (), f, {
+	let start = f.stream_position().unwrap();
+	match unwrap_or_ret!(u16::from_file(f)){
+		1 => {
+			let coverage_offset = unwrap_or_ret!(Offset16::from_file(f));
+			let seq_rule_sets = unwrap_or_ret!(optional_offset16_array_from_file(f, start));
+			Ok(Self::Format1{coverage: unwrap_or_ret!(from_file_at(f, start, coverage_offset as u32)), seq_rule_sets})
+		},
+		2 => {
+			let coverage_offset = unwrap_or_ret!(Offset16::from_file(f));
+			let class_def_offset = unwrap_or_ret!(Offset16::from_file(f));
+			let class_seq_rule_sets = unwrap_or_ret!(optional_offset16_array_from_file(f, start));
+			Ok(Self::Format2{
+				coverage: unwrap_or_ret!(from_file_at(f, start, coverage_offset as u32)),
+				class_def: unwrap_or_ret!(ClassDef::from_file_at_or_empty(f, start, class_def_offset)),
+				class_seq_rule_sets,
+			})
+		},
+		3 => {
+			let glyph_count = unwrap_or_ret!(u16::from_file(f));
+			let seq_lookup_count = unwrap_or_ret!(u16::from_file(f));
+			let coverage_offsets: Box<[Offset16]> = unwrap_or_ret!(array_from_file(f, glyph_count as usize));
+			let seq_lookup_records = unwrap_or_ret!(array_from_file(f, seq_lookup_count as usize));
+			Ok(Self::Format3{coverages: unwrap_or_ret!(coverages_from_file(f, start, &coverage_offsets)), seq_lookup_records})
+		},
+		_ => Err(FromFileErr::InvalidData(())),
+	}
+});
+#[derive(Debug)]
+pub struct SequenceRuleSet{
+	///Rules ordered by preference
+	pub seq_rules: Box<[SequenceRule]>,
+}
+impl_from_file!(SequenceRuleSet, (), (), f, {
+	let start = f.stream_position().unwrap();
+	Ok(Self{seq_rules: unwrap_or_ret!(offset16_array_from_file(f, start))})
+});
+#[derive(Debug)]
+pub struct SequenceRule{
+	///Array of input glyph IDs (or classes for format 2) — starting with the second glyph
+	pub input_sequence: Box<[u16]>,
+	///Array of Sequence lookup records
+	pub seq_lookup_records: Box<[SequenceLookupRecord]>,
+}
+impl_from_file!(SequenceRule, (), (), f, {
+	let glyph_count = unwrap_or_ret!(u16::from_file(f));
+	let seq_lookup_count = unwrap_or_ret!(u16::from_file(f));
+	if glyph_count == 0{return Err(FromFileErr::InvalidData(()));}
+	Ok(Self{
+		input_sequence: unwrap_or_ret!(array_from_file(f, glyph_count as usize - 1)),
+		seq_lookup_records: unwrap_or_ret!(array_from_file(f, seq_lookup_count as usize)),
+	})
+});
+///A chained contextual lookup subtable (`GSUB` type 6 and `GPOS` type 8)
+#[derive(Debug)]
+pub enum ChainedSequenceContext{
+	///Simple glyph contexts
+	Format1{
+		coverage: Coverage,
+		///One rule set per covered glyph (in coverage index order)
+		chained_seq_rule_sets: Box<[Option<ChainedSequenceRuleSet>]>,
+	},
+	///Class-based glyph contexts where the rule sequences hold classes rather than glyph ids
+	Format2{
+		coverage: Coverage,
+		backtrack_class_def: ClassDef,
+		input_class_def: ClassDef,
+		lookahead_class_def: ClassDef,
+		///One rule set per input class of the first glyph
+		chained_class_seq_rule_sets: Box<[Option<ChainedSequenceRuleSet>]>,
+	},
+	///Coverage-based glyph contexts with one coverage table per glyph
+	Format3{
+		///Backtrack coverages in reverse logical order (the glyph before the input first)
+		backtrack_coverages: Box<[Coverage]>,
+		input_coverages: Box<[Coverage]>,
+		lookahead_coverages: Box<[Coverage]>,
+		seq_lookup_records: Box<[SequenceLookupRecord]>,
+	},
+}
+impl_from_file!(ChainedSequenceContext, (), (), f, {
+	let start = f.stream_position().unwrap();
+	match unwrap_or_ret!(u16::from_file(f)){
+		1 => {
+			let coverage_offset = unwrap_or_ret!(Offset16::from_file(f));
+			let chained_seq_rule_sets = unwrap_or_ret!(optional_offset16_array_from_file(f, start));
+			Ok(Self::Format1{coverage: unwrap_or_ret!(from_file_at(f, start, coverage_offset as u32)), chained_seq_rule_sets})
+		},
+		2 => {
+			let coverage_offset = unwrap_or_ret!(Offset16::from_file(f));
+			let backtrack_class_def_offset = unwrap_or_ret!(Offset16::from_file(f));
+			let input_class_def_offset = unwrap_or_ret!(Offset16::from_file(f));
+			let lookahead_class_def_offset = unwrap_or_ret!(Offset16::from_file(f));
+			let chained_class_seq_rule_sets = unwrap_or_ret!(optional_offset16_array_from_file(f, start));
+			Ok(Self::Format2{
+				coverage: unwrap_or_ret!(from_file_at(f, start, coverage_offset as u32)),
+				backtrack_class_def: unwrap_or_ret!(ClassDef::from_file_at_or_empty(f, start, backtrack_class_def_offset)),
+				input_class_def: unwrap_or_ret!(ClassDef::from_file_at_or_empty(f, start, input_class_def_offset)),
+				lookahead_class_def: unwrap_or_ret!(ClassDef::from_file_at_or_empty(f, start, lookahead_class_def_offset)),
+				chained_class_seq_rule_sets,
+			})
+		},
+		3 => {
+			let backtrack_offsets: Box<[Offset16]> = unwrap_or_ret!(counted_array_from_file(f));
+			let input_offsets: Box<[Offset16]> = unwrap_or_ret!(counted_array_from_file(f));
+			let lookahead_offsets: Box<[Offset16]> = unwrap_or_ret!(counted_array_from_file(f));
+			let seq_lookup_records = unwrap_or_ret!(counted_array_from_file(f));
+			Ok(Self::Format3{
+				backtrack_coverages: unwrap_or_ret!(coverages_from_file(f, start, &backtrack_offsets)),
+				input_coverages: unwrap_or_ret!(coverages_from_file(f, start, &input_offsets)),
+				lookahead_coverages: unwrap_or_ret!(coverages_from_file(f, start, &lookahead_offsets)),
+				seq_lookup_records,
+			})
+		},
+		_ => Err(FromFileErr::InvalidData(())),
+	}
+});
+#[derive(Debug)]
+pub struct ChainedSequenceRuleSet{
+	///Rules ordered by preference
+	pub chained_seq_rules: Box<[ChainedSequenceRule]>,
+}
+impl_from_file!(ChainedSequenceRuleSet, (), (), f, {
+	let start = f.stream_position().unwrap();
+	Ok(Self{chained_seq_rules: unwrap_or_ret!(offset16_array_from_file(f, start))})
+});
+#[derive(Debug)]
+pub struct ChainedSequenceRule{
+	///Array of backtrack glyph IDs (or classes for format 2) in reverse logical order
+	pub backtrack_sequence: Box<[u16]>,
+	///Array of input glyph IDs (or classes for format 2) — start with second glyph
+	pub input_sequence: Box<[u16]>,
+	///Array of lookahead glyph IDs (or classes for format 2)
+	pub lookahead_sequence: Box<[u16]>,
+	///Array of SequenceLookupRecords
+	pub seq_lookup_records: Box<[SequenceLookupRecord]>,
+}
+impl_from_file!(ChainedSequenceRule, (), (), f, {
+	let backtrack_sequence = unwrap_or_ret!(counted_array_from_file(f));
+	let input_glyph_count = unwrap_or_ret!(u16::from_file(f));
+	if input_glyph_count == 0{return Err(FromFileErr::InvalidData(()));}
+	let input_sequence = unwrap_or_ret!(array_from_file(f, input_glyph_count as usize - 1));
+	let lookahead_sequence = unwrap_or_ret!(counted_array_from_file(f));
+	let seq_lookup_records = unwrap_or_ret!(counted_array_from_file(f));
+	Ok(Self{backtrack_sequence, input_sequence, lookahead_sequence, seq_lookup_records})
+});
+
+///The glyph substitution table
+pub type GsubTable = LayoutTable<GsubSubtable>;
+///A `GSUB` lookup subtable, extension subtables (type 7) are replaced by the subtable they point to
+#[derive(Debug)]
+pub enum GsubSubtable{
+	///Type 1: replace one glyph with one glyph
+	Single(SingleSubst),
+	///Type 2: replace one glyph with more than one glyph
+	Multiple(MultipleSubst),
+	///Type 3: replace one glyph with one of many glyphs
+	Alternate(AlternateSubst),
+	///Type 4: replace multiple glyphs with one glyph
+	Ligature(LigatureSubst),
+	///Type 5: replace one or more glyphs in context
+	Context(SequenceContext),
+	///Type 6: replace one or more glyphs in chained context
+	ChainedContext(ChainedSequenceContext),
+	///Type 8: applied in reverse order, replace single glyph in chaining context
+	ReverseChainSingle(ReverseChainSingleSubst),
+	///A lookup type this library doesn't know about
+	Unknown(u16),
+}
+impl GsubSubtable{
+	pub const SINGLE: u16 = 1;
+	pub const MULTIPLE: u16 = 2;
+	pub const ALTERNATE: u16 = 3;
+	pub const LIGATURE: u16 = 4;
+	pub const CONTEXT: u16 = 5;
+	pub const CHAINED_CONTEXT: u16 = 6;
+	pub const EXTENSION: u16 = 7;
+	pub const REVERSE_CHAIN_SINGLE: u16 = 8;
+}
+impl LookupSubtable for GsubSubtable{
+	const EXTENSION_TYPE: u16 = Self::EXTENSION;
+	fn from_file_with_type<F>(f: &mut F, lookup_type: u16)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		Ok(match lookup_type{
+			Self::SINGLE => Self::Single(unwrap_or_ret!(SingleSubst::from_file(f))),
+			Self::MULTIPLE => Self::Multiple(unwrap_or_ret!(MultipleSubst::from_file(f))),
+			Self::ALTERNATE => Self::Alternate(unwrap_or_ret!(AlternateSubst::from_file(f))),
+			Self::LIGATURE => Self::Ligature(unwrap_or_ret!(LigatureSubst::from_file(f))),
+			Self::CONTEXT => Self::Context(unwrap_or_ret!(SequenceContext::from_file(f))),
+			Self::CHAINED_CONTEXT => Self::ChainedContext(unwrap_or_ret!(ChainedSequenceContext::from_file(f))),
+			Self::REVERSE_CHAIN_SINGLE => Self::ReverseChainSingle(unwrap_or_ret!(ReverseChainSingleSubst::from_file(f))),
+			t => Self::Unknown(t),
+		})
+	}
+}
+#[derive(Debug)]
+pub enum SingleSubst{
+	Format1{
+		coverage: Coverage,
+		///Add to original glyph ID to get substitute glyph ID (modulo 65536)
+		delta_glyph_id: i16,
+	},
+	Format2{
+		coverage: Coverage,
+		///Array of substitute glyph IDs — ordered by Coverage index
+		substitute_glyph_ids: Box<[u16]>,
+	},
+}
+impl_from_file!(SingleSubst, (), (), f, {
+	let start = f.stream_position().unwrap();
+	let format = unwrap_or_ret!(u16::from_file(f));
+	let coverage_offset = unwrap_or_ret!(Offset16::from_file(f));
+	match format{
+		1 => {
+			let delta_glyph_id = unwrap_or_ret!(i16::from_file(f));
+			Ok(Self::Format1{coverage: unwrap_or_ret!(from_file_at(f, start, coverage_offset as u32)), delta_glyph_id})
+		},
+		2 => {
+			let substitute_glyph_ids = unwrap_or_ret!(counted_array_from_file(f));
+			Ok(Self::Format2{coverage: unwrap_or_ret!(from_file_at(f, start, coverage_offset as u32)), substitute_glyph_ids})
+		},
+		_ => Err(FromFileErr::InvalidData(())),
+	}
+});
+impl SingleSubst{
+	///The glyph that replaces `glyph_id` or `None` if it isn't covered
+	pub fn substitute(&self, glyph_id: u16)->Option<u16>{match self{
+		Self::Format1{coverage, delta_glyph_id} => {
+			coverage.index(glyph_id)?;
+			Some(glyph_id.wrapping_add(*delta_glyph_id as u16))
+		},
+		Self::Format2{coverage, substitute_glyph_ids} =>
+			substitute_glyph_ids.get(coverage.index(glyph_id)? as usize).copied(),
+	}}
+}
+#[derive(Debug)]
+pub struct MultipleSubst{
+	pub coverage: Coverage,
+	///Sequence tables ordered by Coverage index
+	pub sequences: Box<[Sequence]>,
+}
+impl_from_file!(MultipleSubst, (), (), f, {
+	let start = f.stream_position().unwrap();
+	if unwrap_or_ret!(u16::from_file(f)) != 1{return Err(FromFileErr::InvalidData(()));}
+	let coverage_offset = unwrap_or_ret!(Offset16::from_file(f));
+	let sequences = unwrap_or_ret!(offset16_array_from_file(f, start));
+	Ok(Self{coverage: unwrap_or_ret!(from_file_at(f, start, coverage_offset as u32)), sequences})
+});
+impl MultipleSubst{
+	///The glyphs that replace `glyph_id` or `None` if it isn't covered
+	pub fn sequence(&self, glyph_id: u16)->Option<&[u16]>{
+		self.sequences.get(self.coverage.index(glyph_id)? as usize).map(|s|&*s.substitute_glyph_ids)
+	}
+}
+#[derive(Debug,FromFile)]
+pub struct Sequence{
+	///Number of glyph IDs in the substituteGlyphIDs array. This must always be greater than 0.
+	pub glyph_count: u16,
+	///String of glyph IDs to substitute
+	#[from_file_count(glyph_count)]
+	pub substitute_glyph_ids: Box<[u16]>,
+}
+#[derive(Debug)]
+pub struct AlternateSubst{
+	pub coverage: Coverage,
+	///AlternateSet tables ordered by Coverage index
+	pub alternate_sets: Box<[AlternateSet]>,
+}
+impl_from_file!(AlternateSubst, (), (), f, {
+	let start = f.stream_position().unwrap();
+	if unwrap_or_ret!(u16::from_file(f)) != 1{return Err(FromFileErr::InvalidData(()));}
+	let coverage_offset = unwrap_or_ret!(Offset16::from_file(f));
+	let alternate_sets = unwrap_or_ret!(offset16_array_from_file(f, start));
+	Ok(Self{coverage: unwrap_or_ret!(from_file_at(f, start, coverage_offset as u32)), alternate_sets})
+});
+impl AlternateSubst{
+	///The alternatives for `glyph_id` or `None` if it isn't covered
+	pub fn alternates(&self, glyph_id: u16)->Option<&[u16]>{
+		self.alternate_sets.get(self.coverage.index(glyph_id)? as usize).map(|s|&*s.alternate_glyph_ids)
+	}
+}
+#[derive(Debug,FromFile)]
+pub struct AlternateSet{
+	///Number of glyph IDs in the alternateGlyphIDs array
+	pub glyph_count: u16,
+	///Array of alternate glyph IDs, in arbitrary order
+	#[from_file_count(glyph_count)]
+	pub alternate_glyph_ids: Box<[u16]>,
+}
+#[derive(Debug)]
+pub struct LigatureSubst{
+	pub coverage: Coverage,
+	///LigatureSet tables ordered by Coverage index
+	pub ligature_sets: Box<[LigatureSet]>,
+}
+impl_from_file!(LigatureSubst, (), (), f, {
+	let start = f.stream_position().unwrap();
+	if unwrap_or_ret!(u16::from_file(f)) != 1{return Err(FromFileErr::InvalidData(()));}
+	let coverage_offset = unwrap_or_ret!(Offset16::from_file(f));
+	let ligature_sets = unwrap_or_ret!(offset16_array_from_file(f, start));
+	Ok(Self{coverage: unwrap_or_ret!(from_file_at(f, start, coverage_offset as u32)), ligature_sets})
+});
+impl LigatureSubst{
+	///The ligatures starting with `glyph_id` in order of preference or `None` if it isn't covered
+	pub fn ligatures(&self, glyph_id: u16)->Option<&[Ligature]>{
+		self.ligature_sets.get(self.coverage.index(glyph_id)? as usize).map(|s|&*s.ligatures)
+	}
+}
+#[derive(Debug)]
+pub struct LigatureSet{
+	///Ligature tables ordered by preference
+	pub ligatures: Box<[Ligature]>,
+}
+impl_from_file!(LigatureSet, (), (), f, {
+	let start = f.stream_position().unwrap();
+	Ok(Self{ligatures: unwrap_or_ret!(offset16_array_from_file(f, start))})
+});
+#[derive(Debug)]
+pub struct Ligature{
+	///glyph ID of ligature to substitute
+	pub ligature_glyph: u16,
+	///Array of component glyph IDs — start with the second component, ordered in writing direction
+	pub component_glyph_ids: Box<[u16]>,
+}
+impl_from_file!(Ligature, (), (), f, {
+	let ligature_glyph = unwrap_or_ret!(u16::from_file(f));
+	let component_count = unwrap_or_ret!(u16::from_file(f));
+	if component_count == 0{return Err(FromFileErr::InvalidData(()));}
+	Ok(Self{ligature_glyph, component_glyph_ids: unwrap_or_ret!(array_from_file(f, component_count as usize - 1))})
+});
+#[derive(Debug)]
+pub struct ReverseChainSingleSubst{
+	pub coverage: Coverage,
+	///Backtrack coverages in reverse logical order (the glyph before the input first)
+	pub backtrack_coverages: Box<[Coverage]>,
+	///Lookahead coverages in logical order
+	pub lookahead_coverages: Box<[Coverage]>,
+	///Array of substitute glyph IDs — ordered by Coverage index
+	pub substitute_glyph_ids: Box<[u16]>,
+}
+impl_from_file!(ReverseChainSingleSubst, (), (), f, {
+	let start = f.stream_position().unwrap();
+	if unwrap_or_ret!(u16::from_file(f)) != 1{return Err(FromFileErr::InvalidData(()));}
+	let coverage_offset = unwrap_or_ret!(Offset16::from_file(f));
+	let backtrack_offsets: Box<[Offset16]> = unwrap_or_ret!(counted_array_from_file(f));
+	let lookahead_offsets: Box<[Offset16]> = unwrap_or_ret!(counted_array_from_file(f));
+	let substitute_glyph_ids = unwrap_or_ret!(counted_array_from_file(f));
+	Ok(Self{
+		coverage: unwrap_or_ret!(from_file_at(f, start, coverage_offset as u32)),
+		backtrack_coverages: unwrap_or_ret!(coverages_from_file(f, start, &backtrack_offsets)),
+		lookahead_coverages: unwrap_or_ret!(coverages_from_file(f, start, &lookahead_offsets)),
+		substitute_glyph_ids,
+	})
+});
+
+///The glyph positioning table
+pub type GposTable = LayoutTable<GposSubtable>;
+///A `GPOS` lookup subtable, extension subtables (type 9) are replaced by the subtable they point to
+#[derive(Debug)]
+pub enum GposSubtable{
+	///Type 1: adjust position of a single glyph
+	Single(SinglePos),
+	///Type 2: adjust position of a pair of glyphs
+	Pair(PairPos),
+	///Type 3: attach cursive glyphs
+	Cursive(CursivePos),
+	///Type 4: attach a combining mark to a base glyph
+	MarkBase(MarkBasePos),
+	///Type 5: attach a combining mark to a ligature
+	MarkLig(MarkLigPos),
+	///Type 6: attach a combining mark to another mark
+	MarkMark(MarkMarkPos),
+	///Type 7: position one or more glyphs in context
+	Context(SequenceContext),
+	///Type 8: position one or more glyphs in chained context
+	ChainedContext(ChainedSequenceContext),
+	///A lookup type this library doesn't know about
+	Unknown(u16),
+}
+impl GposSubtable{
+	pub const SINGLE: u16 = 1;
+	pub const PAIR: u16 = 2;
+	pub const CURSIVE: u16 = 3;
+	pub const MARK_BASE: u16 = 4;
+	pub const MARK_LIG: u16 = 5;
+	pub const MARK_MARK: u16 = 6;
+	pub const CONTEXT: u16 = 7;
+	pub const CHAINED_CONTEXT: u16 = 8;
+	pub const EXTENSION: u16 = 9;
+}
+impl LookupSubtable for GposSubtable{
+	const EXTENSION_TYPE: u16 = Self::EXTENSION;
+	fn from_file_with_type<F>(f: &mut F, lookup_type: u16)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		Ok(match lookup_type{
+			Self::SINGLE => Self::Single(unwrap_or_ret!(SinglePos::from_file(f))),
+			Self::PAIR => Self::Pair(unwrap_or_ret!(PairPos::from_file(f))),
+			Self::CURSIVE => Self::Cursive(unwrap_or_ret!(CursivePos::from_file(f))),
+			Self::MARK_BASE => Self::MarkBase(unwrap_or_ret!(MarkBasePos::from_file(f))),
+			Self::MARK_LIG => Self::MarkLig(unwrap_or_ret!(MarkLigPos::from_file(f))),
+			Self::MARK_MARK => Self::MarkMark(unwrap_or_ret!(MarkMarkPos::from_file(f))),
+			Self::CONTEXT => Self::Context(unwrap_or_ret!(SequenceContext::from_file(f))),
+			Self::CHAINED_CONTEXT => Self::ChainedContext(unwrap_or_ret!(ChainedSequenceContext::from_file(f))),
+			t => Self::Unknown(t),
+		})
+	}
+}
+
+///Adjustments to the position of a glyph, values not included by the value format are 0
+#[derive(Debug, Default)]
+pub struct ValueRecord{
+	///Horizontal adjustment for placement, in design units.
+	pub x_placement: FWord,
+	///Vertical adjustment for placement, in design units.
+	pub y_placement: FWord,
+	///Horizontal adjustment for advance, in design units — only used for horizontal layout.
+	pub x_advance: FWord,
+	///Vertical adjustment for advance, in design units — only used for vertical layout.
+	pub y_advance: FWord,
+	///Device table (non-variable font) / VariationIndex table (variable font) for horizontal placement
+	pub x_pla_device: Option<DeviceTable>,
+	///Device table (non-variable font) / VariationIndex table (variable font) for vertical placement
+	pub y_pla_device: Option<DeviceTable>,
+	///Device table (non-variable font) / VariationIndex table (variable font) for horizontal advance
+	pub x_adv_device: Option<DeviceTable>,
+	///Device table (non-variable font) / VariationIndex table (variable font) for vertical advance
+	pub y_adv_device: Option<DeviceTable>,
+}
+impl ValueRecord{
+	///Includes horizontal adjustment for placement
+	pub const X_PLACEMENT: u16 = 0x0001;
+	///Includes vertical adjustment for placement
+	pub const Y_PLACEMENT: u16 = 0x0002;
+	///Includes horizontal adjustment for advance
+	pub const X_ADVANCE: u16 = 0x0004;
+	///Includes vertical adjustment for advance
+	pub const Y_ADVANCE: u16 = 0x0008;
+	///Includes Device table (non-variable font) / VariationIndex table (variable font) for horizontal placement
+	pub const X_PLACEMENT_DEVICE: u16 = 0x0010;
+	///Includes Device table (non-variable font) / VariationIndex table (variable font) for vertical placement
+	pub const Y_PLACEMENT_DEVICE: u16 = 0x0020;
+	///Includes Device table (non-variable font) / VariationIndex table (variable font) for horizontal advance
+	pub const X_ADVANCE_DEVICE: u16 = 0x0040;
+	///Includes Device table (non-variable font) / VariationIndex table (variable font) for vertical advance
+	pub const Y_ADVANCE_DEVICE: u16 = 0x0080;
+
+	///Reads a value record with the fields in `value_format` where device offsets are from `base`
+	pub fn from_file_with_format<F>(f: &mut F, value_format: u16, base: u64)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		let mut rv = Self::default();
+		for (bit, value) in [
+			(Self::X_PLACEMENT, &mut rv.x_placement),
+			(Self::Y_PLACEMENT, &mut rv.y_placement),
+			(Self::X_ADVANCE, &mut rv.x_advance),
+			(Self::Y_ADVANCE, &mut rv.y_advance),
+		]{if value_format & bit != 0{*value = unwrap_or_ret!(FWord::from_file(f));}}
+		let mut device_offsets = [0 as Offset16; 4];
+		for (i, bit) in [Self::X_PLACEMENT_DEVICE, Self::Y_PLACEMENT_DEVICE, Self::X_ADVANCE_DEVICE, Self::Y_ADVANCE_DEVICE].iter().enumerate()
+		{if value_format & bit != 0{device_offsets[i] = unwrap_or_ret!(Offset16::from_file(f));}}
+		if device_offsets.iter().all(|o|*o == 0){return Ok(rv);}
+		// value records are usually in arrays so return to the end of this one
+		let end = f.stream_position().unwrap();
+		for (offset, device) in device_offsets.iter().zip([
+			&mut rv.x_pla_device, &mut rv.y_pla_device, &mut rv.x_adv_device, &mut rv.y_adv_device,
+		]){if *offset != 0{*device = Some(unwrap_or_ret!(from_file_at(f, base, *offset as u32)));}}
+		if f.seek(SeekFrom::Start(end)).is_err(){return Err(FromFileErr::EOF);}
+		Ok(rv)
+	}
+}
+
+///Hinting adjustments for particular sizes or, in variable fonts, an index into the `GDEF` item variation store
+#[derive(Debug)]
+pub enum DeviceTable{
+	Device{
+		///Smallest size to correct, in ppem
+		start_size: u16,
+		///Largest size to correct, in ppem
+		end_size: u16,
+		///Format of deltaValue array data: 0x0001, 0x0002, or 0x0003
+		delta_format: u16,
+		///The unpacked adjustment in pixels for each size from start_size to end_size
+		delta_values: Box<[i8]>,
+	},
+	VariationIndex{
+		///A delta-set outer index — used to select an item variation data subtable within the item variation store.
+		delta_set_outer_index: u16,
+		///A delta-set inner index — used to select a delta-set row within an item variation data subtable.
+		delta_set_inner_index: u16,
+	},
+}
+impl DeviceTable{
+	///Signed 2-bit value, 8 values per uint16
+	pub const LOCAL_2_BIT_DELTAS: u16 = 0x0001;
+	///Signed 4-bit value, 4 values per uint16
+	pub const LOCAL_4_BIT_DELTAS: u16 = 0x0002;
+	///Signed 8-bit value, 2 values per uint16
+	pub const LOCAL_8_BIT_DELTAS: u16 = 0x0003;
+	///VariationIndex table, contains a delta-set index pair.
+	pub const VARIATION_INDEX: u16 = 0x8000;
+
+	///The adjustment in pixels at the size `ppem` (always 0 for variation indices)
+	pub fn pixel_delta(&self, ppem: u16)->i8{match self{
+		Self::Device{start_size, delta_values, ..} => ppem.checked_sub(*start_size)
+			.and_then(|i|delta_values.get(i as usize)).copied().unwrap_or(0),
+		Self::VariationIndex{..} => 0,
+	}}
+	///The adjustment in design units at the normalized coordinates `coords` (always 0 for device tables)
+	pub fn variation_delta(&self, store: &ItemVariationStore, coords: &[f32])->f32{match self{
+		Self::Device{..} => 0.0,
+		Self::VariationIndex{delta_set_outer_index, delta_set_inner_index} =>
+			store.delta(*delta_set_outer_index, *delta_set_inner_index, coords),
+	}}
+}
+impl_from_file!(DeviceTable, (), (), f, {
+	let start_size = unwrap_or_ret!(u16::from_file(f));
+	let end_size = unwrap_or_ret!(u16::from_file(f));
+	let delta_format = unwrap_or_ret!(u16::from_file(f));
+	match delta_format{
+		Self::VARIATION_INDEX => Ok(Self::VariationIndex{delta_set_outer_index: start_size, delta_set_inner_index: end_size}),
+		Self::LOCAL_2_BIT_DELTAS..=Self::LOCAL_8_BIT_DELTAS => {
+			let bits = 1usize << delta_format;
+			let count = if end_size >= start_size{(end_size - start_size) as usize + 1}else{0};
+			let words: Box<[u16]> = unwrap_or_ret!(array_from_file(f, (count * bits).div_ceil(16)));
+			let delta_values = (0..count).map(|i|{
+				let shift = 16 - bits - i * bits % 16;
+				let v = (words[i * bits / 16] >> shift) as i32 & ((1 << bits) - 1);
+				// sign extend
+				(if v >= 1 << (bits - 1){v - (1 << bits)}else{v}) as i8
+			}).collect();
+			Ok(Self::Device{start_size, end_size, delta_format, delta_values})
+		},
+		_ => Err(FromFileErr::InvalidData(())),
+	}
+});
+
+///A point that glyphs are attached to
+#[derive(Debug)]
+pub enum Anchor{
+	///Design units only
+	Format1{x_coordinate: FWord, y_coordinate: FWord},
+	///Design units plus contour point
+	Format2{
+		x_coordinate: FWord,
+		y_coordinate: FWord,
+		///Index to glyph contour point
+		anchor_point: u16,
+	},
+	///Design units plus Device or VariationIndex tables
+	Format3{
+		x_coordinate: FWord,
+		y_coordinate: FWord,
+		x_device: Option<DeviceTable>,
+		y_device: Option<DeviceTable>,
+	},
+}
+impl_from_file!(Anchor, (), (), f, {
+	let start = f.stream_position().unwrap();
+	let format = unwrap_or_ret!(u16::from_file(f));
+	let x_coordinate = unwrap_or_ret!(FWord::from_file(f));
+	let y_coordinate = unwrap_or_ret!(FWord::from_file(f));
+	match format{
+		1 => Ok(Self::Format1{x_coordinate, y_coordinate}),
+		2 => Ok(Self::Format2{x_coordinate, y_coordinate, anchor_point: unwrap_or_ret!(u16::from_file(f))}),
+		3 => {
+			let x_device_offset = unwrap_or_ret!(Offset16::from_file(f));
+			let y_device_offset = unwrap_or_ret!(Offset16::from_file(f));
+			let x_device = match x_device_offset{0 => None, o => Some(unwrap_or_ret!(from_file_at(f, start, o as u32)))};
+			let y_device = match y_device_offset{0 => None, o => Some(unwrap_or_ret!(from_file_at(f, start, o as u32)))};
+			Ok(Self::Format3{x_coordinate, y_coordinate, x_device, y_device})
+		},
+		_ => Err(FromFileErr::InvalidData(())),
+	}
+});
+impl Anchor{
+	///The (x, y) position of the anchor in design units
+	pub fn coordinates(&self)->(FWord, FWord){match self{
+		Self::Format1{x_coordinate, y_coordinate}
+		| Self::Format2{x_coordinate, y_coordinate, ..}
+		| Self::Format3{x_coordinate, y_coordinate, ..} => (*x_coordinate, *y_coordinate),
+	}}
+}
+///Parses the optional anchor at `base + offset`
+fn anchor_at<F>(f: &mut F, base: u64, offset: Offset16)->Result<Option<Anchor>, FromFileErr<(),()>> where F: Read, F: Seek{
+	match offset{
+		0 => Ok(None),
+		o => Ok(Some(unwrap_or_ret!(from_file_at(f, base, o as u32)))),
+	}
+}
+
+#[derive(Debug)]
+pub enum SinglePos{
+	///Single positioning value applied to all covered glyphs
+	Format1{coverage: Coverage, value_format: u16, value_record: ValueRecord},
+	///Array of positioning values ordered by coverage index
+	Format2{coverage: Coverage, value_format: u16, value_records: Box<[ValueRecord]>},
+}
+impl_from_file!(SinglePos, (), (), f, {
+	let start = f.stream_position().unwrap();
+	let format = unwrap_or_ret!(u16::from_file(f));
+	let coverage_offset = unwrap_or_ret!(Offset16::from_file(f));
+	let value_format = unwrap_or_ret!(u16::from_file(f));
+	let rv = match format{
+		1 => Self::Format1{
+			value_record: unwrap_or_ret!(ValueRecord::from_file_with_format(f, value_format, start)),
+			coverage: unwrap_or_ret!(from_file_at(f, start, coverage_offset as u32)),
+			value_format,
+		},
+		2 => {
+			let value_count = unwrap_or_ret!(u16::from_file(f));
+			let mut value_records = Vec::with_capacity(value_count as usize);
+			for _ in 0..value_count{value_records.push(unwrap_or_ret!(ValueRecord::from_file_with_format(f, value_format, start)));}
+			Self::Format2{
+				coverage: unwrap_or_ret!(from_file_at(f, start, coverage_offset as u32)),
+				value_format,
+				value_records: value_records.into(),
+			}
+		},
+		_ => return Err(FromFileErr::InvalidData(())),
+	};
+	Ok(rv)
+});
+impl SinglePos{
+	///The adjustment for `glyph_id` or `None` if it isn't covered
+	pub fn value(&self, glyph_id: u16)->Option<&ValueRecord>{match self{
+		Self::Format1{coverage, value_record, ..} => {coverage.index(glyph_id)?; Some(value_record)},
+		Self::Format2{coverage, value_records, ..} => value_records.get(coverage.index(glyph_id)? as usize),
+	}}
+}
+
+#[derive(Debug)]
+pub enum PairPos{
+	///Adjustments for glyph pairs
+	Format1{
+		coverage: Coverage,
+		///Defines the types of data in valueRecord1 — for the first glyph in the pair (may be zero).
+		value_format1: u16,
+		///Defines the types of data in valueRecord2 — for the second glyph in the pair (may be zero).
+		value_format2: u16,
+		///PairSet tables ordered by Coverage Index
+		pair_sets: Box<[PairSet]>,
+	},
+	///Class pair adjustment
+	Format2{
+		coverage: Coverage,
+		value_format1: u16,
+		value_format2: u16,
+		///Classes for the first glyph of the pair
+		class_def1: ClassDef,
+		///Classes for the second glyph of the pair
+		class_def2: ClassDef,
+		///Number of classes in classDef1 table — includes Class 0.
+		class1_count: u16,
+		///Number of classes in classDef2 table — includes Class 0.
+		class2_count: u16,
+		///Array of Class1 records, ordered by classes in classDef1.
+		class1_records: Box<[Class1Record]>,
+	},
+}
+impl_from_file!(PairPos, (), (), f, {
+	let start = f.stream_position().unwrap();
+	let format = unwrap_or_ret!(u16::from_file(f));
+	let coverage_offset = unwrap_or_ret!(Offset16::from_file(f));
+	let value_format1 = unwrap_or_ret!(u16::from_file(f));
+	let value_format2 = unwrap_or_ret!(u16::from_file(f));
+	match format{
+		1 => {
+			let pair_set_offsets: Box<[Offset16]> = unwrap_or_ret!(counted_array_from_file(f));
+			let mut pair_sets = Vec::with_capacity(pair_set_offsets.len());
+			for offset in pair_set_offsets.iter(){
+				if f.seek(SeekFrom::Start(start + *offset as u64)).is_err(){return Err(FromFileErr::EOF);}
+				pair_sets.push(unwrap_or_ret!(PairSet::from_file_with_formats(f, value_format1, value_format2)));
+			}
+			Ok(Self::Format1{
+				coverage: unwrap_or_ret!(from_file_at(f, start, coverage_offset as u32)),
+				value_format1,
+				value_format2,
+				pair_sets: pair_sets.into(),
+			})
+		},
+		2 => {
+			let class_def1_offset = unwrap_or_ret!(Offset16::from_file(f));
+			let class_def2_offset = unwrap_or_ret!(Offset16::from_file(f));
+			let class1_count = unwrap_or_ret!(u16::from_file(f));
+			let class2_count = unwrap_or_ret!(u16::from_file(f));
+			let mut class1_records = Vec::with_capacity(class1_count as usize);
+			for _ in 0..class1_count{
+				let mut class2_records = Vec::with_capacity(class2_count as usize);
+				for _ in 0..class2_count{class2_records.push(Class2Record{
+					value_record1: unwrap_or_ret!(ValueRecord::from_file_with_format(f, value_format1, start)),
+					value_record2: unwrap_or_ret!(ValueRecord::from_file_with_format(f, value_format2, start)),
+				});}
+				class1_records.push(Class1Record{class2_records: class2_records.into()});
+			}
+			Ok(Self::Format2{
+				coverage: unwrap_or_ret!(from_file_at(f, start, coverage_offset as u32)),
+				value_format1,
+				value_format2,
+				class_def1: unwrap_or_ret!(ClassDef::from_file_at_or_empty(f, start, class_def1_offset)),
+				class_def2: unwrap_or_ret!(ClassDef::from_file_at_or_empty(f, start, class_def2_offset)),
+				class1_count,
+				class2_count,
+				class1_records: class1_records.into(),
+			})
+		},
+		_ => Err(FromFileErr::InvalidData(())),
+	}
+});
+impl PairPos{
+	///The adjustments for the first and second glyph of the pair or `None` if the pair isn't in the subtable
+	pub fn adjustment(&self, first: u16, second: u16)->Option<(&ValueRecord, &ValueRecord)>{match self{
+		Self::Format1{coverage, pair_sets, ..} => {
+			let records = &pair_sets.get(coverage.index(first)? as usize)?.pair_value_records;
+			let record = &records[records.binary_search_by_key(&second, |r|r.second_glyph).ok()?];
+			Some((&record.value_record1, &record.value_record2))
+		},
+		Self::Format2{coverage, class_def1, class_def2, class1_records, ..} => {
+			coverage.index(first)?;
+			let record = class1_records.get(class_def1.class(first) as usize)?
+				.class2_records.get(class_def2.class(second) as usize)?;
+			Some((&record.value_record1, &record.value_record2))
+		},
+	}}
+}
+#[derive(Debug)]
+pub struct PairSet{
+	///Array of PairValueRecords, ordered by glyph ID of the second glyph.
+	pub pair_value_records: Box<[PairValueRecord]>,
+}
+impl PairSet{
+	fn from_file_with_formats<F>(f: &mut F, value_format1: u16, value_format2: u16)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		let start = f.stream_position().unwrap();
+		let pair_value_count = unwrap_or_ret!(u16::from_file(f));
+		let mut pair_value_records = Vec::with_capacity(pair_value_count as usize);
+		for _ in 0..pair_value_count{pair_value_records.push(PairValueRecord{
+			second_glyph: unwrap_or_ret!(u16::from_file(f)),
+			value_record1: unwrap_or_ret!(ValueRecord::from_file_with_format(f, value_format1, start)),
+			value_record2: unwrap_or_ret!(ValueRecord::from_file_with_format(f, value_format2, start)),
+		});}
+		Ok(Self{pair_value_records: pair_value_records.into()})
+	}
+}
+#[derive(Debug)]
+pub struct PairValueRecord{
+	///Glyph ID of second glyph in the pair (first glyph is listed in the Coverage table).
+	pub second_glyph: u16,
+	///Positioning data for the first glyph in the pair.
+	pub value_record1: ValueRecord,
+	///Positioning data for the second glyph in the pair.
+	pub value_record2: ValueRecord,
+}
+#[derive(Debug)]
+pub struct Class1Record{
+	///Array of Class2 records, ordered by classes in classDef2.
+	pub class2_records: Box<[Class2Record]>,
+}
+#[derive(Debug)]
+pub struct Class2Record{
+	///Positioning for first glyph — empty if valueFormat1 = 0.
+	pub value_record1: ValueRecord,
+	///Positioning for second glyph — empty if valueFormat2 = 0.
+	pub value_record2: ValueRecord,
+}
+
+#[derive(Debug)]
+pub struct CursivePos{
+	pub coverage: Coverage,
+	///Array of EntryExit records, in Coverage index order.
+	pub entry_exit_records: Box<[EntryExitRecord]>,
+}
+impl_from_file!(CursivePos, (), (), f, {
+	let start = f.stream_position().unwrap();
+	if unwrap_or_ret!(u16::from_file(f)) != 1{return Err(FromFileErr::InvalidData(()));}
+	let coverage_offset = unwrap_or_ret!(Offset16::from_file(f));
+	let entry_exit_count = unwrap_or_ret!(u16::from_file(f));
+	let anchor_offsets: Box<[Offset16]> = unwrap_or_ret!(array_from_file(f, entry_exit_count as usize * 2));
+	let mut entry_exit_records = Vec::with_capacity(entry_exit_count as usize);
+	for offsets in anchor_offsets.chunks_exact(2){entry_exit_records.push(EntryExitRecord{
+		entry_anchor: unwrap_or_ret!(anchor_at(f, start, offsets[0])),
+		exit_anchor: unwrap_or_ret!(anchor_at(f, start, offsets[1])),
+	});}
+	Ok(Self{
+		coverage: unwrap_or_ret!(from_file_at(f, start, coverage_offset as u32)),
+		entry_exit_records: entry_exit_records.into(),
+	})
+});
+impl CursivePos{
+	///The entry and exit anchors of `glyph_id` or `None` if it isn't covered
+	pub fn entry_exit(&self, glyph_id: u16)->Option<&EntryExitRecord>{
+		self.entry_exit_records.get(self.coverage.index(glyph_id)? as usize)
+	}
+}
+#[derive(Debug)]
+pub struct EntryExitRecord{
+	pub entry_anchor: Option<Anchor>,
+	pub exit_anchor: Option<Anchor>,
+}
+
+#[derive(Debug)]
+pub struct MarkArray{
+	///Array of MarkRecords, ordered by corresponding glyphs in the associated mark Coverage table.
+	pub mark_records: Box<[MarkRecord]>,
+}
+impl_from_file!(MarkArray, (), (), f, {
+	let start = f.stream_position().unwrap();
+	let mark_count = unwrap_or_ret!(u16::from_file(f));
+	let offsets: Box<[u16]> = unwrap_or_ret!(array_from_file(f, mark_count as usize * 2));
+	let mut mark_records = Vec::with_capacity(mark_count as usize);
+	for record in offsets.chunks_exact(2){mark_records.push(MarkRecord{
+		mark_class: record[0],
+		mark_anchor: unwrap_or_ret!(from_file_at(f, start, record[1] as u32)),
+	});}
+	Ok(Self{mark_records: mark_records.into()})
+});
+#[derive(Debug)]
+pub struct MarkRecord{
+	///Class defined for the associated mark.
+	pub mark_class: u16,
+	pub mark_anchor: Anchor,
+}
+///Rows of optional anchors with one column per mark class (used for base glyphs, ligature components and marks that marks attach to)
+#[derive(Debug)]
+pub struct AnchorMatrix{
+	pub rows: Box<[Box<[Option<Anchor>]>]>,
+}
+impl AnchorMatrix{
+	fn from_file_with_columns<F>(f: &mut F, mark_class_count: u16)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		let start = f.stream_position().unwrap();
+		let count = unwrap_or_ret!(u16::from_file(f));
+		let offsets: Box<[Offset16]> = unwrap_or_ret!(bounded_array_from_file(f, count as u32 * mark_class_count as u32, 2, u64::MAX));
+		let mut rows = Vec::with_capacity(count as usize);
+		for row in offsets.chunks_exact(mark_class_count.max(1) as usize){
+			let mut anchors = Vec::with_capacity(row.len());
+			for offset in row{anchors.push(unwrap_or_ret!(anchor_at(f, start, *offset)));}
+			rows.push(anchors.into());
+		}
+		Ok(Self{rows: rows.into()})
+	}
+	fn at<F>(f: &mut F, base: u64, offset: Offset16, mark_class_count: u16)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		if f.seek(SeekFrom::Start(base + offset as u64)).is_err(){return Err(FromFileErr::EOF);}
+		Self::from_file_with_columns(f, mark_class_count)
+	}
+	///The anchor in `row` for `mark_class`
+	pub fn get(&self, row: usize, mark_class: u16)->Option<&Anchor>{
+		self.rows.get(row)?.get(mark_class as usize)?.as_ref()
+	}
+}
+///The header shared by the mark attachment subtables
+struct MarkAttachmentHeader{
+	mark_coverage: Coverage,
+	coverage: Coverage,
+	mark_class_count: u16,
+	mark_array: MarkArray,
+	///Offset to the base, ligature or mark2 array
+	array_offset: Offset16,
+}
+impl MarkAttachmentHeader{
+	fn from_file_at_start<F>(f: &mut F, start: u64)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		if unwrap_or_ret!(u16::from_file(f)) != 1{return Err(FromFileErr::InvalidData(()));}
+		let mark_coverage_offset = unwrap_or_ret!(Offset16::from_file(f));
+		let coverage_offset = unwrap_or_ret!(Offset16::from_file(f));
+		let mark_class_count = unwrap_or_ret!(u16::from_file(f));
+		let mark_array_offset = unwrap_or_ret!(Offset16::from_file(f));
+		let array_offset = unwrap_or_ret!(Offset16::from_file(f));
+		Ok(Self{
+			mark_coverage: unwrap_or_ret!(from_file_at(f, start, mark_coverage_offset as u32)),
+			coverage: unwrap_or_ret!(from_file_at(f, start, coverage_offset as u32)),
+			mark_class_count,
+			mark_array: unwrap_or_ret!(from_file_at(f, start, mark_array_offset as u32)),
+			array_offset,
+		})
+	}
+}
+#[derive(Debug)]
+pub struct MarkBasePos{
+	pub mark_coverage: Coverage,
+	pub base_coverage: Coverage,
+	///Number of classes defined for marks
+	pub mark_class_count: u16,
+	pub mark_array: MarkArray,
+	///An anchor for each mark class of each base glyph (in base coverage index order)
+	pub base_array: AnchorMatrix,
+}
+impl_from_file!(MarkBasePos, (), (), f, {
+	let start = f.stream_position().unwrap();
+	let header = unwrap_or_ret!(MarkAttachmentHeader::from_file_at_start(f, start));
+	Ok(Self{
+		base_array: unwrap_or_ret!(AnchorMatrix::at(f, start, header.array_offset, header.mark_class_count)),
+		mark_coverage: header.mark_coverage,
+		base_coverage: header.coverage,
+		mark_class_count: header.mark_class_count,
+		mark_array: header.mark_array,
+	})
+});
+impl MarkBasePos{
+	///The (mark anchor, base anchor) used to attach `mark` to `base`
+	pub fn attachment(&self, mark: u16, base: u16)->Option<(&Anchor, &Anchor)>{
+		let mark_record = self.mark_array.mark_records.get(self.mark_coverage.index(mark)? as usize)?;
+		let base_anchor = self.base_array.get(self.base_coverage.index(base)? as usize, mark_record.mark_class)?;
+		Some((&mark_record.mark_anchor, base_anchor))
+	}
+}
+#[derive(Debug)]
+pub struct MarkLigPos{
+	pub mark_coverage: Coverage,
+	pub ligature_coverage: Coverage,
+	///Number of defined mark classes
+	pub mark_class_count: u16,
+	pub mark_array: MarkArray,
+	///For each ligature (in ligature coverage index order) an anchor for each mark class of each component
+	pub ligature_array: Box<[AnchorMatrix]>,
+}
+impl_from_file!(MarkLigPos, (), (), f, {
+	let start = f.stream_position().unwrap();
+	let header = unwrap_or_ret!(MarkAttachmentHeader::from_file_at_start(f, start));
+	let ligature_array_start = start + header.array_offset as u64;
+	if f.seek(SeekFrom::Start(ligature_array_start)).is_err(){return Err(FromFileErr::EOF);}
+	let ligature_attach_offsets: Box<[Offset16]> = unwrap_or_ret!(counted_array_from_file(f));
+	let mut ligature_array = Vec::with_capacity(ligature_attach_offsets.len());
+	for offset in ligature_attach_offsets.iter()
+	{ligature_array.push(unwrap_or_ret!(AnchorMatrix::at(f, ligature_array_start, *offset, header.mark_class_count)));}
+	Ok(Self{
+		mark_coverage: header.mark_coverage,
+		ligature_coverage: header.coverage,
+		mark_class_count: header.mark_class_count,
+		mark_array: header.mark_array,
+		ligature_array: ligature_array.into(),
+	})
+});
+impl MarkLigPos{
+	///The (mark anchor, ligature anchor) used to attach `mark` to the component `component` of `ligature`
+	pub fn attachment(&self, mark: u16, ligature: u16, component: usize)->Option<(&Anchor, &Anchor)>{
+		let mark_record = self.mark_array.mark_records.get(self.mark_coverage.index(mark)? as usize)?;
+		let ligature_attach = self.ligature_array.get(self.ligature_coverage.index(ligature)? as usize)?;
+		Some((&mark_record.mark_anchor, ligature_attach.get(component, mark_record.mark_class)?))
+	}
+}
+#[derive(Debug)]
+pub struct MarkMarkPos{
+	///The combining marks being attached
+	pub mark1_coverage: Coverage,
+	///The marks being attached to
+	pub mark2_coverage: Coverage,
+	///Number of Combining Mark classes defined
+	pub mark_class_count: u16,
+	pub mark1_array: MarkArray,
+	///An anchor for each mark class of each mark2 glyph (in mark2 coverage index order)
+	pub mark2_array: AnchorMatrix,
+}
+impl_from_file!(MarkMarkPos, (), (), f, {
+	let start = f.stream_position().unwrap();
+	let header = unwrap_or_ret!(MarkAttachmentHeader::from_file_at_start(f, start));
+	Ok(Self{
+		mark2_array: unwrap_or_ret!(AnchorMatrix::at(f, start, header.array_offset, header.mark_class_count)),
+		mark1_coverage: header.mark_coverage,
+		mark2_coverage: header.coverage,
+		mark_class_count: header.mark_class_count,
+		mark1_array: header.mark_array,
+	})
+});
+impl MarkMarkPos{
+	///The (mark1 anchor, mark2 anchor) used to attach `mark1` to `mark2`
+	pub fn attachment(&self, mark1: u16, mark2: u16)->Option<(&Anchor, &Anchor)>{
+		let mark_record = self.mark1_array.mark_records.get(self.mark1_coverage.index(mark1)? as usize)?;
+		let mark2_anchor = self.mark2_array.get(self.mark2_coverage.index(mark2)? as usize, mark_record.mark_class)?;
+		Some((&mark_record.mark_anchor, mark2_anchor))
+	}
+}
+
+///The glyph definition table
+#[derive(Debug)]
+pub struct GdefTable{
+	///Major version of the GDEF table, = 1
+	pub major_version: u16,
+	///Minor version of the GDEF table, = 0, 2 or 3
+	pub minor_version: u16,
+	///Offset to class definition table for glyph type, from beginning of GDEF header (may be NULL)
+	pub glyph_class_def_offset: Offset16,
+	///Offset to attachment point list table, from beginning of GDEF header (may be NULL)
+	pub attach_list_offset: Offset16,
+	///Offset to ligature caret list table, from beginning of GDEF header (may be NULL)
+	pub lig_caret_list_offset: Offset16,
+	///Offset to class definition table for mark attachment type, from beginning of GDEF header (may be NULL)
+	pub mark_attach_class_def_offset: Offset16,
+	///Version (>= 1.2) Offset to the table of mark glyph set definitions, from beginning of GDEF header (may be NULL)
+	pub mark_glyph_sets_def_offset: Option<Offset16>,
+	///Version (>= 1.3) Offset to the Item Variation Store table, from beginning of GDEF header (may be NULL)
+	pub item_var_store_offset: Option<Offset32>,
+	pub glyph_class_def: Option<ClassDef>,
+	pub attach_list: Option<AttachList>,
+	pub lig_caret_list: Option<LigCaretList>,
+	pub mark_attach_class_def: Option<ClassDef>,
+	pub mark_glyph_sets_def: Option<MarkGlyphSets>,
+	///Variation data for the VariationIndex tables in `GDEF` and `GPOS`
+	pub item_var_store: Option<ItemVariationStore>,
+}
+impl_from_file!(GdefTable, (), (), f, {
+	let start = f.stream_position().unwrap();
+	let major_version = unwrap_or_ret!(u16::from_file(f));
+	let minor_version = unwrap_or_ret!(u16::from_file(f));
+	if major_version != 1{return Err(FromFileErr::InvalidData(()));}
+	let glyph_class_def_offset = unwrap_or_ret!(Offset16::from_file(f));
+	let attach_list_offset = unwrap_or_ret!(Offset16::from_file(f));
+	let lig_caret_list_offset = unwrap_or_ret!(Offset16::from_file(f));
+	let mark_attach_class_def_offset = unwrap_or_ret!(Offset16::from_file(f));
+	let mark_glyph_sets_def_offset = if minor_version >= 2{Some(unwrap_or_ret!(Offset16::from_file(f)))}else{None};
+	let item_var_store_offset = if minor_version >= 3{Some(unwrap_or_ret!(Offset32::from_file(f)))}else{None};
+	macro_rules! optional_at{($offset: expr) => {match $offset as u32{
+		0 => None,
+		o => Some(unwrap_or_ret!(from_file_at(f, start, o))),
+	}};}
+	Ok(Self{
+		major_version,
+		minor_version,
+		glyph_class_def_offset,
+		attach_list_offset,
+		lig_caret_list_offset,
+		mark_attach_class_def_offset,
+		mark_glyph_sets_def_offset,
+		item_var_store_offset,
+		glyph_class_def: optional_at!(glyph_class_def_offset),
+		attach_list: optional_at!(attach_list_offset),
+		lig_caret_list: optional_at!(lig_caret_list_offset),
+		mark_attach_class_def: optional_at!(mark_attach_class_def_offset),
+		mark_glyph_sets_def: optional_at!(mark_glyph_sets_def_offset.unwrap_or(0)),
+		item_var_store: optional_at!(item_var_store_offset.unwrap_or(0)),
+	})
+});
+impl GdefTable{
+	///The class of `glyph_id` in the glyph class definition table
+	pub fn glyph_class(&self, glyph_id: u16)->GlyphClass{
+		match self.glyph_class_def.as_ref().map_or(0, |c|c.class(glyph_id)){
+			1 => GlyphClass::Base,
+			2 => GlyphClass::Ligature,
+			3 => GlyphClass::Mark,
+			4 => GlyphClass::Component,
+			_ => GlyphClass::Unclassified,
+		}
+	}
+	///The mark attachment class of `glyph_id` (0 if it has none)
+	pub fn mark_attachment_class(&self, glyph_id: u16)->u16{
+		self.mark_attach_class_def.as_ref().map_or(0, |c|c.class(glyph_id))
+	}
+	///The contour point indices of the attachment points of `glyph_id`
+	pub fn attach_points(&self, glyph_id: u16)->Option<&[u16]>{
+		let attach_list = self.attach_list.as_ref()?;
+		attach_list.attach_points.get(attach_list.coverage.index(glyph_id)? as usize).map(|p|&*p.point_indices)
+	}
+	///The caret positions between the components of the ligature `glyph_id` in increasing coordinate order
+	pub fn ligature_carets(&self, glyph_id: u16)->Option<&[CaretValue]>{
+		let lig_caret_list = self.lig_caret_list.as_ref()?;
+		lig_caret_list.lig_glyphs.get(lig_caret_list.coverage.index(glyph_id)? as usize).map(|l|&*l.caret_values)
+	}
+	///Whether `glyph_id` is in the mark glyph set `set` (`false` if there is no such set)
+	pub fn is_in_mark_glyph_set(&self, set: u16, glyph_id: u16)->bool{
+		self.mark_glyph_sets_def.as_ref().and_then(|s|s.coverages.get(set as usize)).is_some_and(|c|c.contains(glyph_id))
+	}
+	///Whether a lookup with the flags `lookup_flag` (and mark filtering set `mark_filtering_set`) skips over `glyph_id`
+	pub fn ignores_glyph(&self, lookup_flag: LookupFlag, mark_filtering_set: Option<u16>, glyph_id: u16)->bool{
+		match self.glyph_class(glyph_id){
+			GlyphClass::Base => lookup_flag.has_flag(LookupFlag::IGNORE_BASE_GLYPHS),
+			GlyphClass::Ligature => lookup_flag.has_flag(LookupFlag::IGNORE_LIGATURES),
+			GlyphClass::Mark => {
+				if lookup_flag.has_flag(LookupFlag::IGNORE_MARKS){return true;}
+				if lookup_flag.has_flag(LookupFlag::USE_MARK_FILTERING_SET){
+					return !mark_filtering_set.is_some_and(|s|self.is_in_mark_glyph_set(s, glyph_id));
+				}
+				let mark_attachment_type = lookup_flag.mark_attachment_type();
+				mark_attachment_type != 0 && self.mark_attachment_class(glyph_id) != mark_attachment_type
+			},
+			GlyphClass::Unclassified | GlyphClass::Component => false,
+		}
+	}
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlyphClass{
+	///Glyphs not assigned a class (class 0)
+	Unclassified,
+	///Base glyph (single character, spacing glyph)
+	Base,
+	///Ligature glyph (multiple character, spacing glyph)
+	Ligature,
+	///Mark glyph (non-spacing combining glyph)
+	Mark,
+	///Component glyph (part of single character, spacing glyph)
+	Component,
+}
+#[derive(Debug)]
+pub struct AttachList{
+	pub coverage: Coverage,
+	///AttachPoint tables ordered by Coverage index
+	pub attach_points: Box<[AttachPoint]>,
+}
+impl_from_file!(AttachList, (), (), f, {
+	let start = f.stream_position().unwrap();
+	let coverage_offset = unwrap_or_ret!(Offset16::from_file(f));
+	let attach_points = unwrap_or_ret!(offset16_array_from_file(f, start));
+	Ok(Self{coverage: unwrap_or_ret!(from_file_at(f, start, coverage_offset as u32)), attach_points})
+});
+#[derive(Debug,FromFile)]
+pub struct AttachPoint{
+	///Number of attachment points on this glyph
+	pub point_count: u16,
+	///Array of contour point indices — in increasing numerical order
+	#[from_file_count(point_count)]
+	pub point_indices: Box<[u16]>,
+}
+#[derive(Debug)]
+pub struct LigCaretList{
+	pub coverage: Coverage,
+	///LigGlyph tables ordered by Coverage index
+	pub lig_glyphs: Box<[LigGlyph]>,
+}
+impl_from_file!(LigCaretList, (), (), f, {
+	let start = f.stream_position().unwrap();
+	let coverage_offset = unwrap_or_ret!(Offset16::from_file(f));
+	let lig_glyphs = unwrap_or_ret!(offset16_array_from_file(f, start));
+	Ok(Self{coverage: unwrap_or_ret!(from_file_at(f, start, coverage_offset as u32)), lig_glyphs})
+});
+#[derive(Debug)]
+pub struct LigGlyph{
+	///CaretValue tables for the ligature — in increasing coordinate order
+	pub caret_values: Box<[CaretValue]>,
+}
+impl_from_file!(LigGlyph, (), (), f, {
+	let start = f.stream_position().unwrap();
+	Ok(Self{caret_values: unwrap_or_ret!(offset16_array_from_file(f, start))})
+});
+#[derive(Debug)]
+pub enum CaretValue{
+	///X or Y value, in design units
+	Format1{coordinate: FWord},
+	///Contour point index on glyph
+	Format2{caret_value_point_index: u16},
+	///X or Y value, in design units, plus a Device or VariationIndex table
+	Format3{coordinate: FWord, device: Option<DeviceTable>},
+}
+impl_from_file!(CaretValue, (), (), f, {
+	let start = f.stream_position().unwrap();
+	match unwrap_or_ret!(u16::from_file(f)){
+		1 => Ok(Self::Format1{coordinate: unwrap_or_ret!(FWord::from_file(f))}),
+		2 => Ok(Self::Format2{caret_value_point_index: unwrap_or_ret!(u16::from_file(f))}),
+		3 => {
+			let coordinate = unwrap_or_ret!(FWord::from_file(f));
+			let device = match unwrap_or_ret!(Offset16::from_file(f)){
+				0 => None,
+				o => Some(unwrap_or_ret!(from_file_at(f, start, o as u32))),
+			};
+			Ok(Self::Format3{coordinate, device})
+		},
+		_ => Err(FromFileErr::InvalidData(())),
+	}
+});
+impl CaretValue{
+	///The caret position in design units or `None` if it is given by a contour point (which needs the hinted outline)
+	pub fn coordinate(&self)->Option<FWord>{match self{
+		Self::Format1{coordinate} | Self::Format3{coordinate, ..} => Some(*coordinate),
+		Self::Format2{..} => None,
+	}}
+}
+#[derive(Debug)]
+pub struct MarkGlyphSets{
+	///Format identifier == 1
+	pub format: u16,
+	///Number of mark glyph sets defined
+	pub mark_glyph_set_count: u16,
+	///Array of offsets to mark glyph set coverage tables, from the start of the MarkGlyphSets table.
+	pub coverage_offsets: Box<[Offset32]>,
+	pub coverages: Box<[Coverage]>,
+}
+impl_from_file!(MarkGlyphSets, (), (), f, {
+	let start = f.stream_position().unwrap();
+	let format = unwrap_or_ret!(u16::from_file(f));
+	if format != 1{return Err(FromFileErr::InvalidData(()));}
+	let mark_glyph_set_count = unwrap_or_ret!(u16::from_file(f));
+	let coverage_offsets: Box<[Offset32]> = unwrap_or_ret!(array_from_file(f, mark_glyph_set_count as usize));
+	let mut coverages = Vec::with_capacity(coverage_offsets.len());
+	for offset in coverage_offsets.iter(){coverages.push(unwrap_or_ret!(from_file_at(f, start, *offset)));}
+	Ok(Self{format, mark_glyph_set_count, coverage_offsets, coverages: coverages.into()})
+});
+
+///The legacy kerning table in either the Microsoft (version 0) or Apple (version 1.0) format
+#[derive(Debug)]
+pub struct KernTable{
+	///0 for the Microsoft format or 0x00010000 for the Apple format
+	pub version: u32,
+	///Number of subtables in the kerning table.
+	pub n_tables: u32,
+	pub subtables: Box<[KernSubtable]>,
+}
+impl_from_file!(KernTable, (), (), f, {
+	let version_major = unwrap_or_ret!(u16::from_file(f));
+	// the Microsoft header has a 16 bit version and count where the Apple one has 32 bit ones
+	let (version, n_tables, apple) = match version_major{
+		0 => (0, unwrap_or_ret!(u16::from_file(f)) as u32, false),
+		1 => {
+			let version = 0x00010000 | unwrap_or_ret!(u16::from_file(f)) as u32;
+			(version, unwrap_or_ret!(u32::from_file(f)), true)
+		},
+		_ => return Err(FromFileErr::InvalidData(())),
+	};
+	let mut subtables = Vec::with_capacity(n_tables.min(256) as usize);
+	for _ in 0..n_tables{subtables.push(unwrap_or_ret!(KernSubtable::from_file_with_header(f, apple)));}
+	Ok(Self{version, n_tables, subtables: subtables.into()})
+});
+impl KernTable{
+	pub const APPLE_VERSION: u32 = 0x00010000;
+	pub fn is_apple(&self)->bool{self.version == Self::APPLE_VERSION}
+	///The horizontal kerning between `left_glyph` and `right_glyph` in design units (0 if there is none)
+	pub fn kerning(&self, left_glyph: u16, right_glyph: u16)->i16{
+		let mut rv = 0i16;
+		for subtable in self.subtables.iter(){
+			if !subtable.is_horizontal() || subtable.is_cross_stream() || subtable.is_minimum() || subtable.is_variation(){continue;}
+			let value = match subtable.kerning(left_glyph, right_glyph){
+				Some(v) => v,
+				None => continue,
+			};
+			rv = if subtable.is_override(){value}else{rv.wrapping_add(value)};
+		}
+		rv
+	}
+}
+#[derive(Debug)]
+pub struct KernSubtable{
+	///Length of the subtable in bytes (including the header)
+	pub length: u32,
+	///What type of information is contained in this subtable (the layout of the bits differs between the Microsoft and Apple formats)
+	pub coverage: u16,
+	///Apple only: the tuple index (used for variations fonts), `None` for Microsoft subtables
+	pub tuple_index: Option<u16>,
+	pub data: KernSubtableData,
+}
+impl KernSubtable{
+	///Microsoft: 1 if table has horizontal data, 0 if vertical.
+	pub const HORIZONTAL: u16 = 0x0001;
+	///Microsoft: If set to 1, the table has minimum values. If set to 0, the table has kerning values.
+	pub const MINIMUM: u16 = 0x0002;
+	///Microsoft: If set to 1, kerning is perpendicular to the flow of the text.
+	pub const CROSS_STREAM: u16 = 0x0004;
+	///Microsoft: If this bit is set to 1 the value in this table should replace the value currently being accumulated.
+	pub const OVERRIDE: u16 = 0x0008;
+	///Apple: Set if table has vertical kerning values.
+	pub const APPLE_VERTICAL: u16 = 0x8000;
+	///Apple: Set if table has cross-stream kerning values.
+	pub const APPLE_CROSS_STREAM: u16 = 0x4000;
+	///Apple: Set if table has variation kerning values.
+	pub const APPLE_VARIATION: u16 = 0x2000;
+
+	fn from_file_with_header<F>(f: &mut F, apple: bool)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		let start = f.stream_position().unwrap();
+		let (length, coverage, tuple_index) = if apple{(
+			unwrap_or_ret!(u32::from_file(f)),
+			unwrap_or_ret!(u16::from_file(f)),
+			Some(unwrap_or_ret!(u16::from_file(f))),
+		)}else{
+			let _version = unwrap_or_ret!(u16::from_file(f));
+			(unwrap_or_ret!(u16::from_file(f)) as u32, unwrap_or_ret!(u16::from_file(f)), None)
+		};
+		let format = if apple{coverage & 0xFF}else{coverage >> 8} as u8;
+		let header_size = f.stream_position().unwrap() - start;
+		let data = match format{
+			0 => KernSubtableData::Format0(unwrap_or_ret!(KernFormat0::from_file(f))),
+			2 => KernSubtableData::Format2(unwrap_or_ret!(KernFormat2::from_file_with_range(f, start, length))),
+			3 => KernSubtableData::Format3(unwrap_or_ret!(KernFormat3::from_file(f))),
+			format => KernSubtableData::Unknown(format),
+		};
+		// format 0 subtables with many pairs overflow the 16 bit length of Microsoft subtables so use the number of pairs instead
+		let end = match &data{
+			KernSubtableData::Format0(k) => start + header_size + 8 + k.pairs.len() as u64 * 6,
+			_ => start + length as u64,
+		};
+		if f.seek(SeekFrom::Start(end)).is_err(){return Err(FromFileErr::EOF);}
+		Ok(Self{length, coverage, tuple_index, data})
+	}
+	pub fn is_apple(&self)->bool{self.tuple_index.is_some()}
+	///The format of the subtable data
+	pub fn format(&self)->u8{if self.is_apple(){(self.coverage & 0xFF) as u8}else{(self.coverage >> 8) as u8}}
+	pub fn is_horizontal(&self)->bool{
+		if self.is_apple(){self.coverage & Self::APPLE_VERTICAL == 0}else{self.coverage & Self::HORIZONTAL != 0}
+	}
+	pub fn is_cross_stream(&self)->bool{
+		self.coverage & if self.is_apple(){Self::APPLE_CROSS_STREAM}else{Self::CROSS_STREAM} != 0
+	}
+	pub fn is_minimum(&self)->bool{!self.is_apple() && self.coverage & Self::MINIMUM != 0}
+	pub fn is_override(&self)->bool{!self.is_apple() && self.coverage & Self::OVERRIDE != 0}
+	pub fn is_variation(&self)->bool{self.is_apple() && self.coverage & Self::APPLE_VARIATION != 0}
+	///The value for the pair in this subtable or `None` if the subtable can't be used
+	pub fn kerning(&self, left_glyph: u16, right_glyph: u16)->Option<i16>{match &self.data{
+		KernSubtableData::Format0(k) => Some(k.kerning(left_glyph, right_glyph)),
+		KernSubtableData::Format2(k) => Some(k.kerning(left_glyph, right_glyph)),
+		KernSubtableData::Format3(k) => Some(k.kerning(left_glyph, right_glyph)),
+		KernSubtableData::Unknown(_) => None,
+	}}
+}
+#[derive(Debug)]
+pub enum KernSubtableData{
+	///Ordered list of kerning pairs
+	Format0(KernFormat0),
+	///Two-dimensional array of kerning values indexed by class
+	Format2(KernFormat2),
+	///Simple n x m array of kerning indices (Apple only)
+	Format3(KernFormat3),
+	///A format this library doesn't read (such as the Apple state table format 1)
+	Unknown(u8),
+}
+#[derive(Debug,FromFile)]
+pub struct KernFormat0{
+	///This gives the number of kerning pairs in the table.
+	pub n_pairs: u16,
+	///The largest power of two less than or equal to the value of nPairs, multiplied by the size in bytes of an entry in the table.
+	pub search_range: u16,
+	///This is calculated as log2 of the largest power of two less than or equal to the value of nPairs.
+	pub entry_selector: u16,
+	///The value of nPairs minus the largest power of two less than or equal to nPairs, and then multiplied by the size in bytes of an entry in the table.
+	pub range_shift: u16,
+	///Array of KernPair records ordered by the left then right glyph.
+	#[from_file_count(n_pairs)]
+	pub pairs: Box<[KernPair]>,
+}
+impl KernFormat0{
+	pub fn kerning(&self, left_glyph: u16, right_glyph: u16)->i16{
+		match self.pairs.binary_search_by_key(&(left_glyph, right_glyph), |p|(p.left, p.right)){
+			Ok(i) => self.pairs[i].value,
+			Err(_) => 0,
+		}
+	}
+}
+#[derive(Debug,FromFile)]
+pub struct KernPair{
+	///The glyph index for the left-hand glyph in the kerning pair.
+	pub left: u16,
+	///The glyph index for the right-hand glyph in the kerning pair.
+	pub right: u16,
+	///The kerning value for the above pair, in design units.
+	pub value: FWord,
+}
+#[derive(Debug)]
+pub struct KernFormat2{
+	///The width, in bytes, of a row in the table.
+	pub row_width: u16,
+	///Offset from beginning of this subtable to left-hand class table.
+	pub left_class_offset: Offset16,
+	///Offset from beginning of this subtable to right-hand class table.
+	pub right_class_offset: Offset16,
+	///Offset from beginning of this subtable to the start of the kerning array.
+	pub kerning_array_offset: Offset16,
+	pub left_class_table: KernClassTable,
+	pub right_class_table: KernClassTable,
+	///The kerning array from its start to the end of the subtable
+	pub kerning_array: Box<[FWord]>,
+}
+impl KernFormat2{
+	fn from_file_with_range<F>(f: &mut F, start: u64, length: u32)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		let row_width = unwrap_or_ret!(u16::from_file(f));
+		let left_class_offset = unwrap_or_ret!(Offset16::from_file(f));
+		let right_class_offset = unwrap_or_ret!(Offset16::from_file(f));
+		let kerning_array_offset = unwrap_or_ret!(Offset16::from_file(f));
+		let left_class_table = unwrap_or_ret!(from_file_at(f, start, left_class_offset as u32));
+		let right_class_table = unwrap_or_ret!(from_file_at(f, start, right_class_offset as u32));
+		if f.seek(SeekFrom::Start(start + kerning_array_offset as u64)).is_err(){return Err(FromFileErr::EOF);}
+		let count = length.saturating_sub(kerning_array_offset as u32) / 2;
+		let kerning_array = unwrap_or_ret!(array_from_file(f, count as usize));
+		Ok(Self{
+			row_width,
+			left_class_offset,
+			right_class_offset,
+			kerning_array_offset,
+			left_class_table,
+			right_class_table,
+			kerning_array,
+		})
+	}
+	pub fn kerning(&self, left_glyph: u16, right_glyph: u16)->i16{
+		// the left classes are offsets to the start of a row and the right classes offsets within it
+		let offset = self.left_class_table.class(left_glyph) as usize + self.right_class_table.class(right_glyph) as usize;
+		match offset.checked_sub(self.kerning_array_offset as usize){
+			Some(o) => self.kerning_array.get(o / 2).copied().unwrap_or(0),
+			None => 0,
+		}
+	}
+}
+#[derive(Debug,FromFile)]
+pub struct KernClassTable{
+	///First glyph in class range.
+	pub first_glyph: u16,
+	///Number of glyph in class range.
+	pub n_glyphs: u16,
+	///The class values (as byte offsets) of each glyph in the range
+	#[from_file_count(n_glyphs)]
+	pub offsets: Box<[u16]>,
+}
+impl KernClassTable{
+	pub fn class(&self, glyph_id: u16)->u16{
+		glyph_id.checked_sub(self.first_glyph).and_then(|i|self.offsets.get(i as usize)).copied().unwrap_or(0)
+	}
+}
+#[derive(Debug)]
+pub struct KernFormat3{
+	///The number of glyphs in this font.
+	pub glyph_count: u16,
+	///The number of kerning values.
+	pub kern_value_count: u8,
+	///The number of left-hand classes.
+	pub left_class_count: u8,
+	///The number of right-hand classes.
+	pub right_class_count: u8,
+	///Set to zero (reserved for future use).
+	pub flags: u8,
+	///The kerning values.
+	pub kern_value: Box<[FWord]>,
+	///The left-hand classes.
+	pub left_class: Box<[u8]>,
+	///The right-hand classes.
+	pub right_class: Box<[u8]>,
+	///The indices into the kernValue array.
+	pub kern_index: Box<[u8]>,
+}
+impl_from_file!(KernFormat3, (), (), f, {
+	let glyph_count = unwrap_or_ret!(u16::from_file(f));
+	let kern_value_count = unwrap_or_ret!(u8::from_file(f));
+	let left_class_count = unwrap_or_ret!(u8::from_file(f));
+	let right_class_count = unwrap_or_ret!(u8::from_file(f));
+	let flags = unwrap_or_ret!(u8::from_file(f));
+	Ok(Self{
+		glyph_count,
+		kern_value_count,
+		left_class_count,
+		right_class_count,
+		flags,
+		kern_value: unwrap_or_ret!(array_from_file(f, kern_value_count as usize)),
+		left_class: unwrap_or_ret!(array_from_file(f, glyph_count as usize)),
+		right_class: unwrap_or_ret!(array_from_file(f, glyph_count as usize)),
+		kern_index: unwrap_or_ret!(array_from_file(f, left_class_count as usize * right_class_count as usize)),
+	})
+});
+impl KernFormat3{
+	pub fn kerning(&self, left_glyph: u16, right_glyph: u16)->i16{
+		let (left, right) = match (self.left_class.get(left_glyph as usize), self.right_class.get(right_glyph as usize)){
+			(Some(l), Some(r)) => (*l as usize, *r as usize),
+			_ => return 0,
+		};
+		if left >= self.left_class_count as usize || right >= self.right_class_count as usize{return 0;}
+		self.kern_index.get(left * self.right_class_count as usize + right)
+			.and_then(|i|self.kern_value.get(*i as usize)).copied().unwrap_or(0)
+	}
+}
+
+///The `cvt ` table holds values that can be referenced by instructions, its size is only known from the table record
+#[derive(Debug)]
+pub struct CvtTable{
+	///List of n values referenceable by instructions. n is the number of FWORD items that fit in the size of the table.
+	pub values: Box<[FWord]>,
+}
+impl CvtTable{
+	pub fn from_file_with_length<F>(f: &mut F, length: u32)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		Ok(Self{values: unwrap_or_ret!(bounded_array_from_file(f, length / 2, 2, u64::MAX))})
+	}
+}
+
+///The `fpgm` table holds the font program which is run once before any other instructions, its size is only known from the table record
+#[derive(Debug)]
+pub struct FpgmTable{
+	///Instructions. n is the number of uint8 items that fit in the size of the table.
+	pub instructions: Box<[u8]>,
+}
+impl FpgmTable{
+	pub fn from_file_with_length<F>(f: &mut F, length: u32)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		Ok(Self{instructions: unwrap_or_ret!(bounded_array_from_file(f, length, 1, u64::MAX))})
+	}
+}
+
+///The `prep` table holds the control value program which is run whenever the point size or transformation changes, its size is only known from the table record
+#[derive(Debug)]
+pub struct PrepTable{
+	///Set of instructions executed whenever point size or font or transformation change. n is the number of uint8 items that fit in the size of the table.
+	pub instructions: Box<[u8]>,
+}
+impl PrepTable{
+	pub fn from_file_with_length<F>(f: &mut F, length: u32)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		Ok(Self{instructions: unwrap_or_ret!(bounded_array_from_file(f, length, 1, u64::MAX))})
+	}
+}
+
+pub fn calc_table_checksum<T>(table: T, length: u32) -> u32 where T: Fn(usize) -> u32{
+	let mut sum = 0u32;
+	let endptr = ((length+3) & !3) / 4;
+	let mut i = 0;
+	while i < endptr as usize {
+		sum = sum.wrapping_add(table(i));
+		i+=1;
+	}
+	return sum;
+}
+///The checksum of a table held in memory, the missing bytes of the last word are zero
+pub fn calc_checksum(data: &[u8]) -> u32{
+	calc_table_checksum(|i|{
+		let mut word = [0u8; 4];
+		let end = (i * 4 + 4).min(data.len());
+		word[..end - i * 4].copy_from_slice(&data[i * 4..end]);
+		u32::from_be_bytes(word)
+	}, data.len() as u32)
 }
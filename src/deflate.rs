@@ -0,0 +1,296 @@
+use crate::core::FromFileErr;
+
+///Base length of each length code from 257
+const LENGTH_BASE: [u16; 29] = [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+const LENGTH_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+///Base distance of each distance code
+const DISTANCE_BASE: [u16; 30] = [
+	1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+///The order code lengths of the code length alphabet are stored in
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+const MAX_BITS: usize = 15;
+const WINDOW_SIZE: usize = 32768;
+const END_OF_BLOCK: u16 = 256;
+
+///Reads bits from the least significant bit of each byte first
+struct BitReader<'a>{
+	data: &'a [u8],
+	pos: usize,
+	buffer: u32,
+	count: u32,
+}
+impl<'a> BitReader<'a>{
+	fn new(data: &'a [u8])->Self{Self{data, pos: 0, buffer: 0, count: 0}}
+	fn bits(&mut self, n: u32)->Result<u32, FromFileErr<(),()>>{
+		while self.count < n{
+			let Some(byte) = self.data.get(self.pos) else{return Err(FromFileErr::EOF)};
+			self.pos += 1;
+			self.buffer |= (*byte as u32) << self.count;
+			self.count += 8;
+		}
+		let rv = self.buffer & ((1u64 << n) - 1) as u32;
+		self.buffer = self.buffer.checked_shr(n).unwrap_or(0);
+		self.count -= n;
+		Ok(rv)
+	}
+	///Drops the bits left in the current byte
+	fn align(&mut self){
+		self.buffer = 0;
+		self.count = 0;
+	}
+}
+
+///A canonical Huffman code as the number of codes of each length and the symbols in code order
+struct Huffman{
+	counts: [u16; MAX_BITS + 1],
+	symbols: Vec<u16>,
+}
+impl Huffman{
+	fn new(lengths: &[u8])->Result<Self, FromFileErr<(),()>>{
+		let mut counts = [0u16; MAX_BITS + 1];
+		for l in lengths{counts[*l as usize] += 1;}
+		counts[0] = 0;
+		// a code is invalid if it has more codes of a length than there is room for
+		let mut left = 1i32;
+		for count in counts[1..].iter(){
+			left = (left << 1) - *count as i32;
+			if left < 0{return Err(FromFileErr::InvalidData(()));}
+		}
+		let mut offsets = [0u16; MAX_BITS + 2];
+		for l in 1..=MAX_BITS{offsets[l + 1] = offsets[l] + counts[l];}
+		let mut symbols = vec![0; offsets[MAX_BITS + 1] as usize];
+		for (symbol, l) in lengths.iter().enumerate(){if *l != 0{
+			symbols[offsets[*l as usize] as usize] = symbol as u16;
+			offsets[*l as usize] += 1;
+		}}
+		Ok(Self{counts, symbols})
+	}
+	fn decode(&self, reader: &mut BitReader)->Result<u16, FromFileErr<(),()>>{
+		let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+		for l in 1..=MAX_BITS{
+			code |= unwrap_or_ret!(reader.bits(1)) as i32;
+			let count = self.counts[l] as i32;
+			if code - first < count{return Ok(self.symbols[(index + code - first) as usize]);}
+			index += count;
+			first = (first + count) << 1;
+			code <<= 1;
+		}
+		Err(FromFileErr::InvalidData(()))
+	}
+}
+
+///The literal/length and distance code of a block compressed with fixed codes
+fn fixed_lengths()->([u8; 288], [u8; 30]){
+	let mut literals = [8u8; 288];
+	literals[144..256].fill(9);
+	literals[256..280].fill(7);
+	(literals, [5; 30])
+}
+
+///Decompresses raw DEFLATE data, failing if the output would be longer than `limit`
+pub fn inflate(data: &[u8], limit: usize)->Result<Vec<u8>, FromFileErr<(),()>>{
+	let mut reader = BitReader::new(data);
+	let mut out = Vec::new();
+	loop{
+		let last = unwrap_or_ret!(reader.bits(1)) == 1;
+		match unwrap_or_ret!(reader.bits(2)){
+			0 => {
+				reader.align();
+				let Some(header) = reader.data.get(reader.pos..reader.pos + 4) else{return Err(FromFileErr::EOF)};
+				let length = u16::from_le_bytes([header[0], header[1]]);
+				if length != !u16::from_le_bytes([header[2], header[3]]){return Err(FromFileErr::InvalidData(()));}
+				reader.pos += 4;
+				let Some(stored) = reader.data.get(reader.pos..reader.pos + length as usize) else{return Err(FromFileErr::EOF)};
+				if out.len() + stored.len() > limit{return Err(FromFileErr::InvalidData(()));}
+				out.extend_from_slice(stored);
+				reader.pos += length as usize;
+			},
+			1 => {
+				let (literals, distances) = fixed_lengths();
+				unwrap_or_ret!(inflate_block(&mut reader, &mut out, &unwrap_or_ret!(Huffman::new(&literals)), &unwrap_or_ret!(Huffman::new(&distances)), limit));
+			},
+			2 => {
+				let literal_count = unwrap_or_ret!(reader.bits(5)) as usize + 257;
+				let distance_count = unwrap_or_ret!(reader.bits(5)) as usize + 1;
+				let code_length_count = unwrap_or_ret!(reader.bits(4)) as usize + 4;
+				let mut code_lengths = [0u8; 19];
+				for i in CODE_LENGTH_ORDER.iter().take(code_length_count){code_lengths[*i] = unwrap_or_ret!(reader.bits(3)) as u8;}
+				let code_length_code = unwrap_or_ret!(Huffman::new(&code_lengths));
+				let mut lengths = Vec::with_capacity(literal_count + distance_count);
+				while lengths.len() < literal_count + distance_count{
+					let (value, repeat) = match unwrap_or_ret!(code_length_code.decode(&mut reader)){
+						l @ 0..=15 => (l as u8, 1),
+						16 => match lengths.last(){
+							Some(l) => (*l, 3 + unwrap_or_ret!(reader.bits(2))),
+							None => return Err(FromFileErr::InvalidData(())),
+						},
+						17 => (0, 3 + unwrap_or_ret!(reader.bits(3))),
+						_ => (0, 11 + unwrap_or_ret!(reader.bits(7))),
+					};
+					if lengths.len() + repeat as usize > literal_count + distance_count{return Err(FromFileErr::InvalidData(()));}
+					lengths.extend(std::iter::repeat_n(value, repeat as usize));
+				}
+				if lengths[END_OF_BLOCK as usize] == 0{return Err(FromFileErr::InvalidData(()));}
+				let literal_code = unwrap_or_ret!(Huffman::new(&lengths[..literal_count]));
+				let distance_code = unwrap_or_ret!(Huffman::new(&lengths[literal_count..]));
+				unwrap_or_ret!(inflate_block(&mut reader, &mut out, &literal_code, &distance_code, limit));
+			},
+			_ => return Err(FromFileErr::InvalidData(())),
+		}
+		if last{return Ok(out);}
+	}
+}
+fn inflate_block(reader: &mut BitReader, out: &mut Vec<u8>, literal_code: &Huffman, distance_code: &Huffman, limit: usize)->Result<(), FromFileErr<(),()>>{
+	loop{
+		let symbol = unwrap_or_ret!(literal_code.decode(reader));
+		if symbol < END_OF_BLOCK{
+			if out.len() >= limit{return Err(FromFileErr::InvalidData(()));}
+			out.push(symbol as u8);
+			continue;
+		}
+		if symbol == END_OF_BLOCK{return Ok(());}
+		let symbol = (symbol - 257) as usize;
+		if symbol >= LENGTH_BASE.len(){return Err(FromFileErr::InvalidData(()));}
+		let length = LENGTH_BASE[symbol] as usize + unwrap_or_ret!(reader.bits(LENGTH_EXTRA[symbol] as u32)) as usize;
+		let symbol = unwrap_or_ret!(distance_code.decode(reader)) as usize;
+		if symbol >= DISTANCE_BASE.len(){return Err(FromFileErr::InvalidData(()));}
+		let distance = DISTANCE_BASE[symbol] as usize + unwrap_or_ret!(reader.bits(DISTANCE_EXTRA[symbol] as u32)) as usize;
+		if distance > out.len() || out.len() + length > limit{return Err(FromFileErr::InvalidData(()));}
+		// the copy can overlap the bytes it produces
+		let start = out.len() - distance;
+		for i in 0..length{out.push(out[start + i]);}
+	}
+}
+
+///Writes bits from the least significant bit of each byte first
+struct BitWriter{
+	out: Vec<u8>,
+	buffer: u64,
+	count: u32,
+}
+impl BitWriter{
+	fn bits(&mut self, value: u32, n: u32){
+		self.buffer |= (value as u64) << self.count;
+		self.count += n;
+		while self.count >= 8{
+			self.out.push(self.buffer as u8);
+			self.buffer >>= 8;
+			self.count -= 8;
+		}
+	}
+	///Writes a Huffman code, which are stored from their most significant bit
+	fn code(&mut self, code: u16, length: u8){
+		self.bits((code.reverse_bits() >> (16 - length)) as u32, length as u32);
+	}
+	fn finish(mut self)->Vec<u8>{
+		if self.count > 0{self.out.push(self.buffer as u8);}
+		self.out
+	}
+}
+
+///The canonical codes for the given code lengths
+fn canonical_codes(lengths: &[u8])->Vec<u16>{
+	let mut counts = [0u16; MAX_BITS + 1];
+	for l in lengths{counts[*l as usize] += 1;}
+	counts[0] = 0;
+	let mut next = [0u16; MAX_BITS + 1];
+	for l in 1..=MAX_BITS{next[l] = (next[l - 1] + counts[l - 1]) << 1;}
+	lengths.iter().map(|l|if *l == 0{0}else{
+		let code = next[*l as usize];
+		next[*l as usize] += 1;
+		code
+	}).collect()
+}
+
+///Compresses `data` as raw DEFLATE in one block with the fixed codes, repeats are found with hash chains over the previous 32KiB
+pub fn deflate(data: &[u8])->Vec<u8>{
+	const MIN_MATCH: usize = 3;
+	const MAX_MATCH: usize = 258;
+	const MAX_CHAIN: usize = 128;
+	const HASH_SIZE: usize = 1 << 15;
+	let (literal_lengths, _) = fixed_lengths();
+	let literal_codes = canonical_codes(&literal_lengths);
+	let mut writer = BitWriter{out: Vec::with_capacity(data.len() / 2 + 16), buffer: 0, count: 0};
+	writer.bits(1, 1);
+	writer.bits(1, 2);
+	let hash = |i: usize|((data[i] as usize) << 10 ^ (data[i + 1] as usize) << 5 ^ data[i + 2] as usize) & (HASH_SIZE - 1);
+	// the most recent position of each hash and the position before it with the same hash
+	let mut head = vec![usize::MAX; HASH_SIZE];
+	let mut previous = vec![usize::MAX; data.len()];
+	let insert = |i: usize, head: &mut [usize], previous: &mut [usize]|if i + MIN_MATCH <= data.len(){
+		let h = hash(i);
+		previous[i] = head[h];
+		head[h] = i;
+	};
+	let mut i = 0;
+	while i < data.len(){
+		let (mut best_length, mut best_distance) = (0, 0);
+		if i + MIN_MATCH <= data.len(){
+			let max_length = MAX_MATCH.min(data.len() - i);
+			let mut candidate = head[hash(i)];
+			let mut chain = 0;
+			while candidate != usize::MAX && i - candidate <= WINDOW_SIZE && chain < MAX_CHAIN{
+				let length = data[candidate..].iter().zip(data[i..i + max_length].iter()).take_while(|(a, b)|a == b).count();
+				if length > best_length{
+					(best_length, best_distance) = (length, i - candidate);
+					if length == max_length{break;}
+				}
+				candidate = previous[candidate];
+				chain += 1;
+			}
+		}
+		if best_length >= MIN_MATCH{
+			let code = LENGTH_BASE.iter().rposition(|b|*b as usize <= best_length).unwrap();
+			writer.code(literal_codes[257 + code], literal_lengths[257 + code]);
+			writer.bits((best_length - LENGTH_BASE[code] as usize) as u32, LENGTH_EXTRA[code] as u32);
+			let code = DISTANCE_BASE.iter().rposition(|b|*b as usize <= best_distance).unwrap();
+			writer.code(code as u16, 5);
+			writer.bits((best_distance - DISTANCE_BASE[code] as usize) as u32, DISTANCE_EXTRA[code] as u32);
+			for j in i..i + best_length{insert(j, &mut head, &mut previous);}
+			i += best_length;
+		}else{
+			writer.code(literal_codes[data[i] as usize], literal_lengths[data[i] as usize]);
+			insert(i, &mut head, &mut previous);
+			i += 1;
+		}
+	}
+	writer.code(literal_codes[END_OF_BLOCK as usize], literal_lengths[END_OF_BLOCK as usize]);
+	writer.finish()
+}
+
+fn adler32(data: &[u8])->u32{
+	const MOD: u32 = 65521;
+	let (mut a, mut b) = (1u32, 0u32);
+	// the sums can't overflow in 5552 bytes
+	for chunk in data.chunks(5552){
+		for byte in chunk{
+			a += *byte as u32;
+			b += a;
+		}
+		a %= MOD;
+		b %= MOD;
+	}
+	b << 16 | a
+}
+
+///Decompresses a zlib stream, failing if the output would be longer than `limit` or the checksum doesn't match
+pub fn zlib_decompress(data: &[u8], limit: usize)->Result<Vec<u8>, FromFileErr<(),()>>{
+	if data.len() < 6{return Err(FromFileErr::EOF);}
+	let (cmf, flg) = (data[0], data[1]);
+	// only deflate with a window of at most 32KiB and no preset dictionary
+	if cmf & 0x0F != 8 || cmf >> 4 > 7 || !((cmf as u16) << 8 | flg as u16).is_multiple_of(31) || flg & 0x20 != 0
+	{return Err(FromFileErr::InvalidData(()));}
+	let out = unwrap_or_ret!(inflate(&data[2..data.len() - 4], limit));
+	let checksum = u32::from_be_bytes([data[data.len() - 4], data[data.len() - 3], data[data.len() - 2], data[data.len() - 1]]);
+	if adler32(&out) != checksum{return Err(FromFileErr::InvalidData(()));}
+	Ok(out)
+}
+///Compresses `data` as a zlib stream
+pub fn zlib_compress(data: &[u8])->Vec<u8>{
+	let mut rv = vec![0x78, 0x9C];
+	rv.extend(deflate(data));
+	rv.extend(adler32(data).to_be_bytes());
+	rv
+}
@@ -0,0 +1,1191 @@
+use std::io::{Read, Seek};
+use std::rc::Rc;
+
+use crate::core::{
+	CvtTable, FWord, FpgmTable, FromFileErr, GlyfTable, GlyphComponent, GlyphContours, GlyphData, HeadTable,
+	HheaTable, HmtxTable, LocaTable, MaxpTable, OTTF, PrepTable, Table,
+};
+use crate::outline::ContourPoint;
+
+///A unit vector with 2.14 fixed point components
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Vector{
+	x: i32,
+	y: i32,
+}
+impl Vector{
+	const X_AXIS: Self = Self{x: 0x4000, y: 0};
+	const Y_AXIS: Self = Self{x: 0, y: 0x4000};
+
+	///The unit vector pointing along (dx, dy) or `None` if it has no length
+	fn normalize(dx: i32, dy: i32)->Option<Self>{
+		if dx == 0 && dy == 0{return None;}
+		let length = (dx as f64).hypot(dy as f64);
+		Some(Self{
+			x: (dx as f64 / length * 16384.0).round() as i32,
+			y: (dy as f64 / length * 16384.0).round() as i32,
+		})
+	}
+	///The length of (dx, dy) along this vector
+	fn project(&self, dx: i32, dy: i32)->i32{
+		((dx as i64 * self.x as i64 + dy as i64 * self.y as i64 + 0x2000) >> 14) as i32
+	}
+}
+
+///`a * b / c` rounded to the nearest integer without intermediate overflow
+fn mul_div(a: i32, b: i32, c: i32)->i32{
+	if c == 0{return if (a as i64 * b as i64) < 0{i32::MIN}else{i32::MAX};}
+	let product = a as i64 * b as i64;
+	let half = (c as i64).abs() / 2;
+	let rounded = if (product < 0) != (c < 0){(product - half) / c as i64}else{(product + half) / c as i64};
+	rounded.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+}
+
+///How distances are rounded, all values are 26.6 fixed point
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RoundState{
+	Grid,
+	HalfGrid,
+	DoubleGrid,
+	DownToGrid,
+	UpToGrid,
+	Off,
+	///Set by SROUND and S45ROUND: values past `threshold` round to a multiple of `period` plus `phase`
+	Super{period: i32, phase: i32, threshold: i32},
+}
+impl RoundState{
+	///The state selected by the operand of SROUND (`grid_period` is 64) or S45ROUND (`grid_period` is 64/√2)
+	fn super_round(selector: i32, grid_period: f64)->Self{
+		let period = match (selector >> 6) & 3{
+			0 => grid_period / 2.0,
+			2 => grid_period * 2.0,
+			_ => grid_period,
+		}.round() as i32;
+		let phase = period * ((selector >> 4) & 3) / 4;
+		let threshold = match selector & 15{
+			0 => period - 1,
+			t => (t - 4) * period / 8,
+		};
+		Self::Super{period, phase, threshold}
+	}
+	fn round(&self, distance: i32)->i32{
+		let d = distance.saturating_abs();
+		let rounded = match *self{
+			Self::Grid => d.saturating_add(32) & !63,
+			Self::HalfGrid => (d & !63) + 32,
+			Self::DoubleGrid => d.saturating_add(16) & !31,
+			Self::DownToGrid => d & !63,
+			Self::UpToGrid => d.saturating_add(63) & !63,
+			Self::Off => d,
+			Self::Super{period, phase, threshold} => {
+				let v = (d as i64 - phase as i64 + threshold as i64).div_euclid(period.max(1) as i64) * period as i64 + phase as i64;
+				if v < 0{phase}else{v.min(i32::MAX as i64) as i32}
+			},
+		};
+		if distance >= 0{rounded}else{-rounded}
+	}
+}
+
+///The variables instructions use to control how points are moved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct GraphicsState{
+	auto_flip: bool,
+	control_value_cut_in: i32,
+	delta_base: i32,
+	delta_shift: i32,
+	dual_vector: Vector,
+	freedom_vector: Vector,
+	projection_vector: Vector,
+	///Set by INSTCTRL, bit 0 stops glyph programs from running and bit 1 makes them ignore the changes `prep` made to this state
+	instruct_control: i32,
+	loop_value: i32,
+	minimum_distance: i32,
+	round_state: RoundState,
+	///Reference points 0, 1 and 2
+	rp: [usize; 3],
+	single_width_cut_in: i32,
+	single_width_value: i32,
+	///Zone pointers 0, 1 and 2 where 0 is the twilight zone and 1 is the glyph zone
+	zp: [usize; 3],
+}
+impl Default for GraphicsState{fn default()->Self{Self{
+	auto_flip: true,
+	control_value_cut_in: 68,
+	delta_base: 9,
+	delta_shift: 3,
+	dual_vector: Vector::X_AXIS,
+	freedom_vector: Vector::X_AXIS,
+	projection_vector: Vector::X_AXIS,
+	instruct_control: 0,
+	loop_value: 1,
+	minimum_distance: 64,
+	round_state: RoundState::Grid,
+	rp: [0; 3],
+	single_width_cut_in: 0,
+	single_width_value: 0,
+	zp: [1; 3],
+}}}
+
+///A set of points instructions can move, coordinates are 26.6 fixed point pixels
+#[derive(Debug, Clone, Default)]
+struct Zone{
+	original: Vec<[i32; 2]>,
+	current: Vec<[i32; 2]>,
+	///Whether each point has been moved in the x and y directions
+	touched: Vec<[bool; 2]>,
+	on_curve: Vec<bool>,
+	///The index of the last point of each contour
+	end_points: Vec<usize>,
+}
+impl Zone{
+	fn new(points: Vec<[i32; 2]>, on_curve: Vec<bool>, end_points: Vec<usize>)->Self{Self{
+		original: points.clone(),
+		touched: vec![[false; 2]; points.len()],
+		current: points,
+		on_curve,
+		end_points,
+	}}
+	fn len(&self)->usize{self.current.len()}
+	///Interpolates the points between touched points of each contour along `axis` (0 for x, 1 for y)
+	fn interpolate_untouched(&mut self, axis: usize){
+		let mut start = 0;
+		for end in self.end_points.clone(){
+			if end < start || end >= self.len(){break;}
+			let touched: Vec<usize> = (start..=end).filter(|i|self.touched[*i][axis]).collect();
+			let next = |i: usize|if i == end{start}else{i + 1};
+			for (k, a) in touched.iter().enumerate(){
+				let b = touched[(k + 1) % touched.len()];
+				let mut i = next(*a);
+				while i != b{
+					self.interpolate_point(i, *a, b, axis);
+					i = next(i);
+				}
+			}
+			start = end + 1;
+		}
+	}
+	fn interpolate_point(&mut self, i: usize, a: usize, b: usize, axis: usize){
+		let (mut a, mut b) = (a, b);
+		if self.original[a][axis] > self.original[b][axis]{std::mem::swap(&mut a, &mut b);}
+		let (o1, o2) = (self.original[a][axis], self.original[b][axis]);
+		let (c1, c2) = (self.current[a][axis], self.current[b][axis]);
+		let o = self.original[i][axis];
+		self.current[i][axis] = if o <= o1{o.wrapping_add(c1).wrapping_sub(o1)}
+		else if o >= o2{o.wrapping_add(c2).wrapping_sub(o2)}
+		else{c1.wrapping_add(mul_div(o.wrapping_sub(o1), c2.wrapping_sub(c1), o2.wrapping_sub(o1)))};
+	}
+}
+
+///The tables a glyph and its metrics are read from when hinting it
+#[derive(Debug, Clone, Copy)]
+pub struct GlyphTables<'a>{
+	pub head: &'a HeadTable,
+	pub loca: &'a LocaTable,
+	pub glyf: &'a GlyfTable,
+	pub hhea: &'a HheaTable,
+	pub hmtx: &'a HmtxTable,
+}
+
+///A grid-fitted glyph in pixels
+#[derive(Debug, Clone, PartialEq)]
+pub struct HintedGlyph{
+	pub contours: GlyphContours,
+	///The advance width after hinting
+	pub advance_width: f32,
+}
+
+///The points of a glyph scaled to 26.6 fixed point pixels followed by its four phantom points
+struct ScaledGlyph{
+	points: Vec<[i32; 2]>,
+	on_curve: Vec<bool>,
+	end_points: Vec<usize>,
+}
+impl ScaledGlyph{
+	const PHANTOM_POINTS: usize = 4;
+	fn outline_len(&self)->usize{self.points.len() - Self::PHANTOM_POINTS}
+}
+
+///A TrueType instruction interpreter set up for one font at one size.
+///The font program (`fpgm`) is run when it is created followed by the control value program (`prep`),
+///the storage area, control values and twilight zone they leave behind are restored before each glyph program runs.
+#[derive(Debug, Clone)]
+pub struct Hinter{
+	ppem: u16,
+	units_per_em: u16,
+	functions: Vec<Option<Rc<[u8]>>>,
+	instruction_defs: Vec<(u8, Rc<[u8]>)>,
+	cvt: Vec<i32>,
+	storage: Vec<i32>,
+	stack: Vec<i32>,
+	max_stack: usize,
+	///Zone 0 is the twilight zone and zone 1 the glyph zone
+	zones: [Zone; 2],
+	state: GraphicsState,
+	default_state: GraphicsState,
+	prep_cvt: Vec<i32>,
+	prep_storage: Vec<i32>,
+	prep_twilight: Zone,
+	///Instructions left before a program is assumed to be stuck in a loop
+	budget: usize,
+	in_prep: bool,
+}
+impl Hinter{
+	///The deepest function calls are allowed to nest
+	pub const MAX_CALL_DEPTH: usize = 64;
+	///The most instructions a single program may execute
+	pub const MAX_INSTRUCTIONS: usize = 1_000_000;
+	///Extra stack space given to fonts that understate `maxp.max_stack_elements`
+	const STACK_SLACK: usize = 32;
+
+	///Runs `fpgm` then `prep` for an em of `ppem` pixels
+	pub fn new(fpgm: &[u8], prep: &[u8], cvt: &[FWord], maxp: &MaxpTable, units_per_em: u16, ppem: u16)->Result<Self, FromFileErr<(),()>>{
+		let twilight_points = maxp.max_twilight_points.unwrap_or(0) as usize;
+		let twilight = Zone::new(vec![[0; 2]; twilight_points], vec![false; twilight_points], Vec::new());
+		let mut rv = Self{
+			ppem,
+			units_per_em: units_per_em.max(1),
+			functions: vec![None; maxp.max_function_defs.unwrap_or(0) as usize],
+			instruction_defs: Vec::new(),
+			cvt: Vec::new(),
+			storage: vec![0; maxp.max_storage.unwrap_or(0) as usize],
+			stack: Vec::new(),
+			max_stack: maxp.max_stack_elements.unwrap_or(0) as usize + Self::STACK_SLACK,
+			zones: [twilight.clone(), Zone::default()],
+			state: GraphicsState::default(),
+			default_state: GraphicsState::default(),
+			prep_cvt: Vec::new(),
+			prep_storage: Vec::new(),
+			prep_twilight: twilight,
+			budget: 0,
+			in_prep: false,
+		};
+		rv.cvt = cvt.iter().map(|v|rv.scale(*v as i32)).collect();
+		unwrap_or_ret!(rv.run_program(fpgm));
+		rv.state = GraphicsState::default();
+		rv.in_prep = true;
+		let prep_result = rv.run_program(prep);
+		rv.in_prep = false;
+		unwrap_or_ret!(prep_result);
+		rv.default_state = GraphicsState{loop_value: 1, rp: [0; 3], zp: [1; 3], ..rv.state};
+		rv.prep_cvt = rv.cvt.clone();
+		rv.prep_storage = rv.storage.clone();
+		rv.prep_twilight = rv.zones[0].clone();
+		Ok(rv)
+	}
+	///Reads `fpgm`, `prep`, `cvt `, `maxp` and `head` from the font and runs the font programs for an em of `ppem` pixels
+	pub fn from_file<F>(f: &mut F, font: &OTTF, ppem: u16)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		let fpgm = match unwrap_or_ret!(font.load_table(f, "fpgm")){Some(Table::Fpgm(t)) => Some(t), _ => None};
+		let prep = match unwrap_or_ret!(font.load_table(f, "prep")){Some(Table::Prep(t)) => Some(t), _ => None};
+		let cvt = match unwrap_or_ret!(font.load_table(f, "cvt ")){Some(Table::Cvt(t)) => Some(t), _ => None};
+		let (maxp, head) = match (unwrap_or_ret!(font.load_table(f, "maxp")), unwrap_or_ret!(font.load_table(f, "head"))){
+			(Some(Table::Maxp(maxp)), Some(Table::Head(head))) => (maxp, head),
+			_ => return Err(FromFileErr::InvalidData(())),
+		};
+		Self::new(
+			fpgm.as_ref().map_or(&[], |t: &FpgmTable|&t.instructions),
+			prep.as_ref().map_or(&[], |t: &PrepTable|&t.instructions),
+			cvt.as_ref().map_or(&[], |t: &CvtTable|&t.values),
+			&maxp,
+			head.units_per_em,
+			ppem,
+		)
+	}
+	pub fn ppem(&self)->u16{self.ppem}
+	///Font units to 26.6 fixed point pixels
+	fn scale(&self, v: i32)->i32{mul_div(v, self.ppem as i32 * 64, self.units_per_em as i32)}
+
+	///Reads glyph `glyph_id`, scales it to pixels and runs its instructions.
+	///Components of composite glyphs are hinted on their own before the composite's instructions run.
+	pub fn hint_glyph<F>(&mut self, f: &mut F, tables: &GlyphTables, glyph_id: u16)->Result<HintedGlyph, FromFileErr<(),()>> where F: Read, F: Seek{
+		let glyph = unwrap_or_ret!(self.load_glyph(f, tables, glyph_id, &mut Vec::new()));
+		let n = glyph.outline_len();
+		Ok(HintedGlyph{
+			contours: GlyphContours{
+				points: glyph.points[..n].iter().zip(glyph.on_curve.iter()).map(
+					|(p, on_curve)|ContourPoint{x: p[0] as f32 / 64.0, y: p[1] as f32 / 64.0, on_curve: *on_curve}
+				).collect(),
+				end_pts_of_contours: glyph.end_points.iter().map(|e|*e as u16).collect(),
+			},
+			advance_width: glyph.points[n + 1][0].wrapping_sub(glyph.points[n][0]) as f32 / 64.0,
+		})
+	}
+	fn load_glyph<F>(&mut self, f: &mut F, tables: &GlyphTables, glyph_id: u16, stack: &mut Vec<u16>)->Result<ScaledGlyph, FromFileErr<(),()>> where F: Read, F: Seek{
+		if stack.contains(&glyph_id) || stack.len() >= GlyfTable::MAX_COMPONENT_DEPTH
+		{return Err(FromFileErr::InvalidData(()));}
+		let metric = unwrap_or_ret!(tables.hmtx.get_metric(f, tables.hhea, glyph_id));
+		let glyph = unwrap_or_ret!(tables.glyf.get_glyph(f, tables.loca, tables.head, glyph_id));
+		let x_min = glyph.as_ref().map_or(metric.lsb, |g|g.header.x_min) as i32;
+		let phantom_x = x_min - metric.lsb as i32;
+		let phantom = [
+			[self.scale(phantom_x), 0],
+			[self.scale(phantom_x + metric.advance_width as i32), 0],
+			[0, self.scale(tables.hhea.ascender as i32)],
+			[0, self.scale(tables.hhea.descender as i32)],
+		];
+		let data = match glyph{
+			Some(g) => g.data,
+			None => return Ok(ScaledGlyph{points: phantom.into(), on_curve: Vec::new(), end_points: Vec::new()}),
+		};
+		match data{
+			GlyphData::Simple(simple) => {
+				let mut points: Vec<[i32; 2]> = simple.points.iter().map(|p|[self.scale(p.x as i32), self.scale(p.y as i32)]).collect();
+				points.extend_from_slice(&phantom);
+				let on_curve = simple.points.iter().map(|p|p.on_curve).collect();
+				let end_points = simple.end_pts_of_contours.iter().map(|e|*e as usize).collect();
+				let mut rv = ScaledGlyph{points, on_curve, end_points};
+				unwrap_or_ret!(self.run_glyph(&mut rv, &simple.instructions, true));
+				Ok(rv)
+			},
+			GlyphData::Composite(composite) => {
+				stack.push(glyph_id);
+				let mut rv = ScaledGlyph{points: Vec::new(), on_curve: Vec::new(), end_points: Vec::new()};
+				let mut phantom = phantom;
+				for component in composite.components.iter(){
+					let child = unwrap_or_ret!(self.load_glyph(f, tables, component.glyph_index, stack));
+					let [xx, xy, yx, yy] = component.transform;
+					let transform = |p: &[i32; 2]|[
+						(xx * p[0] as f32 + yx * p[1] as f32).round() as i32,
+						(xy * p[0] as f32 + yy * p[1] as f32).round() as i32,
+					];
+					let child_points: Vec<[i32; 2]> = child.points.iter().map(transform).collect();
+					let (dx, dy) = if component.flags & GlyphComponent::ARGS_ARE_XY_VALUES != 0{
+						let (mut dx, mut dy) = (self.scale(component.argument1), self.scale(component.argument2));
+						if component.flags & GlyphComponent::SCALED_COMPONENT_OFFSET != 0
+						&& component.flags & GlyphComponent::UNSCALED_COMPONENT_OFFSET == 0{
+							[dx, dy] = transform(&[dx, dy]);
+						}
+						if component.flags & GlyphComponent::ROUND_XY_TO_GRID != 0{
+							(dx, dy) = (RoundState::Grid.round(dx), RoundState::Grid.round(dy));
+						}
+						(dx, dy)
+					}else{
+						// point matching against the points of the composite so far
+						let parent = match rv.points.get(component.argument1 as usize){
+							Some(p) => *p,
+							None => return Err(FromFileErr::InvalidData(())),
+						};
+						let child_point = match child_points[..child.outline_len()].get(component.argument2 as usize){
+							Some(p) => *p,
+							None => return Err(FromFileErr::InvalidData(())),
+						};
+						(parent[0].wrapping_sub(child_point[0]), parent[1].wrapping_sub(child_point[1]))
+					};
+					if component.flags & GlyphComponent::USE_MY_METRICS != 0{
+						for (i, p) in phantom.iter_mut().enumerate(){*p = child_points[child.outline_len() + i];}
+					}
+					let base = rv.points.len();
+					rv.points.extend(child_points[..child.outline_len()].iter().map(|p|[p[0].wrapping_add(dx), p[1].wrapping_add(dy)]));
+					rv.on_curve.extend_from_slice(&child.on_curve);
+					rv.end_points.extend(child.end_points.iter().map(|e|e + base));
+				}
+				stack.pop();
+				rv.points.extend_from_slice(&phantom);
+				if let Some(instructions) = &composite.instructions{
+					unwrap_or_ret!(self.run_glyph(&mut rv, instructions, false));
+				}
+				Ok(rv)
+			},
+		}
+	}
+	///Runs the instructions of a glyph moving its points
+	fn run_glyph(&mut self, glyph: &mut ScaledGlyph, instructions: &[u8], round_phantom: bool)->Result<(), FromFileErr<(),()>>{
+		let n = glyph.outline_len();
+		if round_phantom{
+			for p in glyph.points[n..].iter_mut(){*p = [RoundState::Grid.round(p[0]), RoundState::Grid.round(p[1])];}
+		}
+		if instructions.is_empty() || self.default_state.instruct_control & 1 != 0{return Ok(());}
+		let mut on_curve = glyph.on_curve.clone();
+		on_curve.resize(glyph.points.len(), true);
+		self.zones[1] = Zone::new(glyph.points.clone(), on_curve, glyph.end_points.clone());
+		self.zones[0] = self.prep_twilight.clone();
+		self.cvt.clone_from(&self.prep_cvt);
+		self.storage.clone_from(&self.prep_storage);
+		self.state = if self.default_state.instruct_control & 2 != 0
+		{GraphicsState{instruct_control: self.default_state.instruct_control, ..GraphicsState::default()}}
+		else{self.default_state};
+		unwrap_or_ret!(self.run_program(instructions));
+		glyph.points.clone_from(&self.zones[1].current);
+		glyph.on_curve.copy_from_slice(&self.zones[1].on_curve[..n]);
+		Ok(())
+	}
+	fn run_program(&mut self, code: &[u8])->Result<(), FromFileErr<(),()>>{
+		self.stack.clear();
+		self.budget = Self::MAX_INSTRUCTIONS;
+		self.execute(code, 0)
+	}
+
+	fn pop(&mut self)->Result<i32, FromFileErr<(),()>>{
+		self.stack.pop().ok_or(FromFileErr::InvalidData(()))
+	}
+	fn pop_index(&mut self)->Result<usize, FromFileErr<(),()>>{
+		let v = unwrap_or_ret!(self.pop());
+		usize::try_from(v).map_err(|_|FromFileErr::InvalidData(()))
+	}
+	fn push(&mut self, v: i32)->Result<(), FromFileErr<(),()>>{
+		if self.stack.len() >= self.max_stack{return Err(FromFileErr::InvalidData(()));}
+		self.stack.push(v);
+		Ok(())
+	}
+	fn pop_zone(&mut self)->Result<usize, FromFileErr<(),()>>{
+		match unwrap_or_ret!(self.pop()){
+			z @ (0 | 1) => Ok(z as usize),
+			_ => Err(FromFileErr::InvalidData(())),
+		}
+	}
+	fn check_point(&self, zone: usize, point: usize)->Result<(), FromFileErr<(),()>>{
+		if point < self.zones[zone].len(){Ok(())}else{Err(FromFileErr::InvalidData(()))}
+	}
+	fn current(&self, zone: usize, point: usize)->Result<[i32; 2], FromFileErr<(),()>>{
+		self.zones[zone].current.get(point).copied().ok_or(FromFileErr::InvalidData(()))
+	}
+	fn original(&self, zone: usize, point: usize)->Result<[i32; 2], FromFileErr<(),()>>{
+		self.zones[zone].original.get(point).copied().ok_or(FromFileErr::InvalidData(()))
+	}
+	///The distance from `b` to `a` along the projection vector
+	fn project(&self, a: [i32; 2], b: [i32; 2])->i32{
+		self.state.projection_vector.project(a[0].wrapping_sub(b[0]), a[1].wrapping_sub(b[1]))
+	}
+	///The distance from `b` to `a` along the dual projection vector
+	fn dual_project(&self, a: [i32; 2], b: [i32; 2])->i32{
+		self.state.dual_vector.project(a[0].wrapping_sub(b[0]), a[1].wrapping_sub(b[1]))
+	}
+	fn round(&self, distance: i32)->i32{self.state.round_state.round(distance)}
+	fn cvt_value(&self, index: usize)->Result<i32, FromFileErr<(),()>>{
+		self.cvt.get(index).copied().ok_or(FromFileErr::InvalidData(()))
+	}
+	///The freedom vector movement that changes a point's projection by `distance`
+	fn freedom_delta(&self, distance: i32)->[i32; 2]{
+		let (fv, pv) = (self.state.freedom_vector, self.state.projection_vector);
+		let mut f_dot_p = ((fv.x as i64 * pv.x as i64 + fv.y as i64 * pv.y as i64) >> 14) as i32;
+		// nearly perpendicular vectors would send the point off to infinity
+		if f_dot_p.abs() < 0x400{f_dot_p = 0x4000;}
+		[mul_div(distance, fv.x, f_dot_p), mul_div(distance, fv.y, f_dot_p)]
+	}
+	///Moves a point along the freedom vector so that its projection changes by `distance` and marks it as touched
+	fn move_point(&mut self, zone: usize, point: usize, distance: i32)->Result<(), FromFileErr<(),()>>{
+		let delta = self.freedom_delta(distance);
+		self.shift_point(zone, point, delta, true)
+	}
+	fn shift_point(&mut self, zone: usize, point: usize, delta: [i32; 2], touch: bool)->Result<(), FromFileErr<(),()>>{
+		unwrap_or_ret!(self.check_point(zone, point));
+		let fv = self.state.freedom_vector;
+		let zone = &mut self.zones[zone];
+		let p = &mut zone.current[point];
+		*p = [p[0].wrapping_add(delta[0]), p[1].wrapping_add(delta[1])];
+		if touch{
+			if fv.x != 0{zone.touched[point][0] = true;}
+			if fv.y != 0{zone.touched[point][1] = true;}
+		}
+		Ok(())
+	}
+	///Moves a twilight point's original position to `distance` along the freedom vector from `from`
+	fn place_twilight_point(&mut self, point: usize, from: [i32; 2], distance: i32)->Result<(), FromFileErr<(),()>>{
+		unwrap_or_ret!(self.check_point(0, point));
+		let fv = self.state.freedom_vector;
+		let p = [
+			from[0].wrapping_add(((distance as i64 * fv.x as i64) >> 14) as i32),
+			from[1].wrapping_add(((distance as i64 * fv.y as i64) >> 14) as i32),
+		];
+		self.zones[0].original[point] = p;
+		self.zones[0].current[point] = p;
+		Ok(())
+	}
+	///The reference point used by SHP, SHC and SHZ and how far it has moved
+	fn reference_displacement(&self, opcode: u8)->Result<(usize, usize, [i32; 2]), FromFileErr<(),()>>{
+		let (zone, point) = if opcode & 1 != 0{(self.state.zp[0], self.state.rp[1])}else{(self.state.zp[1], self.state.rp[2])};
+		let d = self.project(unwrap_or_ret!(self.current(zone, point)), unwrap_or_ret!(self.original(zone, point)));
+		Ok((zone, point, self.freedom_delta(d)))
+	}
+	///The direction from a point in zone pointer 2 to a point in zone pointer 1 (rotated 90° counter-clockwise if `perpendicular`)
+	fn line_vector(&self, zp2_point: usize, zp1_point: usize, perpendicular: bool, original: bool)->Result<Vector, FromFileErr<(),()>>{
+		let (a, b) = if original{
+			(unwrap_or_ret!(self.original(self.state.zp[1], zp1_point)), unwrap_or_ret!(self.original(self.state.zp[2], zp2_point)))
+		}else{
+			(unwrap_or_ret!(self.current(self.state.zp[1], zp1_point)), unwrap_or_ret!(self.current(self.state.zp[2], zp2_point)))
+		};
+		let (dx, dy) = (a[0].wrapping_sub(b[0]), a[1].wrapping_sub(b[1]));
+		let (dx, dy) = if perpendicular{(dy.wrapping_neg(), dx)}else{(dx, dy)};
+		Ok(Vector::normalize(dx, dy).unwrap_or(Vector::X_AXIS))
+	}
+	///Pops the loop count worth of points calling `action` on each then resets the loop count
+	fn for_each_looped<A>(&mut self, mut action: A)->Result<(), FromFileErr<(),()>> where A: FnMut(&mut Self, usize)->Result<(), FromFileErr<(),()>>{
+		for _ in 0..self.state.loop_value.max(1){
+			let point = unwrap_or_ret!(self.pop_index());
+			unwrap_or_ret!(action(self, point));
+		}
+		self.state.loop_value = 1;
+		Ok(())
+	}
+	///Applies DELTAP and DELTAC exceptions, `range` is 0, 1 or 2 for the ppem ranges starting at delta base, +16 and +32
+	fn delta_exceptions(&mut self, range: i32, cvt: bool)->Result<(), FromFileErr<(),()>>{
+		let n = unwrap_or_ret!(self.pop());
+		for _ in 0..n.max(0){
+			let target = unwrap_or_ret!(self.pop_index());
+			let arg = unwrap_or_ret!(self.pop());
+			let ppem = self.state.delta_base.wrapping_add(range * 16 + ((arg >> 4) & 15));
+			if ppem != self.ppem as i32{continue;}
+			let steps = (arg & 15) - 8;
+			let steps = if steps >= 0{steps + 1}else{steps};
+			let delta = (steps * 64) >> self.state.delta_shift.clamp(0, 6);
+			if cvt{
+				match self.cvt.get_mut(target){
+					Some(v) => *v = v.wrapping_add(delta),
+					None => return Err(FromFileErr::InvalidData(())),
+				}
+			}else{
+				unwrap_or_ret!(self.move_point(self.state.zp[0], target, delta));
+			}
+		}
+		Ok(())
+	}
+
+	///The length of the instruction at `pc` including any data pushed with it
+	fn instruction_length(code: &[u8], pc: usize)->Result<usize, FromFileErr<(),()>>{
+		let n = |i: usize|code.get(i).map(|v|*v as usize).ok_or(FromFileErr::InvalidData(()));
+		Ok(match code[pc]{
+			// NPUSHB, NPUSHW
+			0x40 => 2 + unwrap_or_ret!(n(pc + 1)),
+			0x41 => 2 + unwrap_or_ret!(n(pc + 1)) * 2,
+			// PUSHB, PUSHW
+			op @ 0xB0..=0xB7 => 1 + (op - 0xAF) as usize,
+			op @ 0xB8..=0xBF => 1 + (op - 0xB7) as usize * 2,
+			_ => 1,
+		})
+	}
+	///The position after the ELSE (if `stop_at_else`) or EIF matching an IF at `pc - 1`
+	fn skip_branch(code: &[u8], mut pc: usize, stop_at_else: bool)->Result<usize, FromFileErr<(),()>>{
+		let mut nesting = 0;
+		while pc < code.len(){
+			match code[pc]{
+				// IF
+				0x58 => nesting += 1,
+				// ELSE
+				0x1B if nesting == 0 && stop_at_else => return Ok(pc + 1),
+				// EIF
+				0x59 => {
+					if nesting == 0{return Ok(pc + 1);}
+					nesting -= 1;
+				},
+				_ => {},
+			}
+			pc += unwrap_or_ret!(Self::instruction_length(code, pc));
+		}
+		Err(FromFileErr::InvalidData(()))
+	}
+	///The position of the ENDF ending a function or instruction definition starting at `pc`
+	fn find_endf(code: &[u8], mut pc: usize)->Result<usize, FromFileErr<(),()>>{
+		while pc < code.len(){
+			match code[pc]{
+				// ENDF
+				0x2D => return Ok(pc),
+				// FDEF and IDEF can't be nested
+				0x2C | 0x89 => return Err(FromFileErr::InvalidData(())),
+				_ => pc += unwrap_or_ret!(Self::instruction_length(code, pc)),
+			}
+		}
+		Err(FromFileErr::InvalidData(()))
+	}
+	fn call(&mut self, function: usize, depth: usize)->Result<(), FromFileErr<(),()>>{
+		let body = match self.functions.get(function){
+			Some(Some(body)) => body.clone(),
+			_ => return Err(FromFileErr::InvalidData(())),
+		};
+		self.execute(&body, depth + 1)
+	}
+
+	///Uses up one instruction of the budget, failing once it has run out
+	fn spend_budget(&mut self)->Result<(), FromFileErr<(),()>>{
+		if self.budget == 0{return Err(FromFileErr::InvalidData(()));}
+		self.budget -= 1;
+		Ok(())
+	}
+	fn execute(&mut self, code: &[u8], depth: usize)->Result<(), FromFileErr<(),()>>{
+		if depth > Self::MAX_CALL_DEPTH{return Err(FromFileErr::InvalidData(()));}
+		let mut pc = 0;
+		while pc < code.len(){
+			unwrap_or_ret!(self.spend_budget());
+			let opcode = code[pc];
+			let mut next = pc + unwrap_or_ret!(Self::instruction_length(code, pc));
+			if next > code.len(){return Err(FromFileErr::InvalidData(()));}
+			match opcode{
+				// SVTCA, SPVTCA, SFVTCA
+				0x00..=0x05 => {
+					let axis = if opcode & 1 != 0{Vector::X_AXIS}else{Vector::Y_AXIS};
+					if opcode < 0x04{
+						self.state.projection_vector = axis;
+						self.state.dual_vector = axis;
+					}
+					if !(0x02..0x04).contains(&opcode){self.state.freedom_vector = axis;}
+				},
+				// SPVTL, SFVTL
+				0x06..=0x09 => {
+					let p2 = unwrap_or_ret!(self.pop_index());
+					let p1 = unwrap_or_ret!(self.pop_index());
+					let v = unwrap_or_ret!(self.line_vector(p2, p1, opcode & 1 != 0, false));
+					if opcode < 0x08{
+						self.state.projection_vector = v;
+						self.state.dual_vector = v;
+					}else{self.state.freedom_vector = v;}
+				},
+				// SPVFS, SFVFS
+				0x0A | 0x0B => {
+					let y = unwrap_or_ret!(self.pop());
+					let x = unwrap_or_ret!(self.pop());
+					let v = Vector::normalize(x, y).unwrap_or(Vector::X_AXIS);
+					if opcode == 0x0A{
+						self.state.projection_vector = v;
+						self.state.dual_vector = v;
+					}else{self.state.freedom_vector = v;}
+				},
+				// GPV, GFV
+				0x0C | 0x0D => {
+					let v = if opcode == 0x0C{self.state.projection_vector}else{self.state.freedom_vector};
+					unwrap_or_ret!(self.push(v.x));
+					unwrap_or_ret!(self.push(v.y));
+				},
+				// SFVTPV
+				0x0E => self.state.freedom_vector = self.state.projection_vector,
+				// ISECT
+				0x0F => {
+					let b1 = unwrap_or_ret!(self.pop_index());
+					let b0 = unwrap_or_ret!(self.pop_index());
+					let a1 = unwrap_or_ret!(self.pop_index());
+					let a0 = unwrap_or_ret!(self.pop_index());
+					let point = unwrap_or_ret!(self.pop_index());
+					let [zp0, zp1, zp2] = self.state.zp;
+					let (pa0, pa1) = (unwrap_or_ret!(self.current(zp1, a0)), unwrap_or_ret!(self.current(zp1, a1)));
+					let (pb0, pb1) = (unwrap_or_ret!(self.current(zp0, b0)), unwrap_or_ret!(self.current(zp0, b1)));
+					unwrap_or_ret!(self.check_point(zp2, point));
+					let (dbx, dby) = (pb1[0].wrapping_sub(pb0[0]), pb1[1].wrapping_sub(pb0[1]));
+					let (dax, day) = (pa1[0].wrapping_sub(pa0[0]), pa1[1].wrapping_sub(pa0[1]));
+					let (dx, dy) = (pb0[0].wrapping_sub(pa0[0]), pb0[1].wrapping_sub(pa0[1]));
+					let discriminant = mul_div(dax, dby.wrapping_neg(), 64).wrapping_add(mul_div(day, dbx, 64));
+					let dot_product = mul_div(dax, dbx, 64).wrapping_add(mul_div(day, dby, 64));
+					// lines within about 3 degrees of parallel meet in the middle of the points
+					self.zones[zp2].current[point] = if 19 * discriminant.unsigned_abs() as i64 > dot_product.unsigned_abs() as i64{
+						let v = mul_div(dx, dby.wrapping_neg(), 64).wrapping_add(mul_div(dy, dbx, 64));
+						[pa0[0].wrapping_add(mul_div(v, dax, discriminant)), pa0[1].wrapping_add(mul_div(v, day, discriminant))]
+					}else{[
+						((pa0[0] as i64 + pa1[0] as i64 + pb0[0] as i64 + pb1[0] as i64) / 4) as i32,
+						((pa0[1] as i64 + pa1[1] as i64 + pb0[1] as i64 + pb1[1] as i64) / 4) as i32,
+					]};
+					self.zones[zp2].touched[point] = [true; 2];
+				},
+				// SRP0, SRP1, SRP2
+				0x10..=0x12 => self.state.rp[(opcode - 0x10) as usize] = unwrap_or_ret!(self.pop_index()),
+				// SZP0, SZP1, SZP2
+				0x13..=0x15 => self.state.zp[(opcode - 0x13) as usize] = unwrap_or_ret!(self.pop_zone()),
+				// SZPS
+				0x16 => self.state.zp = [unwrap_or_ret!(self.pop_zone()); 3],
+				// SLOOP
+				0x17 => {
+					let n = unwrap_or_ret!(self.pop());
+					if n < 0{return Err(FromFileErr::InvalidData(()));}
+					self.state.loop_value = n.min(0xFFFF);
+				},
+				// RTG, RTHG
+				0x18 => self.state.round_state = RoundState::Grid,
+				0x19 => self.state.round_state = RoundState::HalfGrid,
+				// SMD
+				0x1A => self.state.minimum_distance = unwrap_or_ret!(self.pop()),
+				// ELSE: reached at the end of a taken IF branch
+				0x1B => next = unwrap_or_ret!(Self::skip_branch(code, pc + 1, false)),
+				// JMPR
+				0x1C => {
+					let offset = unwrap_or_ret!(self.pop());
+					next = unwrap_or_ret!(Self::jump(code, pc, offset));
+				},
+				// SCVTCI, SSWCI
+				0x1D => self.state.control_value_cut_in = unwrap_or_ret!(self.pop()),
+				0x1E => self.state.single_width_cut_in = unwrap_or_ret!(self.pop()),
+				// SSW (in font units)
+				0x1F => {
+					let v = unwrap_or_ret!(self.pop());
+					self.state.single_width_value = self.scale(v);
+				},
+				// DUP
+				0x20 => {
+					let v = match self.stack.last(){
+						Some(v) => *v,
+						None => return Err(FromFileErr::InvalidData(())),
+					};
+					unwrap_or_ret!(self.push(v));
+				},
+				// POP
+				0x21 => {unwrap_or_ret!(self.pop());},
+				// CLEAR
+				0x22 => self.stack.clear(),
+				// SWAP
+				0x23 => {
+					let a = unwrap_or_ret!(self.pop());
+					let b = unwrap_or_ret!(self.pop());
+					self.stack.push(a);
+					self.stack.push(b);
+				},
+				// DEPTH
+				0x24 => unwrap_or_ret!(self.push(self.stack.len() as i32)),
+				// CINDEX, MINDEX
+				0x25 | 0x26 => {
+					let k = unwrap_or_ret!(self.pop_index());
+					if k == 0 || k > self.stack.len(){return Err(FromFileErr::InvalidData(()));}
+					let i = self.stack.len() - k;
+					let v = if opcode == 0x25{self.stack[i]}else{self.stack.remove(i)};
+					unwrap_or_ret!(self.push(v));
+				},
+				// ALIGNPTS
+				0x27 => {
+					let p2 = unwrap_or_ret!(self.pop_index());
+					let p1 = unwrap_or_ret!(self.pop_index());
+					let [zp0, zp1, _] = self.state.zp;
+					let d = self.project(unwrap_or_ret!(self.current(zp0, p2)), unwrap_or_ret!(self.current(zp1, p1))) / 2;
+					unwrap_or_ret!(self.move_point(zp1, p1, d));
+					unwrap_or_ret!(self.move_point(zp0, p2, d.wrapping_neg()));
+				},
+				// UTP
+				0x29 => {
+					let point = unwrap_or_ret!(self.pop_index());
+					let zone = self.state.zp[0];
+					unwrap_or_ret!(self.check_point(zone, point));
+					let fv = self.state.freedom_vector;
+					if fv.x != 0{self.zones[zone].touched[point][0] = false;}
+					if fv.y != 0{self.zones[zone].touched[point][1] = false;}
+				},
+				// LOOPCALL
+				0x2A => {
+					let function = unwrap_or_ret!(self.pop_index());
+					let count = unwrap_or_ret!(self.pop());
+					for _ in 0..count.max(0){
+						// an empty function costs nothing to run so each call is charged too
+						unwrap_or_ret!(self.spend_budget());
+						unwrap_or_ret!(self.call(function, depth));
+					}
+				},
+				// CALL
+				0x2B => {
+					let function = unwrap_or_ret!(self.pop_index());
+					unwrap_or_ret!(self.call(function, depth));
+				},
+				// FDEF
+				0x2C => {
+					let function = unwrap_or_ret!(self.pop_index());
+					if function > u16::MAX as usize{return Err(FromFileErr::InvalidData(()));}
+					let end = unwrap_or_ret!(Self::find_endf(code, pc + 1));
+					if function >= self.functions.len(){self.functions.resize(function + 1, None);}
+					self.functions[function] = Some(code[pc + 1..end].into());
+					next = end + 1;
+				},
+				// ENDF outside of a definition
+				0x2D => return Err(FromFileErr::InvalidData(())),
+				// MDAP
+				0x2E | 0x2F => {
+					let point = unwrap_or_ret!(self.pop_index());
+					let zone = self.state.zp[0];
+					let distance = if opcode & 1 != 0{
+						let d = self.project(unwrap_or_ret!(self.current(zone, point)), [0, 0]);
+						self.round(d).wrapping_sub(d)
+					}else{0};
+					unwrap_or_ret!(self.move_point(zone, point, distance));
+					self.state.rp[0] = point;
+					self.state.rp[1] = point;
+				},
+				// IUP
+				0x30 | 0x31 => self.zones[1].interpolate_untouched(if opcode & 1 != 0{0}else{1}),
+				// SHP
+				0x32 | 0x33 => {
+					let (_, _, delta) = unwrap_or_ret!(self.reference_displacement(opcode));
+					let zone = self.state.zp[2];
+					unwrap_or_ret!(self.for_each_looped(|h, point|h.shift_point(zone, point, delta, true)));
+				},
+				// SHC
+				0x34 | 0x35 => {
+					let contour = unwrap_or_ret!(self.pop_index());
+					let (ref_zone, ref_point, delta) = unwrap_or_ret!(self.reference_displacement(opcode));
+					let zone = self.state.zp[2];
+					let end_points = &self.zones[zone].end_points;
+					let end = match end_points.get(contour){
+						Some(e) => *e,
+						None => return Err(FromFileErr::InvalidData(())),
+					};
+					let start = if contour == 0{0}else{end_points[contour - 1] + 1};
+					for point in start..=end{
+						if zone == ref_zone && point == ref_point{continue;}
+						unwrap_or_ret!(self.shift_point(zone, point, delta, true));
+					}
+				},
+				// SHZ
+				0x36 | 0x37 => {
+					let zone = unwrap_or_ret!(self.pop_zone());
+					let (ref_zone, ref_point, delta) = unwrap_or_ret!(self.reference_displacement(opcode));
+					// the phantom points of the glyph zone stay put
+					let end = if zone == 0{self.zones[0].len()}else{self.zones[1].len().saturating_sub(ScaledGlyph::PHANTOM_POINTS)};
+					for point in 0..end{
+						if zone == ref_zone && point == ref_point{continue;}
+						unwrap_or_ret!(self.shift_point(zone, point, delta, false));
+					}
+				},
+				// SHPIX
+				0x38 => {
+					let distance = unwrap_or_ret!(self.pop());
+					let fv = self.state.freedom_vector;
+					let delta = [((distance as i64 * fv.x as i64) >> 14) as i32, ((distance as i64 * fv.y as i64) >> 14) as i32];
+					let zone = self.state.zp[2];
+					unwrap_or_ret!(self.for_each_looped(|h, point|h.shift_point(zone, point, delta, true)));
+				},
+				// IP
+				0x39 => {
+					let [zp0, zp1, zp2] = self.state.zp;
+					let [_, rp1, rp2] = self.state.rp;
+					let (original1, current1) = (unwrap_or_ret!(self.original(zp0, rp1)), unwrap_or_ret!(self.current(zp0, rp1)));
+					let original_range = self.dual_project(unwrap_or_ret!(self.original(zp1, rp2)), original1);
+					let current_range = self.project(unwrap_or_ret!(self.current(zp1, rp2)), current1);
+					unwrap_or_ret!(self.for_each_looped(|h, point|{
+						let original_distance = h.dual_project(unwrap_or_ret!(h.original(zp2, point)), original1);
+						let current_distance = h.project(unwrap_or_ret!(h.current(zp2, point)), current1);
+						let distance = if original_range != 0{mul_div(original_distance, current_range, original_range)}else{original_distance};
+						h.move_point(zp2, point, distance.wrapping_sub(current_distance))
+					}));
+				},
+				// MSIRP
+				0x3A | 0x3B => {
+					let distance = unwrap_or_ret!(self.pop());
+					let point = unwrap_or_ret!(self.pop_index());
+					let [zp0, zp1, _] = self.state.zp;
+					let rp0 = self.state.rp[0];
+					if zp1 == 0{unwrap_or_ret!(self.place_twilight_point(point, unwrap_or_ret!(self.original(zp0, rp0)), distance));}
+					let d = self.project(unwrap_or_ret!(self.current(zp1, point)), unwrap_or_ret!(self.current(zp0, rp0)));
+					unwrap_or_ret!(self.move_point(zp1, point, distance.wrapping_sub(d)));
+					self.state.rp[1] = rp0;
+					self.state.rp[2] = point;
+					if opcode & 1 != 0{self.state.rp[0] = point;}
+				},
+				// ALIGNRP
+				0x3C => {
+					let [zp0, zp1, _] = self.state.zp;
+					let reference = unwrap_or_ret!(self.current(zp0, self.state.rp[0]));
+					unwrap_or_ret!(self.for_each_looped(|h, point|{
+						let d = h.project(unwrap_or_ret!(h.current(zp1, point)), reference);
+						h.move_point(zp1, point, d.wrapping_neg())
+					}));
+				},
+				// RTDG
+				0x3D => self.state.round_state = RoundState::DoubleGrid,
+				// MIAP
+				0x3E | 0x3F => {
+					let cvt_index = unwrap_or_ret!(self.pop_index());
+					let point = unwrap_or_ret!(self.pop_index());
+					let zone = self.state.zp[0];
+					let mut distance = unwrap_or_ret!(self.cvt_value(cvt_index));
+					if zone == 0{unwrap_or_ret!(self.place_twilight_point(point, [0, 0], distance));}
+					let d = self.project(unwrap_or_ret!(self.current(zone, point)), [0, 0]);
+					if opcode & 1 != 0{
+						if (distance as i64 - d as i64).abs() > self.state.control_value_cut_in as i64{distance = d;}
+						distance = self.round(distance);
+					}
+					unwrap_or_ret!(self.move_point(zone, point, distance.wrapping_sub(d)));
+					self.state.rp[0] = point;
+					self.state.rp[1] = point;
+				},
+				// NPUSHB, PUSHB
+				0x40 | 0xB0..=0xB7 => {
+					let start = if opcode == 0x40{pc + 2}else{pc + 1};
+					for b in code[start..next].iter(){unwrap_or_ret!(self.push(*b as i32));}
+				},
+				// NPUSHW, PUSHW
+				0x41 | 0xB8..=0xBF => {
+					let start = if opcode == 0x41{pc + 2}else{pc + 1};
+					for w in code[start..next].chunks_exact(2){unwrap_or_ret!(self.push(i16::from_be_bytes([w[0], w[1]]) as i32));}
+				},
+				// WS
+				0x42 => {
+					let v = unwrap_or_ret!(self.pop());
+					let i = unwrap_or_ret!(self.pop_index());
+					match self.storage.get_mut(i){
+						Some(s) => *s = v,
+						None => return Err(FromFileErr::InvalidData(())),
+					}
+				},
+				// RS
+				0x43 => {
+					let i = unwrap_or_ret!(self.pop_index());
+					let v = match self.storage.get(i){
+						Some(v) => *v,
+						None => return Err(FromFileErr::InvalidData(())),
+					};
+					unwrap_or_ret!(self.push(v));
+				},
+				// WCVTP, WCVTF (in font units)
+				0x44 | 0x70 => {
+					let v = unwrap_or_ret!(self.pop());
+					let i = unwrap_or_ret!(self.pop_index());
+					let v = if opcode == 0x70{self.scale(v)}else{v};
+					match self.cvt.get_mut(i){
+						Some(c) => *c = v,
+						None => return Err(FromFileErr::InvalidData(())),
+					}
+				},
+				// RCVT
+				0x45 => {
+					let i = unwrap_or_ret!(self.pop_index());
+					unwrap_or_ret!(self.push(unwrap_or_ret!(self.cvt_value(i))));
+				},
+				// GC
+				0x46 | 0x47 => {
+					let point = unwrap_or_ret!(self.pop_index());
+					let zone = self.state.zp[2];
+					let v = if opcode & 1 != 0{self.dual_project(unwrap_or_ret!(self.original(zone, point)), [0, 0])}
+					else{self.project(unwrap_or_ret!(self.current(zone, point)), [0, 0])};
+					unwrap_or_ret!(self.push(v));
+				},
+				// SCFS
+				0x48 => {
+					let v = unwrap_or_ret!(self.pop());
+					let point = unwrap_or_ret!(self.pop_index());
+					let zone = self.state.zp[2];
+					let d = self.project(unwrap_or_ret!(self.current(zone, point)), [0, 0]);
+					unwrap_or_ret!(self.move_point(zone, point, v.wrapping_sub(d)));
+					if zone == 0{self.zones[0].original[point] = self.zones[0].current[point];}
+				},
+				// MD
+				0x49 | 0x4A => {
+					let p2 = unwrap_or_ret!(self.pop_index());
+					let p1 = unwrap_or_ret!(self.pop_index());
+					let [zp0, zp1, _] = self.state.zp;
+					let d = if opcode & 1 != 0{self.project(unwrap_or_ret!(self.current(zp0, p1)), unwrap_or_ret!(self.current(zp1, p2)))}
+					else{self.dual_project(unwrap_or_ret!(self.original(zp0, p1)), unwrap_or_ret!(self.original(zp1, p2)))};
+					unwrap_or_ret!(self.push(d));
+				},
+				// MPPEM, MPS
+				0x4B | 0x4C => unwrap_or_ret!(self.push(self.ppem as i32)),
+				// FLIPON, FLIPOFF
+				0x4D => self.state.auto_flip = true,
+				0x4E => self.state.auto_flip = false,
+				// DEBUG
+				0x4F => {unwrap_or_ret!(self.pop());},
+				// LT, LTEQ, GT, GTEQ, EQ, NEQ
+				0x50..=0x55 => {
+					let b = unwrap_or_ret!(self.pop());
+					let a = unwrap_or_ret!(self.pop());
+					let v = match opcode{
+						0x50 => a < b,
+						0x51 => a <= b,
+						0x52 => a > b,
+						0x53 => a >= b,
+						0x54 => a == b,
+						_ => a != b,
+					};
+					unwrap_or_ret!(self.push(v as i32));
+				},
+				// ODD, EVEN
+				0x56 | 0x57 => {
+					let v = unwrap_or_ret!(self.pop());
+					let odd = (self.round(v) >> 6) & 1 == 1;
+					unwrap_or_ret!(self.push((odd == (opcode == 0x56)) as i32));
+				},
+				// IF
+				0x58 => {
+					if unwrap_or_ret!(self.pop()) == 0{next = unwrap_or_ret!(Self::skip_branch(code, pc + 1, true));}
+				},
+				// EIF
+				0x59 => {},
+				// AND, OR
+				0x5A | 0x5B => {
+					let b = unwrap_or_ret!(self.pop()) != 0;
+					let a = unwrap_or_ret!(self.pop()) != 0;
+					unwrap_or_ret!(self.push(if opcode == 0x5A{a && b}else{a || b} as i32));
+				},
+				// NOT
+				0x5C => {
+					let v = unwrap_or_ret!(self.pop());
+					unwrap_or_ret!(self.push((v == 0) as i32));
+				},
+				// DELTAP1, DELTAP2, DELTAP3
+				0x5D => unwrap_or_ret!(self.delta_exceptions(0, false)),
+				0x71 | 0x72 => unwrap_or_ret!(self.delta_exceptions((opcode - 0x70) as i32, false)),
+				// SDB, SDS
+				0x5E => self.state.delta_base = unwrap_or_ret!(self.pop()),
+				0x5F => self.state.delta_shift = unwrap_or_ret!(self.pop()),
+				// ADD, SUB, DIV, MUL
+				0x60..=0x63 => {
+					let b = unwrap_or_ret!(self.pop());
+					let a = unwrap_or_ret!(self.pop());
+					let v = match opcode{
+						0x60 => a.wrapping_add(b),
+						0x61 => a.wrapping_sub(b),
+						0x62 => {
+							if b == 0{return Err(FromFileErr::InvalidData(()));}
+							(a as i64 * 64 / b as i64) as i32
+						},
+						_ => ((a as i64 * b as i64) / 64) as i32,
+					};
+					unwrap_or_ret!(self.push(v));
+				},
+				// ABS, NEG, FLOOR, CEILING
+				0x64..=0x67 => {
+					let v = unwrap_or_ret!(self.pop());
+					unwrap_or_ret!(self.push(match opcode{
+						0x64 => v.wrapping_abs(),
+						0x65 => v.wrapping_neg(),
+						0x66 => v & !63,
+						_ => v.wrapping_add(63) & !63,
+					}));
+				},
+				// ROUND (engine compensation is always zero)
+				0x68..=0x6B => {
+					let v = unwrap_or_ret!(self.pop());
+					unwrap_or_ret!(self.push(self.round(v)));
+				},
+				// NROUND
+				0x6C..=0x6F => {},
+				// DELTAC1, DELTAC2, DELTAC3
+				0x73..=0x75 => unwrap_or_ret!(self.delta_exceptions((opcode - 0x73) as i32, true)),
+				// SROUND, S45ROUND
+				0x76 => self.state.round_state = RoundState::super_round(unwrap_or_ret!(self.pop()), 64.0),
+				0x77 => self.state.round_state = RoundState::super_round(unwrap_or_ret!(self.pop()), 64.0 / std::f64::consts::SQRT_2),
+				// JROT, JROF
+				0x78 | 0x79 => {
+					let condition = unwrap_or_ret!(self.pop()) != 0;
+					let offset = unwrap_or_ret!(self.pop());
+					if condition == (opcode == 0x78){next = unwrap_or_ret!(Self::jump(code, pc, offset));}
+				},
+				// ROFF, RUTG, RDTG
+				0x7A => self.state.round_state = RoundState::Off,
+				0x7C => self.state.round_state = RoundState::UpToGrid,
+				0x7D => self.state.round_state = RoundState::DownToGrid,
+				// SANGW, AA (obsolete)
+				0x7E | 0x7F => {unwrap_or_ret!(self.pop());},
+				// FLIPPT
+				0x80 => {
+					let zone = self.state.zp[0];
+					unwrap_or_ret!(self.for_each_looped(|h, point|{
+						unwrap_or_ret!(h.check_point(zone, point));
+						h.zones[zone].on_curve[point] = !h.zones[zone].on_curve[point];
+						Ok(())
+					}));
+				},
+				// FLIPRGON, FLIPRGOFF
+				0x81 | 0x82 => {
+					let high = unwrap_or_ret!(self.pop_index());
+					let low = unwrap_or_ret!(self.pop_index());
+					let zone = self.state.zp[0];
+					if low > high{return Err(FromFileErr::InvalidData(()));}
+					unwrap_or_ret!(self.check_point(zone, high));
+					for on_curve in self.zones[zone].on_curve[low..=high].iter_mut(){*on_curve = opcode == 0x81;}
+				},
+				// SCANCTRL, SCANTYPE (there is no dropout control to configure)
+				0x85 | 0x8D => {unwrap_or_ret!(self.pop());},
+				// SDPVTL
+				0x86 | 0x87 => {
+					let p2 = unwrap_or_ret!(self.pop_index());
+					let p1 = unwrap_or_ret!(self.pop_index());
+					self.state.dual_vector = unwrap_or_ret!(self.line_vector(p2, p1, opcode & 1 != 0, true));
+					self.state.projection_vector = unwrap_or_ret!(self.line_vector(p2, p1, opcode & 1 != 0, false));
+				},
+				// GETINFO
+				0x88 => {
+					let selector = unwrap_or_ret!(self.pop());
+					let mut v = 0;
+					// the version of the classic rasterizer
+					if selector & 1 != 0{v |= 35;}
+					// rendering in grayscale
+					if selector & 32 != 0{v |= 1 << 12;}
+					unwrap_or_ret!(self.push(v));
+				},
+				// IDEF
+				0x89 => {
+					let instruction = unwrap_or_ret!(self.pop());
+					let end = unwrap_or_ret!(Self::find_endf(code, pc + 1));
+					let instruction = instruction as u8;
+					self.instruction_defs.retain(|(op, _)|*op != instruction);
+					self.instruction_defs.push((instruction, code[pc + 1..end].into()));
+					next = end + 1;
+				},
+				// ROLL
+				0x8A => {
+					if self.stack.len() < 3{return Err(FromFileErr::InvalidData(()));}
+					let v = self.stack.remove(self.stack.len() - 3);
+					self.stack.push(v);
+				},
+				// MAX, MIN
+				0x8B | 0x8C => {
+					let b = unwrap_or_ret!(self.pop());
+					let a = unwrap_or_ret!(self.pop());
+					unwrap_or_ret!(self.push(if opcode == 0x8B{a.max(b)}else{a.min(b)}));
+				},
+				// INSTCTRL (only allowed in the control value program)
+				0x8E => {
+					let selector = unwrap_or_ret!(self.pop());
+					let value = unwrap_or_ret!(self.pop());
+					if self.in_prep && (1..=2).contains(&selector){
+						self.state.instruct_control = (self.state.instruct_control & !selector) | if value != 0{selector}else{0};
+					}
+				},
+				// MDRP
+				0xC0..=0xDF => {
+					let point = unwrap_or_ret!(self.pop_index());
+					let [zp0, zp1, _] = self.state.zp;
+					let rp0 = self.state.rp[0];
+					let mut original_distance = self.dual_project(unwrap_or_ret!(self.original(zp1, point)), unwrap_or_ret!(self.original(zp0, rp0)));
+					if (original_distance as i64 - self.state.single_width_value as i64).abs() < self.state.single_width_cut_in as i64{
+						original_distance = if original_distance >= 0{self.state.single_width_value}else{self.state.single_width_value.wrapping_neg()};
+					}
+					let distance = if opcode & 0x04 != 0{self.round(original_distance)}else{original_distance};
+					let distance = if opcode & 0x08 != 0{self.keep_minimum_distance(distance, original_distance)}else{distance};
+					let current_distance = self.project(unwrap_or_ret!(self.current(zp1, point)), unwrap_or_ret!(self.current(zp0, rp0)));
+					unwrap_or_ret!(self.move_point(zp1, point, distance.wrapping_sub(current_distance)));
+					self.state.rp[1] = rp0;
+					self.state.rp[2] = point;
+					if opcode & 0x10 != 0{self.state.rp[0] = point;}
+				},
+				// MIRP
+				0xE0..=0xFF => {
+					let cvt_index = unwrap_or_ret!(self.pop());
+					let point = unwrap_or_ret!(self.pop_index());
+					let [zp0, zp1, _] = self.state.zp;
+					let rp0 = self.state.rp[0];
+					// index -1 is a distance of zero
+					let mut cvt_distance = if cvt_index == -1{0}else{
+						unwrap_or_ret!(self.cvt_value(unwrap_or_ret!(usize::try_from(cvt_index).map_err(|_|FromFileErr::InvalidData(())))))
+					};
+					if (cvt_distance as i64 - self.state.single_width_value as i64).abs() < self.state.single_width_cut_in as i64{
+						cvt_distance = if cvt_distance >= 0{self.state.single_width_value}else{self.state.single_width_value.wrapping_neg()};
+					}
+					if zp1 == 0{unwrap_or_ret!(self.place_twilight_point(point, unwrap_or_ret!(self.original(zp0, rp0)), cvt_distance));}
+					let original_distance = self.dual_project(unwrap_or_ret!(self.original(zp1, point)), unwrap_or_ret!(self.original(zp0, rp0)));
+					let current_distance = self.project(unwrap_or_ret!(self.current(zp1, point)), unwrap_or_ret!(self.current(zp0, rp0)));
+					if self.state.auto_flip && (original_distance ^ cvt_distance) < 0{cvt_distance = cvt_distance.wrapping_neg();}
+					let distance = if opcode & 0x04 != 0{
+						if zp0 == zp1 && (cvt_distance as i64 - original_distance as i64).abs() > self.state.control_value_cut_in as i64
+						{cvt_distance = original_distance;}
+						self.round(cvt_distance)
+					}else{cvt_distance};
+					let distance = if opcode & 0x08 != 0{self.keep_minimum_distance(distance, original_distance)}else{distance};
+					unwrap_or_ret!(self.move_point(zp1, point, distance.wrapping_sub(current_distance)));
+					self.state.rp[1] = rp0;
+					self.state.rp[2] = point;
+					if opcode & 0x10 != 0{self.state.rp[0] = point;}
+				},
+				_ => {
+					let body = match self.instruction_defs.iter().find(|(op, _)|*op == opcode){
+						Some((_, body)) => body.clone(),
+						None => return Err(FromFileErr::InvalidData(())),
+					};
+					unwrap_or_ret!(self.execute(&body, depth + 1));
+				},
+			}
+			pc = next;
+		}
+		Ok(())
+	}
+	///The position `offset` bytes from the jump instruction at `pc`
+	fn jump(code: &[u8], pc: usize, offset: i32)->Result<usize, FromFileErr<(),()>>{
+		match usize::try_from(pc as i64 + offset as i64){
+			Ok(target) if target <= code.len() => Ok(target),
+			_ => Err(FromFileErr::InvalidData(())),
+		}
+	}
+	///Pushes `distance` out to the minimum distance keeping the sign of `original_distance`
+	fn keep_minimum_distance(&self, distance: i32, original_distance: i32)->i32{
+		let minimum = self.state.minimum_distance;
+		if original_distance >= 0{distance.max(minimum)}else{distance.min(minimum.saturating_neg())}
+	}
+}
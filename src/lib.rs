@@ -1,5 +1,19 @@
+#[macro_use]
 pub mod core;
 pub mod char_sets;
+pub mod outline;
+pub mod cff;
+pub mod shaping;
+pub mod raster;
+pub mod hinting;
+pub mod sdf;
+pub mod svg;
+pub mod builder;
+pub mod validate;
+pub mod deflate;
+pub mod woff;
+pub mod brotli;
+pub mod woff2;
 
 #[cfg(test)]
 mod tests {
@@ -30,8 +44,1108 @@ mod tests {
 				);}
 			},
 			crate::core::Table::DSIG(dsig_t) => {println!("{:#?}",dsig_t);}
+			t => {println!("{:#?}",t);}
 		}
 	}
+	fn head_bytes()->Vec<u8>{
+		let mut data = vec![0x00, 0x01, 0x00, 0x00, 0x00, 0x02, 0x80, 0x00];
+		data.extend_from_slice(&[0x12, 0x34, 0x56, 0x78, 0x5F, 0x0F, 0x3C, 0xF5, 0x00, 0x0B, 0x08, 0x00]);
+		data.extend_from_slice(&[0, 0, 0, 0, 0xD2, 0xAB, 0x72, 0x00]);// 2016-01-01
+		data.extend_from_slice(&[0, 0, 0, 0, 0xD2, 0xAB, 0x72, 0x01]);
+		data.extend_from_slice(&[0xFF, 0x9C, 0xFE, 0x0C, 0x04, 0xB0, 0x07, 0xD0, 0x00, 0x03]);
+		data.extend_from_slice(&[0x00, 0x09, 0x00, 0x02, 0x00, 0x01, 0x00, 0x00]);
+		data
+	}
+	fn table_record(tag: &str, offset: u32, length: u32)->crate::core::TableRecord{crate::core::TableRecord{
+		table_tag: crate::core::Tag{data: tag.to_string()},
+		checksum: 0,
+		offset,
+		length,
+	}}
+	#[test]
+	fn test_head() {
+		let head = crate::core::HeadTable::from_file(&mut std::io::Cursor::new(head_bytes())).unwrap();
+		assert_eq!(head.font_revision.to_f64(), 2.5);
+		assert_eq!(head.magic_number, crate::core::HeadTable::MAGIC_NUMBER);
+		assert_eq!(head.units_per_em, 2048);
+		assert_eq!(head.created_unix(), 1451606400);
+		assert_eq!((head.x_min, head.y_min, head.x_max, head.y_max), (-100, -500, 1200, 2000));
+		assert!(head.is_bold() && head.is_italic());
+		assert!(head.long_loca_offsets());
+		let mut data = head_bytes();
+		data[20..28].copy_from_slice(&i64::MIN.to_be_bytes());
+		let head = crate::core::HeadTable::from_file(&mut std::io::Cursor::new(data)).unwrap();
+		assert_eq!(head.created_unix(), i64::MIN.wrapping_sub(2_082_844_800));
+	}
+	fn be_bytes(words: &[u16])->Vec<u8>{words.iter().flat_map(|w|w.to_be_bytes()).collect()}
+	#[test]
+	fn test_cmap() {
+		let mut data = be_bytes(&[0, 2, 3, 1, 0, 20, 3, 10, 0, 62]);
+		// format 4: 'A'-'C' -> 10.., 'x' via glyph id array, 0xFFFF terminator
+		data.extend(be_bytes(&[4, 42, 0, 6, 4, 1, 2, 0x43, 0x78, 0xFFFF, 0, 0x41, 0x78, 0xFFFF, 10u16.wrapping_sub(0x41), 0, 1, 0, 4, 0, 7]));
+		// format 12: 'A'-'C' -> 10.., U+1F600 -> 20
+		data.extend(be_bytes(&[12, 0, 0, 40, 0, 0, 0, 2, 0, 0x41, 0, 0x43, 0, 10, 0x1, 0xF600, 0x1, 0xF600, 0, 20]));
+		let cmap = crate::core::CmapTable::from_file(&mut std::io::Cursor::new(data)).unwrap();
+		assert_eq!(cmap.char_to_glyph('A'), Some(10));
+		assert_eq!(cmap.char_to_glyph('C'), Some(12));
+		assert_eq!(cmap.char_to_glyph('😀'), Some(20));
+		assert_eq!(cmap.char_to_glyph('D'), None);
+		let fmt4 = &cmap.subtables[0];
+		assert_eq!(fmt4.glyph_id('x' as u32), Some(7));
+		assert_eq!(fmt4.mappings().collect::<Vec<_>>(), vec![(0x41, 10), (0x42, 11), (0x43, 12), (0x78, 7)]);
+		assert_eq!(cmap.mappings().collect::<Vec<_>>(), vec![('A', 10), ('B', 11), ('C', 12), ('😀', 20)]);
+
+		// a format 12 subtable claiming far more groups than it holds, and one whose glyph ids overflow
+		let mut data = be_bytes(&[0, 1, 3, 10, 0, 12]);
+		data.extend(be_bytes(&[12, 0, 0xFFFF, 0xFFFF, 0, 0, 0x1000, 0]));
+		assert!(crate::core::CmapTable::from_file(&mut std::io::Cursor::new(data)).is_err());
+		let mut data = be_bytes(&[0, 1, 3, 10, 0, 12]);
+		data.extend(be_bytes(&[12, 0, 0, 28, 0, 0, 0, 1, 0, 0, 0x10, 0, 0xFFFF, 0xFFFF]));
+		let cmap = crate::core::CmapTable::from_file(&mut std::io::Cursor::new(data)).unwrap();
+		assert_eq!(cmap.subtables[0].glyph_id(0), None);
+		assert_eq!(cmap.subtables[0].glyph_id(5), None);
+	}
+	#[test]
+	fn test_glyf() {
+		use crate::{core::{Table, FromFileErr}, outline::{PathCommand, Point}};
+		let mut data = head_bytes();
+		let glyf_start = data.len() as u32;
+		// a 100 unit square
+		data.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 100, 0, 100, 0, 3, 0, 0, 0x31, 0x33, 0x35, 0x23, 100, 100, 100, 0]);
+		// the square at half scale offset by 100 units
+		data.extend_from_slice(&[0xFF, 0xFF, 0, 0, 0, 0, 0, 0, 0, 0, 0x00, 0x0B, 0, 0, 0, 100, 0, 0, 0x20, 0]);
+		// a composite containing itself
+		data.extend_from_slice(&[0xFF, 0xFF, 0, 0, 0, 0, 0, 0, 0, 0, 0x00, 0x02, 0, 2, 0, 0]);
+		let loca_start = data.len() as u32;
+		for offset in [0u32, 22, 42, 58]{data.extend_from_slice(&offset.to_be_bytes());}
+		let mut f = std::io::Cursor::new(data);
+		let head = crate::core::HeadTable::from_file(&mut f).unwrap();
+		let Ok(Table::Glyf(glyf)) = table_record("glyf", glyf_start, 58).get_table(&mut f) else {panic!()};
+		let Ok(Table::Loca(loca)) = table_record("loca", loca_start, 16).get_table(&mut f) else {panic!()};
+		assert_eq!(loca.num_glyphs(&head), 3);
+
+		let outline = glyf.get_outline(&mut f, &loca, &head, 0).unwrap();
+		assert_eq!(outline.commands.len(), 6);
+		assert_eq!(outline.commands[2], PathCommand::LineTo(Point::new(100.0, 100.0)));
+		let outline = glyf.get_outline(&mut f, &loca, &head, 1).unwrap();
+		assert_eq!(outline.control_bounds(), Some((Point::new(100.0, 0.0), Point::new(150.0, 50.0))));
+		assert!(matches!(glyf.get_outline(&mut f, &loca, &head, 2), Err(FromFileErr::InvalidData(()))));
+
+		// 40000 points and a composite of it twice which has too many points to number
+		let mut data = head_bytes();
+		let glyf_start = data.len() as u32;
+		data.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0x9C, 0x3F, 0, 0]);
+		for _ in 0..156{data.extend_from_slice(&[0x39, 255]);}
+		data.extend_from_slice(&[0x39, 63]);
+		let simple_length = data.len() as u32 - glyf_start;
+		data.extend_from_slice(&[0xFF, 0xFF, 0, 0, 0, 0, 0, 0, 0, 0, 0x00, 0x22, 0, 0, 0, 0, 0x00, 0x02, 0, 0, 0, 0]);
+		let loca_start = data.len() as u32;
+		for offset in [0, simple_length, loca_start - glyf_start]{data.extend_from_slice(&offset.to_be_bytes());}
+		let mut f = std::io::Cursor::new(data);
+		let Ok(Table::Glyf(glyf)) = table_record("glyf", glyf_start, loca_start - glyf_start).get_table(&mut f) else {panic!()};
+		let Ok(Table::Loca(loca)) = table_record("loca", loca_start, 12).get_table(&mut f) else {panic!()};
+		assert_eq!(glyf.get_contours(&mut f, &loca, &head, 0).unwrap().points.len(), 40000);
+		assert!(matches!(glyf.get_contours(&mut f, &loca, &head, 1), Err(FromFileErr::InvalidData(()))));
+	}
+	#[test]
+	fn test_cff() {
+		use crate::outline::{PathCommand, Point};
+		let mut data = vec![1, 0, 4, 4, 0, 1, 1, 1, 2, b'A'];
+		// top DICT: CharStrings at 28, Private (2 bytes) at 45
+		data.extend_from_slice(&[0, 1, 1, 1, 10, 28, 0, 28, 17, 141, 28, 0, 45, 18]);
+		data.extend_from_slice(&[0, 0, 0, 0]);
+		// .notdef then a glyph with a width of 100 that calls local subroutine 0
+		data.extend_from_slice(&[0, 2, 1, 1, 2, 12, 14, 239, 149, 159, 21, 189, 139, 5, 32, 10, 14]);
+		// Private DICT with the local subroutines straight after it
+		data.extend_from_slice(&[141, 19, 0, 1, 1, 1, 5, 139, 189, 5, 11]);
+		let cff = crate::cff::CffTable::from_file(&mut std::io::Cursor::new(data)).unwrap();
+		assert_eq!(cff.font_name().as_deref(), Some("A"));
+		// INDEXes whose count or last offset is past the end of the data
+		use crate::cff::CffIndex;
+		assert!(CffIndex::from_file_cff2(&mut std::io::Cursor::new([0xFF, 0xFF, 0xFF, 0xFF, 4])).is_err());
+		assert!(CffIndex::from_file(&mut std::io::Cursor::new([0, 1, 4, 0, 0, 0, 1, 0xFF, 0xFF, 0xFF, 0xFF])).is_err());
+		assert_eq!(cff.num_glyphs(), 2);
+		let (outline, width) = cff.get_glyph(1).unwrap();
+		assert_eq!(width, 100.0);
+		assert_eq!(outline.commands, vec![
+			PathCommand::MoveTo(Point::new(10.0, 20.0)),
+			PathCommand::LineTo(Point::new(60.0, 20.0)),
+			PathCommand::LineTo(Point::new(60.0, 70.0)),
+			PathCommand::Close,
+		]);
+		assert!(cff.get_outline(0).unwrap().is_empty());
+		// a font with just a top DICT and global subroutines before the rest of the data
+		let cff_bytes = |top_dict: &[u8], gsubrs: &[u8]|{
+			let mut data = vec![1, 0, 4, 4, 0, 1, 1, 1, 2, b'A', 0, 1, 1, 1, top_dict.len() as u8 + 1];
+			data.extend_from_slice(top_dict);
+			data.extend_from_slice(&[0, 0]);
+			data.extend_from_slice(gsubrs);
+			data
+		};
+		// Private DICT sizes and offsets which don't fit in the file
+		let mut data = cff_bytes(&[28, 0, 31, 17, 30, 0x1B, 0x15, 0xFF, 28, 0, 37, 18], &[0, 0]);
+		data.extend_from_slice(&[0, 1, 1, 1, 2, 14]);
+		assert!(crate::cff::CffTable::from_file(&mut std::io::Cursor::new(data)).is_err());
+		let mut data = vec![0];
+		data.extend_from_slice(&cff_bytes(&[30, 0x1B, 0x30, 0xFF, 17], &[0, 0]));
+		let mut cursor = std::io::Cursor::new(data);
+		cursor.set_position(1);
+		assert!(crate::cff::CffTable::from_file(&mut cursor).is_err());
+		// three global subroutines which each call the next 80 times
+		let mut gsubrs = vec![0, 4, 2, 0, 1, 0, 162, 1, 67, 1, 228, 1, 229];
+		for k in 0..3{
+			for _ in 0..80{gsubrs.extend_from_slice(&[33 + k, 29]);}
+			gsubrs.push(11);
+		}
+		gsubrs.push(11);
+		let char_strings = 21 + gsubrs.len() as u16;
+		let mut data = cff_bytes(&[28, (char_strings >> 8) as u8, char_strings as u8, 17], &gsubrs);
+		// the second glyph calls a subroutine number too big for an i32
+		data.extend_from_slice(&[0, 2, 1, 1, 4, 17, 32, 29, 14, 28, 0x7F, 0xFF, 28, 0x7F, 0xFF, 12, 24, 143, 12, 24, 29, 14]);
+		let cff = crate::cff::CffTable::from_file(&mut std::io::Cursor::new(data)).unwrap();
+		assert!(cff.get_outline(0).is_err());
+		assert!(cff.get_outline(1).is_err());
+	}
+	#[test]
+	fn test_cff2() {
+		use crate::outline::{PathCommand, Point};
+		let mut data = vec![2, 0, 5, 0, 13, 28, 0, 22, 17, 28, 0, 39, 24, 28, 0, 71, 12, 36, 0, 0, 0, 0];
+		// moves to (10, 20) with 100 units added to x at the peak of the only region
+		data.extend_from_slice(&[0, 0, 0, 1, 1, 1, 11, 149, 159, 239, 139, 141, 16, 21, 189, 139, 5]);
+		data.extend_from_slice(&[0, 30, 0, 1, 0, 0, 0, 12, 0, 1, 0, 0, 0, 22]);
+		data.extend_from_slice(&[0, 1, 0, 1, 0, 0, 0x40, 0, 0x40, 0, 0, 0, 0, 0, 0, 1, 0, 0]);
+		data.extend_from_slice(&[0, 0, 0, 1, 1, 1, 6, 139, 28, 0, 0, 18]);
+		let cff2 = crate::cff::Cff2Table::from_file(&mut std::io::Cursor::new(&data)).unwrap();
+		assert_eq!(cff2.num_glyphs(), 1);
+		assert_eq!(cff2.get_outline(0, &[]).unwrap().commands[..2], [
+			PathCommand::MoveTo(Point::new(10.0, 20.0)),
+			PathCommand::LineTo(Point::new(60.0, 20.0)),
+		]);
+		assert_eq!(cff2.get_outline(0, &[0.5]).unwrap().commands[0], PathCommand::MoveTo(Point::new(60.0, 20.0)));
+		assert_eq!(cff2.get_outline(0, &[1.0]).unwrap().commands[0], PathCommand::MoveTo(Point::new(110.0, 20.0)));
+		assert_eq!(cff2.get_outline(0, &[-0.5]).unwrap().commands[0], PathCommand::MoveTo(Point::new(10.0, 20.0)));
+		// blends with an infinite number of values in a charstring and in the private DICT
+		let mut blend = data.clone();
+		blend[29..40].copy_from_slice(&[140, 139, 12, 12, 16, 139, 139, 139, 139, 139, 139]);
+		let blend = crate::cff::Cff2Table::from_file(&mut std::io::Cursor::new(blend)).unwrap();
+		assert!(blend.get_outline(0, &[]).is_err());
+		let mut blend = data.clone();
+		blend[78..83].copy_from_slice(&[144, 28, 0, 83, 18]);
+		blend.extend_from_slice(&[30, 0x1B, 0x30, 0xFF, 23]);
+		assert!(crate::cff::Cff2Table::from_file(&mut std::io::Cursor::new(blend)).is_err());
+		// an FDSelect with far more ranges than the table holds
+		let mut fd_select = vec![2, 0, 5, 0, 18, 28, 0, 27, 17, 28, 0, 44, 24, 28, 0, 76, 12, 36, 28, 0, 88, 12, 37];
+		fd_select.extend_from_slice(&data[18..]);
+		fd_select.extend_from_slice(&[4, 0xFF, 0xFF, 0xFF, 0xFF]);
+		assert!(crate::cff::Cff2Table::from_file(&mut std::io::Cursor::new(fd_select)).is_err());
+	}
+	#[test]
+	fn test_hmtx() {
+		use crate::core::Table;
+		let mut data = be_bytes(&[1, 0, 800, (-200i16) as u16, 90, 600, 0, 0, 600, 1, 0, 0, 0, 0, 0, 0, 0, 2]);
+		data.extend(be_bytes(&[500, 10, 600, 20, 30, (-5i16) as u16]));
+		let mut f = std::io::Cursor::new(data);
+		let Ok(Table::Hhea(hhea)) = table_record("hhea", 0, 36).get_table(&mut f) else {panic!()};
+		let Ok(Table::Hmtx(hmtx)) = table_record("hmtx", 36, 12).get_table(&mut f) else {panic!()};
+		assert_eq!((hhea.ascender, hhea.descender, hhea.line_gap), (800, -200, 90));
+		assert_eq!(hmtx.num_glyphs(&hhea), 4);
+		assert_eq!(hmtx.advance_width(&mut f, &hhea, 1).unwrap(), 600);
+		assert_eq!(hmtx.left_side_bearing(&mut f, &hhea, 1).unwrap(), 20);
+		assert_eq!(hmtx.advance_width(&mut f, &hhea, 3).unwrap(), 600);
+		assert_eq!(hmtx.left_side_bearing(&mut f, &hhea, 3).unwrap(), -5);
+		assert!(hmtx.advance_width(&mut f, &hhea, 4).is_err());
+	}
+	#[test]
+	fn test_vertical_metrics() {
+		use crate::core::Table;
+		let mut data = be_bytes(&[1, 0x1000, 880, (-120i16) as u16, 0, 1000, 0, 0, 1000, 0, 1, 0, 0, 0, 0, 0, 0, 1]);
+		data.extend(be_bytes(&[1000, 80, 120]));
+		data.extend(be_bytes(&[1, 0, 880, 2, 3, 900, 7, 820]));
+		let mut f = std::io::Cursor::new(data);
+		let Ok(Table::Vhea(vhea)) = table_record("vhea", 0, 36).get_table(&mut f) else {panic!()};
+		let Ok(Table::Vmtx(vmtx)) = table_record("vmtx", 36, 6).get_table(&mut f) else {panic!()};
+		let Ok(Table::VORG(vorg)) = table_record("VORG", 42, 16).get_table(&mut f) else {panic!()};
+		assert_eq!(vhea.version.0, 0x00011000);
+		assert_eq!(vmtx.vertical_advance(&mut f, &vhea, 1).unwrap(), 1000);
+		assert_eq!(vmtx.top_side_bearing(&mut f, &vhea, 1).unwrap(), 120);
+		assert_eq!(vorg.vertical_origin_y(3), 900);
+		assert_eq!(vorg.vertical_origin_y(5), 880);
+		assert_eq!(vorg.vertical_origin_y(7), 820);
+	}
+	#[test]
+	fn test_maxp() {
+		let maxp = crate::core::MaxpTable::from_file(&mut std::io::Cursor::new(be_bytes(&[0, 0x5000, 300]))).unwrap();
+		assert_eq!(maxp.num_glyphs, 300);
+		assert_eq!(maxp.max_points, None);
+		let data = be_bytes(&[1, 0, 300, 120, 8, 400, 20, 2, 16, 64, 32, 0, 256, 1024, 4, 2]);
+		let maxp = crate::core::MaxpTable::from_file(&mut std::io::Cursor::new(data)).unwrap();
+		assert_eq!(maxp.max_points, Some(120));
+		assert_eq!(maxp.max_zones, Some(2));
+		assert_eq!(maxp.max_stack_elements, Some(256));
+		assert_eq!(maxp.max_component_depth, Some(2));
+	}
+	#[test]
+	fn test_os2() {
+		use crate::core::{EmbeddingPermission, Os2Table, WeightClass, WidthClass};
+		let mut data = be_bytes(&[4, 500, 700, 5, 0x0108, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+		data.extend(be_bytes(&[0, 1, 0, 0, 0, 0, 0, 2, 0x5445, 0x5354, 0x00A0, 0x20, 0xFFFD]));
+		data.extend(be_bytes(&[800, (-200i16) as u16, 0, 1000, 300, 0, 1, 0, 0, 500, 700, 0, 32, 2]));
+		let os2 = Os2Table::from_file(&mut std::io::Cursor::new(data)).unwrap();
+		assert_eq!(os2.vendor_id(), "TEST");
+		assert_eq!(os2.embedding_permission(), EmbeddingPermission::Editable);
+		assert!(os2.no_subsetting());
+		assert_eq!(os2.weight_class(), WeightClass::Bold);
+		assert_eq!(os2.width_class(), WidthClass::Normal);
+		assert!(os2.is_bold() && os2.use_typo_metrics() && !os2.is_italic());
+		assert_eq!(os2.unicode_ranges(), vec![0, 97]);
+		assert!(os2.has_code_page(0));
+		assert_eq!((os2.s_cap_height, os2.us_break_char, os2.us_max_context), (Some(700), Some(32), Some(2)));
+		assert_eq!(os2.us_lower_optical_point_size, None);
+		assert_eq!(os2.s_typo_ascender, Some(800));
+
+		// a 68 byte version 0 table with a NUL padded vendor id
+		let mut data = be_bytes(&[0; 29]);
+		data.extend(b"AB\0\0");
+		data.extend(be_bytes(&[0, 0x20, 0x7E]));
+		let os2 = Os2Table::from_file_with_length(&mut std::io::Cursor::new(data), 68).unwrap();
+		assert_eq!((os2.ach_vend_id, os2.vendor_id()), (*b"AB\0\0", "AB\u{FFFD}\u{FFFD}".to_string()));
+		assert_eq!((os2.us_last_char_index, os2.s_typo_ascender, os2.us_win_descent), (0x7E, None, None));
+	}
+	#[test]
+	fn test_post() {
+		use crate::core::PostTable;
+		let mut data = be_bytes(&[2, 0, 0xFFF4, 0x8000, (-100i16) as u16, 50, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0]);
+		data.extend(be_bytes(&[4, 0, 36, 259, 258]));
+		data.extend_from_slice(b"\x03f_f\x05Alpha");
+		let post = PostTable::from_file(&mut std::io::Cursor::new(data)).unwrap();
+		assert_eq!(post.italic_angle.to_f64(), -11.5);
+		assert!(post.is_fixed_pitch());
+		assert_eq!(post.glyph_name(0), Some(".notdef"));
+		assert_eq!(post.glyph_name(1), Some("A"));
+		assert_eq!(post.glyph_name(2), Some("Alpha"));
+		assert_eq!(post.glyph_name(3), Some("f_f"));
+		assert_eq!(post.glyph_name(4), None);
+		let post = PostTable::from_file(&mut std::io::Cursor::new(be_bytes(&[1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]))).unwrap();
+		assert_eq!(post.glyph_name(257), Some("dcroat"));
+		assert_eq!(post.glyph_name(258), None);
+	}
+	fn gsub_bytes()->Vec<u8>{
+		// script list with only a default language system for 'latn', features 'liga' and 'smcp'
+		let mut data = be_bytes(&[1, 0, 10, 32, 58, 1, 0x6C61, 0x746E, 8, 4, 0, 0, 0xFFFF, 2, 0, 1]);
+		data.extend(be_bytes(&[2, 0x6C69, 0x6761, 14, 0x736D, 0x6370, 20, 0, 1, 0, 0, 1, 1]));
+		// lookup 0: ligature 10 11 12 -> 50, lookup 1: single substitution +100 for 5-9 through an extension
+		data.extend(be_bytes(&[2, 6, 40, 4, 0, 1, 8, 1, 8, 1, 14, 1, 1, 10, 1, 4, 50, 3, 11, 12]));
+		data.extend(be_bytes(&[7, 0, 1, 8, 1, 1, 0, 8, 1, 6, 100, 2, 1, 5, 9, 0]));
+		data
+	}
+	#[test]
+	fn test_gsub() {
+		use crate::core::{GsubSubtable, GsubTable};
+		let gsub = GsubTable::from_file(&mut std::io::Cursor::new(gsub_bytes())).unwrap();
+		assert_eq!(gsub.lookup_indices("latn", None, &["liga"]), vec![0]);
+		assert_eq!(gsub.lookup_indices("latn", Some("TRK "), &["smcp", "liga"]), vec![0, 1]);
+		assert!(gsub.lookup_indices("cyrl", None, &["liga"]).is_empty());
+		let lookups = gsub.lookups("latn", None, &["liga", "smcp"]);
+		match &lookups[0].1.subtables[0]{
+			GsubSubtable::Ligature(l) => {
+				let ligature = &l.ligatures(10).unwrap()[0];
+				assert_eq!((ligature.ligature_glyph, &*ligature.component_glyph_ids), (50, &[11, 12][..]));
+				assert!(l.ligatures(11).is_none());
+			},
+			s => panic!("{:?}", s),
+		}
+		assert_eq!(lookups[1].1.lookup_type, GsubSubtable::EXTENSION);
+		match &lookups[1].1.subtables[0]{
+			GsubSubtable::Single(s) => {
+				assert_eq!(s.substitute(7), Some(107));
+				assert_eq!(s.substitute(4), None);
+			},
+			s => panic!("{:?}", s),
+		}
+		// a ligature subtable whose 16000 sets are all the same set of 16000 copies of one ligature
+		let count = 16000u16;
+		let mut data = be_bytes(&[1, 0, 0, 0, 10, 1, 4, 4, 0, 1, 8, 1, 6 + 2 * count, count]);
+		data.extend(be_bytes(&vec![12 + 2 * count; count as usize]));
+		data.extend(be_bytes(&[1, 1, 10, count]));
+		data.extend(be_bytes(&vec![2 + 2 * count; count as usize]));
+		data.extend(be_bytes(&[50, 2, 11]));
+		assert!(GsubTable::from_file(&mut std::io::Cursor::new(data)).is_err());
+	}
+	fn gpos_bytes()->Vec<u8>{
+		// 'DFLT' script with features 'kern' and 'mark'
+		let mut data = be_bytes(&[1, 0, 10, 32, 58, 1, 0x4446, 0x4C54, 8, 4, 0, 0, 0xFFFF, 2, 0, 1]);
+		data.extend(be_bytes(&[2, 0x6B65, 0x726E, 14, 0x6D61, 0x726B, 20, 0, 1, 0, 0, 1, 1]));
+		// lookup 0: pair 20 21 has an x advance of -50 on the first glyph
+		data.extend(be_bytes(&[2, 6, 38, 2, 0, 1, 8, 1, 12, 4, 0, 1, 18, 1, 1, 20, 1, 21, (-50i16) as u16]));
+		// lookup 1: mark 30 attaches to base 20, the base anchor has a device table for 12-13ppem
+		data.extend(be_bytes(&[4, 0, 1, 8, 1, 12, 18, 1, 24, 36, 1, 1, 30, 1, 1, 20, 1, 0, 6, 1, 100, 500]));
+		data.extend(be_bytes(&[1, 4, 3, 250, 700, 10, 0, 12, 13, 1, 0x7000]));
+		data
+	}
+	#[test]
+	fn test_gpos() {
+		use crate::core::{Anchor, GposSubtable, GposTable};
+		let gpos = GposTable::from_file(&mut std::io::Cursor::new(gpos_bytes())).unwrap();
+		let lookups = gpos.lookups("latn", None, &["kern", "mark"]);
+		assert_eq!(lookups.len(), 2);
+		match &lookups[0].1.subtables[0]{
+			GposSubtable::Pair(p) => {
+				let (first, second) = p.adjustment(20, 21).unwrap();
+				assert_eq!((first.x_advance, first.x_placement, second.x_advance), (-50, 0, 0));
+				assert!(p.adjustment(20, 22).is_none());
+				assert!(p.adjustment(21, 21).is_none());
+			},
+			s => panic!("{:?}", s),
+		}
+		match &lookups[1].1.subtables[0]{
+			GposSubtable::MarkBase(m) => {
+				let (mark, base) = m.attachment(30, 20).unwrap();
+				assert_eq!((mark.coordinates(), base.coordinates()), ((100, 500), (250, 700)));
+				match base{
+					Anchor::Format3{x_device: Some(d), y_device: None, ..} =>
+						assert_eq!((d.pixel_delta(11), d.pixel_delta(12), d.pixel_delta(13)), (0, 1, -1)),
+					a => panic!("{:?}", a),
+				}
+				assert!(m.attachment(20, 20).is_none());
+			},
+			s => panic!("{:?}", s),
+		}
+		// a base array of 0xFFFF rows of 0xFFFF mark classes in a 20 byte subtable
+		use crate::core::LookupSubtable;
+		let data = be_bytes(&[1, 12, 12, 0xFFFF, 16, 18, 1, 0, 0, 0xFFFF]);
+		assert!(GposSubtable::from_file_with_type(&mut std::io::Cursor::new(data), 4).is_err());
+	}
+	#[test]
+	fn test_gdef() {
+		use crate::core::{GdefTable, GlyphClass, LookupFlag};
+		// bases 10-19, ligature 20, marks 30-39
+		let mut data = be_bytes(&[1, 2, 14, 0, 36, 62, 74, 2, 3, 10, 19, 1, 20, 20, 2, 30, 39, 3]);
+		// ligature 20 has a caret at 300 and one on contour point 5
+		data.extend(be_bytes(&[6, 1, 12, 1, 1, 20, 2, 6, 10, 1, 300, 2, 5]));
+		// marks 30-31 have attachment class 1 and 32 class 2, mark glyph set 0 is just 31
+		data.extend(be_bytes(&[1, 30, 3, 1, 1, 2, 1, 1, 0, 8, 1, 1, 31]));
+		let gdef = GdefTable::from_file(&mut std::io::Cursor::new(data)).unwrap();
+		assert_eq!(gdef.glyph_class(15), GlyphClass::Base);
+		assert_eq!(gdef.glyph_class(20), GlyphClass::Ligature);
+		assert_eq!(gdef.glyph_class(31), GlyphClass::Mark);
+		assert_eq!(gdef.glyph_class(5), GlyphClass::Unclassified);
+		let carets: Vec<_> = gdef.ligature_carets(20).unwrap().iter().map(|c|c.coordinate()).collect();
+		assert_eq!(carets, vec![Some(300), None]);
+		assert_eq!((gdef.mark_attachment_class(30), gdef.mark_attachment_class(32), gdef.mark_attachment_class(35)), (1, 2, 0));
+		assert!(gdef.is_in_mark_glyph_set(0, 31));
+		assert!(!gdef.is_in_mark_glyph_set(0, 30));
+		assert!(!gdef.is_in_mark_glyph_set(1, 31));
+		assert!(gdef.ignores_glyph(LookupFlag(LookupFlag::IGNORE_MARKS), None, 31));
+		assert!(!gdef.ignores_glyph(LookupFlag(LookupFlag::IGNORE_MARKS), None, 15));
+		assert!(gdef.ignores_glyph(LookupFlag(LookupFlag::IGNORE_BASE_GLYPHS), None, 15));
+		assert!(!gdef.ignores_glyph(LookupFlag(LookupFlag::USE_MARK_FILTERING_SET), Some(0), 31));
+		assert!(gdef.ignores_glyph(LookupFlag(LookupFlag::USE_MARK_FILTERING_SET), Some(0), 30));
+		assert!(!gdef.ignores_glyph(LookupFlag(0x0200), None, 32));
+		assert!(gdef.ignores_glyph(LookupFlag(0x0200), None, 30));
+	}
+	#[test]
+	fn test_shape() {
+		use crate::core::{CmapTable, GposTable, GsubTable};
+		use crate::shaping::{shape, PositionedGlyph, ShapingFont};
+		// format 12: 'a'-'c' -> 10-12, 'd'-'e' -> 20-21, 'x' -> 7, U+0301 -> 30
+		let mut cmap = be_bytes(&[0, 1, 3, 10, 0, 12, 12, 0, 0, 64, 0, 0, 0, 4]);
+		cmap.extend(be_bytes(&[0, 0x61, 0, 0x63, 0, 10, 0, 0x64, 0, 0x65, 0, 20, 0, 0x78, 0, 0x78, 0, 7]));
+		cmap.extend(be_bytes(&[0, 0x301, 0, 0x301, 0, 30]));
+		let mut font = ShapingFont{
+			cmap: CmapTable::from_file(&mut std::io::Cursor::new(cmap)).unwrap(),
+			gsub: Some(GsubTable::from_file(&mut std::io::Cursor::new(gsub_bytes())).unwrap()),
+			gpos: Some(GposTable::from_file(&mut std::io::Cursor::new(gpos_bytes())).unwrap()),
+			gdef: None,
+			kern: None,
+			advance_widths: vec![500; 200].into(),
+		};
+		let glyph = |glyph_id, cluster, x_advance, x_offset, y_offset|PositionedGlyph{glyph_id, cluster, x_advance, y_advance: 0, x_offset, y_offset};
+		assert_eq!(shape(&font, "abcx", "latn", None, &["liga", "smcp"]), vec![glyph(50, 0, 500, 0, 0), glyph(107, 3, 500, 0, 0)]);
+		assert_eq!(shape(&font, "abx", "latn", None, &["liga"]), vec![glyph(10, 0, 500, 0, 0), glyph(11, 1, 500, 0, 0), glyph(7, 2, 500, 0, 0)]);
+		assert_eq!(shape(&font, "de", "latn", None, &["kern"]), vec![glyph(20, 0, 450, 0, 0), glyph(21, 1, 500, 0, 0)]);
+		assert_eq!(shape(&font, "d\u{301}", "latn", None, &["mark"]), vec![glyph(20, 0, 500, 0, 0), glyph(30, 1, 0, -350, 200)]);
+		// right to left text comes out in visual order
+		assert_eq!(shape(&font, "d\u{301}x", "arab", None, &["mark"]), vec![glyph(7, 3, 500, 0, 0), glyph(30, 1, 0, 150, 200), glyph(20, 0, 500, 0, 0)]);
+		// the kern table is only used without GPOS
+		font.kern = Some(crate::core::KernTable::from_file(&mut std::io::Cursor::new(be_bytes(&[0, 1, 0, 20, 1, 1, 6, 0, 0, 20, 21, (-30i16) as u16]))).unwrap());
+		assert_eq!(shape(&font, "de", "latn", None, &["kern"])[0].x_advance, 450);
+		font.gpos = None;
+		assert_eq!(shape(&font, "de", "latn", None, &["kern"])[0].x_advance, 470);
+		assert_eq!(shape(&font, "de", "latn", None, &[])[0].x_advance, 500);
+		// contextual lookups which apply themselves twice at every level of nesting
+		let recursive = |lookup_type|{
+			let mut data = be_bytes(&[1, 0, 10, 30, 44, 1, 0x6C61, 0x746E, 8, 4, 0, 0, 0xFFFF, 1, 0]);
+			data.extend(be_bytes(&[1, 0x6C69, 0x6761, 8, 0, 1, 0, 1, 4, lookup_type, 0, 1, 8]));
+			data.extend(be_bytes(&[3, 1, 2, 16, 0, 0, 0, 0, 1, 1, 10]));
+			std::io::Cursor::new(data)
+		};
+		font.gsub = Some(GsubTable::from_file(&mut recursive(5)).unwrap());
+		font.gpos = Some(GposTable::from_file(&mut recursive(7)).unwrap());
+		assert_eq!(shape(&font, "a", "latn", None, &["liga"]), vec![glyph(10, 0, 500, 0, 0)]);
+	}
+	#[test]
+	fn test_kern() {
+		use crate::core::KernTable;
+		// microsoft header with a horizontal format 0 subtable
+		let data = be_bytes(&[0, 1, 0, 26, 0x0001, 2, 12, 0, 0, 3, 4, (-40i16) as u16, 3, 6, 25]);
+		let kern = KernTable::from_file(&mut std::io::Cursor::new(data)).unwrap();
+		assert!(!kern.is_apple());
+		assert_eq!((kern.kerning(3, 4), kern.kerning(3, 6), kern.kerning(4, 3)), (-40, 25, 0));
+		// apple header with a format 2 subtable, a format 3 subtable and a vertical one that is ignored
+		let mut data = be_bytes(&[1, 0, 0, 3]);
+		// glyphs 5-6 are left classes 0-1, glyph 7 is right class 1 of 2
+		data.extend(be_bytes(&[0, 38, 0x0002, 0, 4, 16, 24, 30, 5, 2, 30, 34, 7, 1, 2, 0, 10, 0, (-20i16) as u16]));
+		// 4 glyphs where 1 and 2 are in left class 1 and 3 in right class 1
+		data.extend(be_bytes(&[0, 30, 0x0003, 0, 4, 0x0202, 0x0200, 0, 15, 0x0001, 0x0100, 0, 0x0001, 0, 0x0001]));
+		data.extend(be_bytes(&[0, 16, 0x8000, 0, 0, 0, 0, 0]));
+		let kern = KernTable::from_file(&mut std::io::Cursor::new(data)).unwrap();
+		assert!(kern.is_apple());
+		assert_eq!(kern.subtables.len(), 3);
+		assert_eq!((kern.kerning(5, 7), kern.kerning(6, 7), kern.kerning(6, 8)), (10, -20, 0));
+		assert_eq!((kern.kerning(1, 3), kern.kerning(2, 3), kern.kerning(0, 3), kern.kerning(1, 2)), (15, 15, 0, 0));
+	}
+	#[test]
+	fn test_raster() {
+		use crate::outline::{Outline, Point};
+		use crate::raster::{rasterize, rasterize_glyph};
+		let mut square = Outline::new();
+		square.move_to(Point::new(0.0, 0.0));
+		square.line_to(Point::new(0.0, 2048.0));
+		square.line_to(Point::new(2048.0, 2048.0));
+		square.line_to(Point::new(2048.0, 0.0));
+		square.close();
+		let head = crate::core::HeadTable::from_file(&mut std::io::Cursor::new(head_bytes())).unwrap();
+		let bitmap = rasterize_glyph(&square, &head, 4.0, Point::default());
+		assert_eq!((bitmap.width, bitmap.height, bitmap.left, bitmap.top), (4, 4, 0, 4));
+		assert!(bitmap.data.iter().all(|c|*c == 255));
+		// half a pixel to the right splits the edge pixels
+		let bitmap = rasterize_glyph(&square, &head, 4.0, Point::new(0.5, 0.0));
+		assert_eq!((bitmap.width, bitmap.left), (5, 0));
+		assert_eq!((bitmap.get(0, 0), bitmap.get(2, 3), bitmap.get(4, 1), bitmap.get(5, 0)), (Some(128), Some(255), Some(128), None));
+		// a right triangle covers half of each pixel on its diagonal
+		let mut triangle = Outline::new();
+		triangle.move_to(Point::new(0.0, 0.0));
+		triangle.line_to(Point::new(8.0, 8.0));
+		triangle.line_to(Point::new(8.0, 0.0));
+		let bitmap = rasterize(&triangle, 1.0, Point::default());
+		assert_eq!((bitmap.width, bitmap.height), (8, 8));
+		assert_eq!((bitmap.get(0, 7), bitmap.get(7, 0), bitmap.get(7, 7), bitmap.get(0, 0)), (Some(128), Some(128), Some(255), Some(0)));
+		let area: u32 = bitmap.data.iter().map(|c|*c as u32).sum();
+		assert!((area as i32 - 32 * 255).abs() < 8);
+		// a curve gets close to the exact area of a quadratic segment (2/3 of its bounding box)
+		let mut curve = Outline::new();
+		curve.move_to(Point::new(0.0, 0.0));
+		curve.quad_to(Point::new(8.0, 16.0), Point::new(16.0, 0.0));
+		curve.close();
+		let bitmap = rasterize(&curve, 1.0, Point::default());
+		let area: u32 = bitmap.data.iter().map(|c|*c as u32).sum();
+		assert!(((area as f32 / 255.0) - 16.0 * 8.0 * 2.0 / 3.0).abs() < 1.5);
+		assert!(rasterize(&Outline::new(), 1.0, Point::default()).data.is_empty());
+		// outlines too big to render give an empty bitmap
+		let mut huge = Outline::new();
+		huge.move_to(Point::new(-1e30, -1e30));
+		huge.line_to(Point::new(1e30, 1e30));
+		huge.line_to(Point::new(1e30, -1e30));
+		assert!(rasterize(&huge, 1.0, Point::default()).data.is_empty());
+		let mut huge = Outline::new();
+		huge.move_to(Point::new(-32767.0, -32767.0));
+		huge.line_to(Point::new(32767.0, 32767.0));
+		huge.line_to(Point::new(32767.0, -32767.0));
+		assert_eq!(rasterize(&huge, 4.0, Point::default()), crate::raster::Bitmap::default());
+	}
+	#[test]
+	fn test_hinting() {
+		use crate::{core::{MaxpTable, Table}, hinting::{GlyphTables, Hinter}};
+		let mut data = head_bytes();
+		let glyf_start = data.len() as u32;
+		// a 290 by 290 unit square: round point 0 in a function, place point 3 with a control value and
+		// interpolate the rest in x, then move point 1 up by a pixel read from the storage area
+		data.extend_from_slice(&[0, 1, 0, 10, 0, 0, 1, 44, 1, 34, 0, 3, 0, 15]);
+		data.extend_from_slice(&[0xB1, 0, 0, 0x2B, 0xB1, 3, 0, 0xFC, 0x31, 0x00, 0xB1, 1, 1, 0x43, 0x38]);
+		data.extend_from_slice(&[0x09, 3]);
+		data.extend(be_bytes(&[10, 0, 290, 0, 0, 290, 0, (-290i16) as u16]));
+		data.push(0);
+		let loca_start = data.len() as u32;
+		data.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 48]);
+		let hhea_start = data.len() as u32;
+		data.extend(be_bytes(&[1, 0, 800, (-200i16) as u16, 0, 400, 0, 0, 400, 1, 0, 0, 0, 0, 0, 0, 0, 1, 400, 10]));
+		data.extend(be_bytes(&[300]));
+		let mut f = std::io::Cursor::new(data);
+		let head = crate::core::HeadTable::from_file(&mut f).unwrap();
+		let Ok(Table::Glyf(glyf)) = table_record("glyf", glyf_start, 48).get_table(&mut f) else {panic!()};
+		let Ok(Table::Loca(loca)) = table_record("loca", loca_start, 8).get_table(&mut f) else {panic!()};
+		let Ok(Table::Hhea(hhea)) = table_record("hhea", hhea_start, 36).get_table(&mut f) else {panic!()};
+		let Ok(Table::Hmtx(hmtx)) = table_record("hmtx", hhea_start + 36, 4).get_table(&mut f) else {panic!()};
+		let Ok(Table::Cvt(cvt)) = table_record("cvt ", hhea_start + 40, 2).get_table(&mut f) else {panic!()};
+		assert_eq!(&*cvt.values, &[300]);
+		assert!(table_record("fpgm", hhea_start + 40, u32::MAX).get_table(&mut f).is_err());
+		let maxp = MaxpTable::from_file(&mut std::io::Cursor::new(be_bytes(&[1, 0, 1, 4, 1, 0, 0, 2, 2, 4, 1, 0, 32, 15, 0, 0]))).unwrap();
+		// function 0 rounds a point, the control value program stores 64 in location 1
+		let fpgm = [0xB0, 0, 0x2C, 0x2F, 0x2D];
+		let prep = [0xB1, 1, 64, 0x42];
+		// 16 pixels per em is 128 units per pixel
+		let mut hinter = Hinter::new(&fpgm, &prep, &cvt.values, &maxp, head.units_per_em, 16).unwrap();
+		let tables = GlyphTables{head: &head, loca: &loca, glyf: &glyf, hhea: &hhea, hmtx: &hmtx};
+		let glyph = hinter.hint_glyph(&mut f, &tables, 0).unwrap();
+		let points: Vec<(f32, f32)> = glyph.contours.points.iter().map(|p|(p.x, p.y)).collect();
+		assert_eq!(points, [(0.0, 0.0), (0.0, 209.0 / 64.0), (2.0, 145.0 / 64.0), (2.0, 0.0)]);
+		assert_eq!(glyph.advance_width, 3.0);
+		// a program that jumps back to its start forever
+		assert!(Hinter::new(&[0xB8, 0xFF, 0xFD, 0x1C], &[], &[], &maxp, head.units_per_em, 16).is_err());
+		assert!(Hinter::new(&[0x2B], &[], &[], &maxp, head.units_per_em, 16).is_err());
+		// a delta base of i32::MAX - 3 doesn't overflow when the ppem of a DELTAC1 exception is worked out
+		let mut fpgm = vec![0xB9, 0x7F, 0xFF, 0x7F, 0xFF, 0x63, 0xB8, 0x20, 0x00, 0x63];
+		for _ in 0..4{fpgm.extend_from_slice(&[0xB8, 0x7F, 0xFF, 0x60]);}
+		fpgm.extend_from_slice(&[0x5E, 0xB2, 0xF0, 0, 1, 0x73]);
+		assert!(Hinter::new(&fpgm, &[], &[], &maxp, head.units_per_em, 16).is_ok());
+		// calling an empty function i32::MAX - 3 times runs out of budget
+		let mut loop_call = vec![0xB0, 0, 0x2C, 0x2D];
+		loop_call.extend_from_slice(&fpgm[..26]);
+		loop_call.extend_from_slice(&[0xB0, 0, 0x2A]);
+		assert!(Hinter::new(&loop_call, &[], &[], &maxp, head.units_per_em, 16).is_err());
+		// IP with a twilight point shifted almost i32::MAX away from where it started
+		let mut ip = vec![0xB0, 0, 0x16, 0xB0, 1, 0xB8, 0x03, 0xE8, 0x48, 0xB0, 1];
+		ip.extend_from_slice(&fpgm[..26]);
+		ip.extend_from_slice(&[0xB8, 0x01, 0xF0, 0x61, 0x38, 0xB0, 0, 0x11, 0xB0, 0, 0x12, 0xB0, 1, 0x39]);
+		assert!(Hinter::new(&ip, &[], &[], &maxp, head.units_per_em, 16).is_ok());
+	}
+	#[test]
+	fn test_sdf() {
+		use crate::outline::{Outline, Point};
+		use crate::sdf::{AtlasBuilder, SdfSettings, generate_msdf, generate_sdf};
+		let mut square = Outline::new();
+		square.move_to(Point::new(0.0, 0.0));
+		square.line_to(Point::new(0.0, 100.0));
+		square.line_to(Point::new(100.0, 100.0));
+		square.line_to(Point::new(100.0, 0.0));
+		square.close();
+		let settings = SdfSettings{scale: 0.1, range: 4.0, padding: 2};
+		let sdf = generate_sdf(&square, &settings);
+		assert_eq!((sdf.width, sdf.height, sdf.channels, sdf.left, sdf.top), (14, 14, 1, -2, 12));
+		// half a pixel inside and outside the left edge, the middle and an outside corner
+		assert_eq!((sdf.get(2, 7, 0), sdf.get(1, 7, 0), sdf.get(7, 7, 0), sdf.get(0, 0, 0)), (Some(159), Some(96), Some(255), Some(0)));
+		assert_eq!(sdf.get(1, 1, 0), Some(82));
+		let msdf = generate_msdf(&square, &settings);
+		assert_eq!(msdf.channels, 3);
+		let median = |x: u32, y: u32|{
+			let mut v = [0, 1, 2].map(|c|msdf.get(x, y, c).unwrap());
+			v.sort();
+			v[1]
+		};
+		assert_eq!((median(2, 7), median(1, 7), median(7, 7)), (159, 96, 255));
+		// the corner stays sharp: the median is the distance to the nearest edge's line rather than to the corner
+		assert_eq!(median(1, 1), 96);
+
+		let mut builder = AtlasBuilder::new(settings, 1000, false, 32);
+		builder.add_glyph(3, &Outline::new(), 250.0);
+		builder.add_glyph(4, &square, 600.0);
+		let atlas = builder.build().unwrap();
+		assert_eq!((atlas.width, atlas.height, atlas.em_size), (32, 16, 100.0));
+		let glyph = atlas.glyph(4).unwrap();
+		assert_eq!(glyph.advance, 0.6);
+		assert_eq!(glyph.plane_bounds, Some([-0.02, -0.02, 0.12, 0.12]));
+		assert_eq!(glyph.atlas_bounds, Some([1, 1, 15, 15]));
+		assert_eq!(glyph.uv, Some([1.0 / 32.0, 1.0 / 16.0, 15.0 / 32.0, 15.0 / 16.0]));
+		assert_eq!(atlas.data[(8 * 32 + 8) as usize], 255);
+		assert_eq!(atlas.glyph(3).unwrap().atlas_bounds, None);
+		assert!(atlas.metadata_json().contains("{\"index\":3,\"advance\":0.25}"));
+		assert!(AtlasBuilder::new(settings, 1000, false, 8).build().is_some());
+		let mut builder = AtlasBuilder::new(settings, 1000, false, 8);
+		builder.add_glyph(4, &square, 600.0);
+		assert!(builder.build().is_none());
+		// outlines and padding too big for a field give an empty one
+		let huge = SdfSettings{scale: 1e30, ..settings};
+		assert!(generate_sdf(&square, &huge).data.is_empty());
+		let huge = SdfSettings{scale: 655.36, ..settings};
+		assert_eq!(generate_msdf(&square, &huge).width, 0);
+		let huge = SdfSettings{padding: u32::MAX, ..settings};
+		assert!(generate_sdf(&square, &huge).data.is_empty());
+	}
+	#[test]
+	fn test_svg() {
+		use crate::{core::Table, outline::{Outline, Point}, shaping::PositionedGlyph};
+		use crate::svg::{font_to_pixel_transform, glyph_path, shaped_text_svg};
+		let mut glyph = Outline::new();
+		glyph.move_to(Point::new(0.0, 0.0));
+		glyph.line_to(Point::new(0.0, 1024.0));
+		glyph.quad_to(Point::new(512.0, 1536.0), Point::new(1024.0, 1024.0));
+		glyph.curve_to(Point::new(1024.0, 512.0), Point::new(768.0, -256.0), Point::new(1024.0, 0.0));
+		glyph.close();
+		let transform = font_to_pixel_transform(2048, 16.0, Point::new(0.0, 16.0));
+		assert_eq!(glyph_path(&glyph, &transform), "<path d=\"M 0 16 L 0 8 Q 4 4 8 8 C 8 12 6 18 8 16 Z\"/>");
+
+		let mut data = head_bytes();
+		data.extend(be_bytes(&[1, 0, 1536, (-512i16) as u16, 0, 1024, 0, 0, 1024, 1, 0, 0, 0, 0, 0, 0, 0, 1]));
+		let mut f = std::io::Cursor::new(data);
+		let head = crate::core::HeadTable::from_file(&mut f).unwrap();
+		let Ok(Table::Hhea(hhea)) = table_record("hhea", 54, 36).get_table(&mut f) else {panic!()};
+		let positioned = |glyph_id, x_advance, y_offset|PositionedGlyph{glyph_id, cluster: 0, x_advance, y_advance: 0, x_offset: 0, y_offset};
+		let glyphs = [positioned(1, 1024, 0), positioned(2, 512, 0), positioned(1, 1024, 256)];
+		let svg = shaped_text_svg(&glyphs, |g|if g == 1{Some(glyph.clone())}else{None}, &head, &hhea, 16.0);
+		let lines: Vec<&str> = svg.lines().collect();
+		assert_eq!(lines.len(), 4);
+		assert_eq!(lines[0], "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"20\" height=\"16\" viewBox=\"0 0 20 16\">");
+		assert!(lines[1].starts_with("<path d=\"M 0 12 L 0 4 "));
+		// the third glyph is after both advances and raised by its offset
+		assert!(lines[2].starts_with("<path d=\"M 12 10 L 12 2 "));
+		assert_eq!(lines[3], "</svg>");
+	}
+	#[test]
+	fn test_to_file() {
+		use std::io::{Cursor, Seek, SeekFrom};
+		use crate::core::{DSIGTable, OTTF, Table, Tag, ToFile};
+		let mut data = be_bytes(&[0x0001, 0x0000, 2, 32, 1, 0]);
+		data.extend(b"DSIG");
+		data.extend(be_bytes(&[0x1234, 0x5678, 0, 88, 0, 32]));
+		data.extend(b"name");
+		data.extend(be_bytes(&[0x9ABC, 0xDEF0, 0, 44, 0, 44]));
+		// name version 1 with two names and a language tag
+		data.extend(be_bytes(&[1, 2, 36, 3, 1, 0x409, 1, 4, 0, 3, 1, 0x409, 2, 2, 4, 1, 2, 6]));
+		data.extend([0, b'A', 0, b'B', 0, b'C', b'e', b'n']);
+		// DSIG with one signature
+		data.extend(be_bytes(&[0, 1, 1, 1, 0, 1, 0, 12, 0, 20, 0, 0, 0, 4]));
+		data.extend([0xDE, 0xAD, 0xBE, 0xEF]);
+		assert_eq!(data.len(), 120);
+
+		let mut f = Cursor::new(data.clone());
+		let font = OTTF::from_file(&mut f).unwrap();
+		let Ok(Some(Table::Name(name))) = font.load_table(&mut f, "name") else {panic!()};
+		let Ok(Some(Table::DSIG(dsig))) = font.load_table(&mut f, "DSIG") else {panic!()};
+		assert_eq!(&*name.storage, b"\0A\0B\0Cen");
+		assert_eq!(&*dsig.signature_blocks[0].signature, &[0xDE, 0xAD, 0xBE, 0xEF]);
+
+		let mut out = Cursor::new(Vec::new());
+		font.table_directory.to_file(&mut out).unwrap();
+		out.seek(SeekFrom::Start(44)).unwrap();
+		name.to_file(&mut out).unwrap();
+		dsig.to_file(&mut out).unwrap();
+		assert_eq!(out.into_inner(), data);
+
+		// padding after the last string is part of the storage
+		let mut padded = data[44..88].to_vec();
+		padded.extend([0, 0]);
+		let name = crate::core::NameTable::from_file_with_length(&mut Cursor::new(padded.clone()), 46).unwrap();
+		let mut out = Cursor::new(Vec::new());
+		name.to_file(&mut out).unwrap();
+		assert_eq!(out.into_inner(), padded);
+
+		// signature blocks stored out of record order with two records sharing a block
+		let mut shared = be_bytes(&[0, 1, 3, 0, 0, 1, 0, 2, 0, 56, 0, 1, 0, 4, 0, 44, 0, 1, 0, 2, 0, 56]);
+		shared.extend(be_bytes(&[0, 0, 0, 4, 0x0102, 0x0304, 0, 0, 0, 2]));
+		shared.extend([5, 6]);
+		let shared_dsig = DSIGTable::from_file(&mut Cursor::new(shared.clone())).unwrap();
+		assert_eq!(&*shared_dsig.signature_blocks[2].signature, &[5, 6]);
+		let mut out = Cursor::new(Vec::new());
+		shared_dsig.to_file(&mut out).unwrap();
+		assert_eq!(out.into_inner(), shared);
+
+		// counts have to match the arrays they count and tags have to be valid
+		let mut dsig = DSIGTable{num_signatures: 2, ..dsig};
+		assert!(dsig.to_file(&mut Cursor::new(Vec::new())).is_err());
+		dsig.num_signatures = 1;
+		assert!(dsig.to_file(&mut Cursor::new(Vec::new())).is_ok());
+		assert!(Tag{data: "abc".to_string()}.to_file(&mut Cursor::new(Vec::new())).is_err());
+		let mut name = name;
+		name.lang_tag_count = None;
+		assert!(name.to_file(&mut Cursor::new(Vec::new())).is_err());
+	}
+	#[test]
+	fn test_font_builder() {
+		use std::io::Cursor;
+		use crate::builder::FontBuilder;
+		use crate::core::{calc_checksum, HeadTable, OTTF, SFNTVer, Table};
+		let mut f = Cursor::new(head_bytes());
+		let head = HeadTable::from_file(&mut f).unwrap();
+		let mut builder = FontBuilder::new(SFNTVer::TrueType);
+		builder.add_raw_table("post", vec![0, 3, 0, 0, 0]);
+		builder.add_table("head", &head).unwrap();
+		builder.add_raw_table("OS/2", vec![1, 2, 3, 4]);
+		builder.add_raw_table("cmap", vec![9; 7]);
+		let font = builder.build().unwrap();
+		assert_eq!(font.len(), 12 + 4 * 16 + 4 + 8 + 56 + 8);
+		assert_eq!(calc_checksum(&font), 0xB1B0AFBA);
+
+		let mut f = Cursor::new(font.clone());
+		let ottf = OTTF::from_file(&mut f).unwrap();
+		let directory = &ottf.table_directory;
+		assert_eq!((directory.num_tables, directory.search_range, directory.entry_selector, directory.range_shift), (4, 64, 2, 0));
+		let tags: Vec<&str> = directory.table_records.iter().map(|r|r.table_tag.data.as_str()).collect();
+		assert_eq!(tags, ["OS/2", "cmap", "head", "post"]);
+		for record in directory.table_records.iter(){
+			assert_eq!(record.offset % 4, 0);
+			let mut data = font[record.offset as usize..(record.offset + record.length) as usize].to_vec();
+			if record.table_tag.data == "head"{data[8..12].fill(0);}
+			assert_eq!(record.checksum, calc_checksum(&data));
+		}
+		let Ok(Some(Table::Head(read_head))) = ottf.load_table(&mut f, "head") else {panic!()};
+		assert_eq!(read_head.units_per_em, 2048);
+		assert_ne!(read_head.checksum_adjustment, head.checksum_adjustment);
+
+		// rebuilding an unchanged font gives the same bytes
+		let rebuilt = FontBuilder::from_font(&mut f, &ottf).unwrap().build().unwrap();
+		assert_eq!(rebuilt, font);
+		// a table length past the end of the file
+		let mut corrupt = font.clone();
+		corrupt[24..28].copy_from_slice(&0xFFFFFFF0u32.to_be_bytes());
+		let mut f = Cursor::new(corrupt);
+		let ottf = OTTF::from_file(&mut f).unwrap();
+		assert!(FontBuilder::from_font(&mut f, &ottf).is_err());
+		assert_eq!(crate::core::TableDirectory::search_params(5), (64, 2, 16));
+	}
+	#[test]
+	fn test_validate() {
+		use std::io::Cursor;
+		use crate::builder::FontBuilder;
+		use crate::core::{OTTF, SFNTVer};
+		use crate::validate::{validate, Severity};
+		let mut builder = FontBuilder::new(SFNTVer::TrueType);
+		builder.add_raw_table("head", head_bytes());
+		for tag in ["cmap", "hhea", "hmtx", "maxp", "name", "OS/2", "post", "glyf", "loca"]{builder.add_raw_table(tag, vec![1, 2, 3]);}
+		let font = builder.build().unwrap();
+		let check = |font: &Vec<u8>|{
+			let mut f = Cursor::new(font.clone());
+			let ottf = OTTF::from_file(&mut f).unwrap();
+			validate(&ottf, &mut f)
+		};
+		assert_eq!(check(&font), []);
+
+		// tables are sorted by tag with capitals first so glyf is the 3rd record
+		let mut broken = font.clone();
+		let glyf_record = 12 + 16 * 2;
+		assert_eq!(&broken[glyf_record..glyf_record + 4], b"glyf");
+		let glyf_offset = u32::from_be_bytes(broken[glyf_record + 8..glyf_record + 12].try_into().unwrap()) as usize;
+		broken[glyf_offset] ^= 0xFF;
+		broken[6..8].copy_from_slice(&[0, 16]);
+		let diagnostics = check(&broken);
+		assert_eq!(diagnostics.len(), 2);
+		assert_eq!((diagnostics[0].severity, diagnostics[0].tag.as_deref(), diagnostics[0].offset), (Severity::Warning, None, 6));
+		assert_eq!((diagnostics[1].severity, diagnostics[1].tag.as_deref(), diagnostics[1].offset), (Severity::Warning, Some("glyf"), glyf_record as u64 + 4));
+
+		// moving glyf onto the next table, renaming it out of order and making the last table run past the end
+		let mut broken = font.clone();
+		broken[glyf_record + 8..glyf_record + 12].copy_from_slice(&(glyf_offset as u32 + 2).to_be_bytes());
+		broken[glyf_record..glyf_record + 4].copy_from_slice(b"zzzz");
+		let last_record = 12 + 16 * 9;
+		broken[last_record + 12..last_record + 16].copy_from_slice(&100u32.to_be_bytes());
+		let diagnostics = check(&broken);
+		let find = |severity, tag: &str, message: &str|diagnostics.iter().any(|d|d.severity == severity && d.tag.as_deref() == Some(tag) && d.message.contains(message));
+		assert!(find(Severity::Warning, "zzzz", "aligned"));
+		assert!(find(Severity::Error, "head", "sorted"));
+		assert!(find(Severity::Error, "head", "overlaps `zzzz`"));
+		assert!(find(Severity::Error, "post", "past the end"));
+		assert!(find(Severity::Error, "glyf", "missing"));
+		assert!(diagnostics.iter().all(|d|d.tag.is_some()));
+	}
+	#[test]
+	fn test_font_collection() {
+		use std::io::Cursor;
+		use crate::core::{FontCollection, SFNTVer, Table};
+		// a version 2 header with two fonts and a signature
+		let mut data = b"ttcf".to_vec();
+		data.extend(be_bytes(&[2, 0, 0, 2, 0, 32, 0, 76]));
+		data.extend(b"DSIG");
+		data.extend(be_bytes(&[0, 32, 0, 184]));
+		data.extend(be_bytes(&[0x0001, 0x0000, 2, 32, 1, 0]));
+		data.extend(b"head");
+		data.extend(be_bytes(&[0, 0, 0, 120, 0, 54]));
+		// both fonts share the name table
+		data.extend(b"name");
+		data.extend(be_bytes(&[0, 0, 0, 176, 0, 6]));
+		data.extend(be_bytes(&[0x4F54, 0x544F, 1, 16, 0, 0]));
+		data.extend(b"name");
+		data.extend(be_bytes(&[0, 0, 0, 176, 0, 6]));
+		data.extend(be_bytes(&[0; 8]));
+		assert_eq!(data.len(), 120);
+		data.extend(head_bytes());
+		data.extend([0, 0]);
+		data.extend(be_bytes(&[0, 0, 6]));
+		data.extend([0, 0]);
+		data.extend(be_bytes(&[0, 1, 1, 1, 0, 1, 0, 12, 0, 20, 0, 0, 0, 4]));
+		data.extend([0xDE, 0xAD, 0xBE, 0xEF]);
+
+		let mut f = Cursor::new(data);
+		let collection = FontCollection::from_file(&mut f).unwrap();
+		assert_eq!((collection.major_version, collection.num_fonts, collection.dsig_length), (2, 2, Some(32)));
+		let fonts = collection.fonts();
+		assert_eq!(fonts.len(), 2);
+		assert_eq!(fonts[0].table_directory.sfnt_version, SFNTVer::TrueType);
+		assert_eq!(fonts[1].table_directory.sfnt_version, SFNTVer::CFF);
+		let Ok(Some(Table::Head(head))) = fonts[0].load_table(&mut f, "head") else {panic!()};
+		assert_eq!(head.units_per_em, 2048);
+		assert!(fonts[1].load_table(&mut f, "head").unwrap().is_none());
+		for font in fonts{
+			let Ok(Some(Table::Name(name))) = font.load_table(&mut f, "name") else {panic!()};
+			assert_eq!(name.count, 0);
+		}
+		let shared = collection.shared_tables();
+		assert_eq!(shared.len(), 1);
+		assert_eq!((shared[0].0.table_tag.data.as_str(), shared[0].1.as_slice()), ("name", [0, 1].as_slice()));
+		let dsig = collection.load_dsig(&mut f).unwrap().unwrap();
+		assert_eq!(&*dsig.signature_blocks[0].signature, &[0xDE, 0xAD, 0xBE, 0xEF]);
+
+		// a font that isn't a collection is rejected
+		let mut f = Cursor::new(be_bytes(&[0x0001, 0x0000, 0, 0, 0, 0]));
+		assert!(FontCollection::from_file(&mut f).is_err());
+		// more fonts than the file has room for
+		let mut data = b"ttcf".to_vec();
+		data.extend(be_bytes(&[1, 0, 0xFFFF, 0xFFFF, 0, 12]));
+		assert!(FontCollection::from_file(&mut Cursor::new(data)).is_err());
+		// three offsets to the same directory of four tables
+		let mut data = b"ttcf".to_vec();
+		data.extend(be_bytes(&[1, 0, 0, 3, 0, 24, 0, 24, 0, 24, 0x0001, 0x0000, 4, 64, 2, 0]));
+		for tag in [b"head", b"hhea", b"maxp", b"name"]{
+			data.extend(tag);
+			data.extend(be_bytes(&[0; 6]));
+		}
+		assert!(FontCollection::from_file(&mut Cursor::new(data)).is_err());
+	}
+	#[test]
+	fn test_deflate() {
+		use crate::deflate::{inflate, zlib_compress, zlib_decompress};
+		let text: Vec<u8> = (0..40).flat_map(|i|format!("The quick brown fox {} jumps over the lazy dog. ", i * i % 97).into_bytes()).collect();
+		// compressed by zlib with dynamic codes
+		let compressed = [
+			0x78, 0xDA, 0x95, 0x94, 0xD9, 0x0D, 0xC2, 0x40, 0x0C, 0x05, 0x5B, 0x79, 0x15, 0x44, 0x64, 0xB3,
+			0x87, 0xB7, 0x8F, 0x34, 0xC0, 0x91, 0x04, 0xC2, 0xB1, 0x10, 0x08, 0x57, 0xF5, 0x50, 0x00, 0x42,
+			0x9A, 0xFF, 0x27, 0x6B, 0x3C, 0x7A, 0x76, 0xBB, 0xED, 0x74, 0x99, 0x77, 0xEB, 0xBD, 0x56, 0x53,
+			0x79, 0x9C, 0xD4, 0x97, 0xA7, 0x16, 0x1A, 0xE7, 0xE3, 0xF9, 0xAA, 0x72, 0xEF, 0x26, 0xDD, 0xBE,
+			0x81, 0xC3, 0xF2, 0xFD, 0xD2, 0xA6, 0x0C, 0x95, 0xDA, 0x1F, 0xF1, 0x9A, 0xC5, 0x3D, 0x8B, 0x67,
+			0x08, 0x13, 0x59, 0xDE, 0x05, 0x96, 0x6F, 0xE0, 0x7C, 0x0F, 0xF9, 0x23, 0xD4, 0x63, 0xD0, 0x7E,
+			0x03, 0xF5, 0x40, 0x1C, 0x9F, 0x58, 0x3E, 0x39, 0xC8, 0x03, 0xB7, 0x85, 0x76, 0x22, 0x9C, 0x9F,
+			0x69, 0x7B, 0xA0, 0xFE, 0x44, 0x4F, 0x11, 0xF2, 0x07, 0xC8, 0x93, 0x69, 0xFB, 0xE9, 0xB1, 0xD3,
+			0x5F, 0x42, 0xF9, 0x21, 0x4F, 0x80, 0xFE, 0x0D, 0xD6, 0x0D, 0xD6, 0xC7, 0xC1, 0xEB, 0x32, 0xC8,
+			0x13, 0xA0, 0x1E, 0x07, 0xEB, 0x66, 0xF4, 0x19, 0xD2, 0xE7, 0x06, 0x7D, 0xD6, 0x70, 0xBE, 0xC1,
+			0xFA, 0xC7, 0x3F, 0xF9, 0x0F, 0x7B, 0xB9, 0x9A, 0x2C,
+		];
+		assert_eq!(zlib_decompress(&compressed, text.len()).unwrap(), text);
+		assert!(zlib_decompress(&compressed, text.len() - 1).is_err());
+		let mut corrupt = compressed;
+		corrupt[compressed.len() - 1] ^= 1;
+		assert!(zlib_decompress(&corrupt, text.len()).is_err());
+
+		// a stored block then a block with the fixed codes
+		assert_eq!(inflate(&[0x00, 0x02, 0x00, 0xFD, 0xFF, b'h', b'i', 0x03, 0x00], 16).unwrap(), b"hi");
+		for data in [Vec::new(), text.clone(), (0..5000u32).map(|i|(i * 7919 % 251) as u8).collect(), vec![0; 70000]]{
+			let compressed = zlib_compress(&data);
+			assert_eq!(zlib_decompress(&compressed, data.len()).unwrap(), data);
+		}
+		assert!(zlib_compress(&text).len() < text.len() / 4);
+	}
+	#[test]
+	fn test_woff() {
+		use std::io::Cursor;
+		use crate::builder::FontBuilder;
+		use crate::core::{OTTF, SFNTVer, Table};
+		use crate::woff::{WoffFont, WoffHeader};
+		let mut builder = FontBuilder::new(SFNTVer::TrueType);
+		builder.add_raw_table("head", head_bytes());
+		builder.add_raw_table("glyf", (0..3000u32).map(|i|(i % 10) as u8).collect());
+		builder.add_raw_table("post", vec![0, 3, 0, 0, 1]);
+		let sfnt = builder.build().unwrap();
+		let metadata = b"<?xml version=\"1.0\"?><metadata version=\"1.0\"></metadata>".to_vec();
+		let woff = WoffFont::from_sfnt(sfnt.clone(), Some(metadata.clone()), Some(vec![1, 2, 3])).unwrap();
+		assert_eq!((woff.major_version, woff.minor_version), (2, 0x8000));
+		let encoded = woff.encode().unwrap();
+		assert!(encoded.len() < sfnt.len() / 2);
+
+		let mut f = Cursor::new(encoded.clone());
+		let header = WoffHeader::from_file(&mut f).unwrap();
+		assert_eq!(&encoded[..4], b"wOFF");
+		assert_eq!((header.length as usize, header.num_tables, header.total_sfnt_size as usize), (encoded.len(), 3, sfnt.len()));
+		assert_eq!((header.meta_offset % 4, header.meta_orig_length as usize), (0, metadata.len()));
+		assert_eq!((header.priv_offset % 4, header.priv_length), (0, 3));
+
+		let mut f = Cursor::new(encoded.clone());
+		let decoded = WoffFont::from_file(&mut f).unwrap();
+		assert_eq!(decoded.data, sfnt);
+		assert_eq!(decoded.metadata, Some(metadata));
+		assert_eq!(decoded.private_data, Some(vec![1, 2, 3]));
+		let Ok(Some(Table::Head(head))) = decoded.font.load_table(&mut decoded.reader(), "head") else {panic!()};
+		assert_eq!(head.units_per_em, 2048);
+
+		// blocks past the length in the header or the end of the file
+		for (offset, value) in [(8, 100), (44 + 8, 0xFFFF_FFF0)]{
+			let mut corrupt = encoded.clone();
+			corrupt[offset..offset + 4].copy_from_slice(&u32::to_be_bytes(value));
+			assert!(WoffFont::from_file(&mut Cursor::new(corrupt)).is_err());
+		}
+		let mut corrupt = encoded.clone();
+		corrupt[8..12].copy_from_slice(&u32::MAX.to_be_bytes());
+		corrupt[28..32].copy_from_slice(&0x7FFF_FFFFu32.to_be_bytes());
+		assert!(WoffFont::from_file(&mut Cursor::new(corrupt)).is_err());
+		// a total size too small for the tables or over the maximum, a repeated tag and a wrong checksum
+		for (offset, value) in [(16, sfnt.len() as u32 - 4), (16, u32::MAX), (44 + 20, u32::from_be_bytes(*b"glyf")), (44 + 16, 0)]{
+			let mut corrupt = encoded.clone();
+			corrupt[offset..offset + 4].copy_from_slice(&u32::to_be_bytes(value));
+			assert!(WoffFont::from_file(&mut Cursor::new(corrupt)).is_err());
+		}
+
+		// a plain font isn't a WOFF file
+		assert!(WoffFont::from_file(&mut Cursor::new(sfnt.clone())).is_err());
+		assert!(OTTF::from_file(&mut Cursor::new(sfnt)).is_ok());
+	}
+	///A Brotli stream holding `data` in a single uncompressed meta-block
+	fn brotli_stored(data: &[u8])->Vec<u8>{
+		let mut rv = (((data.len() as u32 - 1) << 4) | 1 << 20).to_le_bytes()[..3].to_vec();
+		rv.extend_from_slice(data);
+		// an empty last meta-block
+		rv.push(0x03);
+		rv
+	}
+	#[test]
+	fn test_brotli() {
+		use crate::brotli::decompress;
+		let text = b"The quick brown fox jumps over the lazy dog. The Quick Brown Foxes jumped over the lazy dogs, and the fox was quick.";
+		// compressed at quality 11, using the dictionary and its transforms
+		let compressed = [
+			0x1B, 0x73, 0x00, 0x88, 0x1C, 0x09, 0x76, 0xEC, 0xC8, 0x3A, 0x87, 0x8C, 0x1E, 0xA9, 0xA8, 0xD0,
+			0xA0, 0x64, 0x47, 0x7A, 0x40, 0x13, 0x08, 0xF2, 0xC0, 0xF7, 0x0B, 0xCA, 0x1E, 0xAA, 0x2A, 0xEC,
+			0xCD, 0x0D, 0x4E, 0x03, 0x0F, 0x0C, 0xB3, 0x74, 0xD8, 0x5F, 0x70, 0x90, 0x7D, 0xA5, 0xC1, 0x21,
+			0x47, 0x2D, 0xD1, 0xEC, 0x10, 0x65, 0xB5, 0x82, 0xD3, 0x13, 0x55, 0xBD, 0x34, 0xA4, 0x01, 0xBE,
+			0xED, 0x3F, 0xF2, 0x0C, 0x14, 0x23, 0xE6, 0xA2, 0xA1, 0x87, 0xEC, 0x43, 0xA9, 0xE5, 0xDD, 0x46,
+			0xBD, 0x1C, 0xA6, 0xCC, 0x01,
+		];
+		assert_eq!(decompress(&compressed, text.len()).unwrap(), text);
+		assert!(decompress(&compressed, text.len() - 1).is_err());
+		assert!(decompress(&compressed[..compressed.len() - 1], text.len()).is_err());
+		assert_eq!(decompress(&[0x3B], 0).unwrap(), b"");
+		assert_eq!(decompress(&brotli_stored(text), text.len()).unwrap(), text);
+	}
+	#[test]
+	fn test_woff2() {
+		use std::io::Cursor;
+		use crate::core::{FontCollection, Glyph, GlyphData, SimpleGlyph, Table};
+		use crate::woff2::{Packed255UInt16, UIntBase128, Woff2Content, Woff2Font};
+		assert_eq!(UIntBase128::from_file(&mut Cursor::new([0x3F])).unwrap(), UIntBase128(63));
+		assert_eq!(UIntBase128::from_file(&mut Cursor::new([0x8F, 0xFF, 0xFF, 0xFF, 0x7F])).unwrap(), UIntBase128(u32::MAX));
+		assert!(UIntBase128::from_file(&mut Cursor::new([0x80, 0x3F])).is_err());
+		assert!(UIntBase128::from_file(&mut Cursor::new([0x90, 0x80, 0x80, 0x80, 0x00])).is_err());
+		for (bytes, value) in [(&[0xFC][..], 252), (&[0xFF, 0x00], 253), (&[0xFE, 0x00], 506), (&[0xFD, 0x03, 0xE8], 1000)]{
+			assert_eq!(Packed255UInt16::from_file(&mut Cursor::new(bytes)).unwrap(), Packed255UInt16(value));
+		}
+
+		// a transformed glyf: a square with instructions, an empty glyph, a composite of the square and two contours that overlap
+		let mut glyf = be_bytes(&[0, 1, 4, 1, 0, 8, 0, 3, 0, 9, 0, 17, 0, 8, 0, 20, 0, 2]);
+		glyf.extend(be_bytes(&[1, 0, 0xFFFF, 2]));
+		glyf.extend([5, 2, 2]);
+		glyf.extend([11, 3, 3, 13, 4, 0x80 | 23, 121, 126, 97]);
+		glyf.extend([50, 0x2C, 0x90, 0xF4, 0xBC, 2, 0x99, 0xBB, 0x87, 0xD0, 0x27, 0x10, 0x01, 0x2C, 0x2B, 0xC7, 0]);
+		glyf.extend(be_bytes(&[0x0003, 0, 100, -50i16 as u16]));
+		glyf.extend([0x30, 0, 0, 0]);
+		glyf.extend(be_bytes(&[150, -50i16 as u16, 650, 650, -6990i16 as u16, -1990i16 as u16, 3010, 10]));
+		glyf.extend([0xB0, 0x01]);
+		glyf.extend([0x10]);
+		assert_eq!(glyf.len(), 104);
+		let mut stream = head_bytes();
+		stream.extend(be_bytes(&[1, 0]));
+		stream.extend(be_bytes(&[0; 15]));
+		stream.extend(be_bytes(&[2]));
+		stream.extend(be_bytes(&[0, 0x5000, 4]));
+		// advances for two glyphs and the left side bearings of the other two
+		stream.extend([0x01]);
+		stream.extend(be_bytes(&[600, 300, 77, -5i16 as u16]));
+		stream.extend(glyf);
+		stream.extend([1, 2, 3]);
+		let directory = [
+			&[1, 54][..], &[2, 36], &[4, 6], &[0x43, 12, 9], &[10, 88, 104], &[11, 20, 0], &[63, b'z', b'z', b'z', b'z', 3],
+		].concat();
+		let metadata = b"<?xml version=\"1.0\"?><metadata version=\"1.0\"></metadata>";
+		let woff2 = |flavor: &[u8], collection: &[u8]|{
+			let compressed = brotli_stored(&stream);
+			let mut data = vec![0; 48];
+			data.extend(&directory);
+			data.extend(collection);
+			data.extend(&compressed);
+			data.resize(data.len().next_multiple_of(4), 0);
+			let meta_offset = data.len() as u32;
+			let compressed_metadata = brotli_stored(metadata);
+			data.extend(&compressed_metadata);
+			data.resize(data.len().next_multiple_of(4), 0);
+			let priv_offset = data.len() as u32;
+			data.extend([9, 9]);
+			let mut header = b"wOF2".to_vec();
+			header.extend(flavor);
+			header.extend((data.len() as u32).to_be_bytes());
+			header.extend(be_bytes(&[7, 0, 0, 400]));
+			header.extend((compressed.len() as u32).to_be_bytes());
+			header.extend(be_bytes(&[1, 0]));
+			for v in [meta_offset, compressed_metadata.len() as u32, metadata.len() as u32, priv_offset, 2]{header.extend(v.to_be_bytes());}
+			data[..48].copy_from_slice(&header);
+			data
+		};
+
+		let decoded = Woff2Font::from_file(&mut Cursor::new(woff2(&[0, 1, 0, 0], &[]))).unwrap();
+		assert_eq!((decoded.major_version, decoded.minor_version), (1, 0));
+		assert_eq!(decoded.metadata.as_deref(), Some(&metadata[..]));
+		assert_eq!(decoded.private_data, Some(vec![9, 9]));
+		let Woff2Content::Font(font) = &decoded.content else {panic!()};
+		assert_eq!(font.get_table_record("glyf").unwrap().length, 88);
+		assert_eq!(font.get_table_record("loca").unwrap().length, 20);
+		assert_eq!(font.get_table_record("zzzz").unwrap().length, 3);
+		let mut f = decoded.reader();
+		let Ok(Some(Table::Head(head))) = font.load_table(&mut f, "head") else {panic!()};
+		let Ok(Some(Table::Hhea(hhea))) = font.load_table(&mut f, "hhea") else {panic!()};
+		let Ok(Some(Table::Hmtx(hmtx))) = font.load_table(&mut f, "hmtx") else {panic!()};
+		let Ok(Some(Table::Loca(loca))) = font.load_table(&mut f, "loca") else {panic!()};
+		let Ok(Some(Table::Glyf(glyf))) = font.load_table(&mut f, "glyf") else {panic!()};
+		let metrics: Vec<(u16, i16)> = (0..4).map(|i|hmtx.get_metric(&mut f, &hhea, i).map(|m|(m.advance_width, m.lsb)).unwrap()).collect();
+		assert_eq!(metrics, [(600, 50), (300, 0), (300, 77), (300, -5)]);
+
+		let Ok(Some(Glyph{header, data: GlyphData::Simple(square)})) = glyf.get_glyph(&mut f, &loca, &head, 0) else {panic!()};
+		assert_eq!((header.x_min, header.y_min, header.x_max, header.y_max), (50, 0, 550, 700));
+		assert_eq!(&*square.instructions, &[0xB0, 0x01]);
+		let points: Vec<(i16, i16)> = square.points.iter().map(|p|(p.x, p.y)).collect();
+		assert_eq!(points, [(50, 0), (50, 300), (50, 700), (550, 700), (550, 0)]);
+		assert!(glyf.get_glyph(&mut f, &loca, &head, 1).unwrap().is_none());
+		let contours = glyf.get_contours(&mut f, &loca, &head, 2).unwrap();
+		assert_eq!((contours.points[0].x, contours.points[0].y, contours.points.len()), (150.0, -50.0, 5));
+		let Ok(Some(Glyph{header, data: GlyphData::Simple(glyph)})) = glyf.get_glyph(&mut f, &loca, &head, 3) else {panic!()};
+		assert_eq!((header.number_of_contours, header.x_min, header.y_max), (2, -6990, 10));
+		assert_eq!(&*glyph.end_pts_of_contours, &[1, 3]);
+		assert_ne!(glyph.flags[0] & SimpleGlyph::OVERLAP_SIMPLE, 0);
+		let points: Vec<(i16, i16, bool)> = glyph.points.iter().map(|p|(p.x, p.y, p.on_curve)).collect();
+		assert_eq!(points, [(10, 10, false), (3010, -1990, true), (-6990, -1690, true), (-6690, -1890, true)]);
+
+		// two fonts sharing every table but the last
+		let mut collection = vec![0, 1, 0, 0, 2, 7, 0, 1, 0, 0, 0, 1, 2, 3, 4, 5, 6, 6, 0, 1, 0, 0];
+		collection.extend(0..6);
+		let decoded = Woff2Font::from_file(&mut Cursor::new(woff2(b"ttcf", &collection))).unwrap();
+		let Woff2Content::Collection(collection) = &decoded.content else {panic!()};
+		assert_eq!(decoded.fonts().len(), 2);
+		let shared: Vec<(&str, Vec<usize>)> = collection.shared_tables().iter().map(|(r, fonts)|(r.table_tag.data.as_str(), fonts.clone())).collect();
+		assert!(shared.contains(&("glyf", vec![0, 1])));
+		assert_eq!(shared.len(), 6);
+		assert!(decoded.fonts()[0].get_table_record("zzzz").is_some() && decoded.fonts()[1].get_table_record("zzzz").is_none());
+		let mut f = decoded.reader();
+		assert!(FontCollection::from_file(&mut f).is_ok());
+		let font = &decoded.fonts()[1];
+		let Ok(Some(Table::Head(head))) = font.load_table(&mut f, "head") else {panic!()};
+		let Ok(Some(Table::Loca(loca))) = font.load_table(&mut f, "loca") else {panic!()};
+		let Ok(Some(Table::Glyf(glyf))) = font.load_table(&mut f, "glyf") else {panic!()};
+		assert_eq!(glyf.get_contours(&mut f, &loca, &head, 3).unwrap().points.len(), 4);
+
+		// a WOFF 1.0 signature
+		let mut data = woff2(&[0, 1, 0, 0], &[]);
+		data[3] = b'F';
+		assert!(Woff2Font::from_file(&mut Cursor::new(data)).is_err());
+		// a glyf table that doesn't rebuild to its original length and a compressed stream past the end of the file
+		let mut data = woff2(&[0, 1, 0, 0], &[]);
+		assert_eq!(data[58], 88);
+		data[58] = 92;
+		assert!(Woff2Font::from_file(&mut Cursor::new(data)).is_err());
+		let mut data = woff2(&[0, 1, 0, 0], &[]);
+		data[20..24].copy_from_slice(&0x7FFF_FFFFu32.to_be_bytes());
+		assert!(Woff2Font::from_file(&mut Cursor::new(data)).is_err());
+		// stored tables that don't fit in the total size or a total size over the maximum
+		for total_sfnt_size in [211, u32::MAX]{
+			let mut data = woff2(&[0, 1, 0, 0], &[]);
+			data[16..20].copy_from_slice(&total_sfnt_size.to_be_bytes());
+			assert!(Woff2Font::from_file(&mut Cursor::new(data)).is_err());
+		}
+		let mut data = woff2(&[0, 1, 0, 0], &[]);
+		data[16..20].copy_from_slice(&212u32.to_be_bytes());
+		assert!(Woff2Font::from_file(&mut Cursor::new(data)).is_ok());
+	}
 	#[test]
 	fn test_char_sets() {
 		println!("{:?}",CharSetStr::<CodePage437>::from_bytes(&[1u8,2u8,3u8]));
@@ -0,0 +1,171 @@
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Point{
+	pub x: f32,
+	pub y: f32,
+}
+impl Point{
+	pub fn new(x: f32, y: f32)->Self{Self{x, y}}
+	pub fn lerp(&self, other: Point, t: f32)->Point{Point{
+		x: self.x + (other.x - self.x) * t,
+		y: self.y + (other.y - self.y) * t,
+	}}
+}
+
+///A point of a TrueType style contour where off curve points are quadratic control points
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContourPoint{
+	pub x: f32,
+	pub y: f32,
+	pub on_curve: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathCommand{
+	MoveTo(Point),
+	LineTo(Point),
+	///control point then end point
+	QuadTo(Point, Point),
+	///two control points then end point
+	CurveTo(Point, Point, Point),
+	Close,
+}
+
+///An affine transform where `x' = xx*x + yx*y + dx` and `y' = xy*x + yy*y + dy`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform{
+	pub xx: f32,
+	pub xy: f32,
+	pub yx: f32,
+	pub yy: f32,
+	pub dx: f32,
+	pub dy: f32,
+}
+impl Default for Transform{fn default()->Self{Self::IDENTITY}}
+impl Transform{
+	pub const IDENTITY: Self = Self{xx: 1.0, xy: 0.0, yx: 0.0, yy: 1.0, dx: 0.0, dy: 0.0};
+	pub fn scale(sx: f32, sy: f32)->Self{Self{xx: sx, yy: sy, ..Self::IDENTITY}}
+	pub fn translate(dx: f32, dy: f32)->Self{Self{dx, dy, ..Self::IDENTITY}}
+	pub fn apply(&self, p: Point)->Point{Point{
+		x: self.xx * p.x + self.yx * p.y + self.dx,
+		y: self.xy * p.x + self.yy * p.y + self.dy,
+	}}
+	///the transform that applies `self` and then `other`
+	pub fn then(&self, other: &Transform)->Transform{Transform{
+		xx: other.xx * self.xx + other.yx * self.xy,
+		xy: other.xy * self.xx + other.yy * self.xy,
+		yx: other.xx * self.yx + other.yx * self.yy,
+		yy: other.xy * self.yx + other.yy * self.yy,
+		dx: other.xx * self.dx + other.yx * self.dy + other.dx,
+		dy: other.xy * self.dx + other.yy * self.dy + other.dy,
+	}}
+}
+
+///Receives the commands of an outline one at a time
+pub trait OutlineSink{
+	fn move_to(&mut self, p: Point);
+	fn line_to(&mut self, p: Point);
+	///control point then end point
+	fn quad_to(&mut self, c: Point, p: Point);
+	///two control points then end point
+	fn curve_to(&mut self, c0: Point, c1: Point, p: Point);
+	fn close(&mut self);
+}
+
+///A glyph outline in font units made up of one or more closed contours
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Outline{
+	pub commands: Vec<PathCommand>,
+}
+impl Outline{
+	pub fn new()->Self{Self{commands: Vec::new()}}
+	pub fn is_empty(&self)->bool{self.commands.is_empty()}
+	pub fn move_to(&mut self, p: Point){self.commands.push(PathCommand::MoveTo(p))}
+	pub fn line_to(&mut self, p: Point){self.commands.push(PathCommand::LineTo(p))}
+	pub fn quad_to(&mut self, c: Point, p: Point){self.commands.push(PathCommand::QuadTo(c, p))}
+	pub fn curve_to(&mut self, c0: Point, c1: Point, p: Point){self.commands.push(PathCommand::CurveTo(c0, c1, p))}
+	pub fn close(&mut self){self.commands.push(PathCommand::Close)}
+
+	///Builds an outline from TrueType contours. Consecutive off curve points have an implied on curve point between them.
+	pub fn from_contours(points: &[ContourPoint], end_pts_of_contours: &[u16])->Self{
+		let mut rv = Self::new();
+		let mut start = 0usize;
+		for end in end_pts_of_contours{
+			let end = *end as usize;
+			if end < start || end >= points.len(){break;}
+			rv.add_quadratic_contour(&points[start..=end]);
+			start = end + 1;
+		}
+		rv
+	}
+	fn add_quadratic_contour(&mut self, contour: &[ContourPoint]){
+		let pt = |p: &ContourPoint|Point::new(p.x, p.y);
+		// find somewhere on the curve to start, if there are no on curve points start between the first two
+		let first_on = contour.iter().position(|p|p.on_curve);
+		let (start, first) = match first_on{
+			Some(i) => (pt(&contour[i]), i),
+			None => (pt(&contour[0]).lerp(pt(&contour[contour.len() - 1]), 0.5), contour.len() - 1),
+		};
+		self.move_to(start);
+		let mut control: Option<Point> = None;
+		for i in 1..=contour.len(){
+			let p = &contour[(first + i) % contour.len()];
+			match (p.on_curve, control){
+				(true, None) => self.line_to(pt(p)),
+				(true, Some(c)) => {self.quad_to(c, pt(p)); control = None;},
+				(false, None) => control = Some(pt(p)),
+				(false, Some(c)) => {
+					self.quad_to(c, c.lerp(pt(p), 0.5));
+					control = Some(pt(p));
+				},
+			}
+		}
+		if let Some(c) = control{self.quad_to(c, start);}
+		self.close();
+	}
+
+	pub fn transform(&mut self, t: &Transform){
+		for c in self.commands.iter_mut(){match c{
+			PathCommand::MoveTo(p) | PathCommand::LineTo(p) => *p = t.apply(*p),
+			PathCommand::QuadTo(c, p) => {*c = t.apply(*c); *p = t.apply(*p);},
+			PathCommand::CurveTo(c0, c1, p) => {*c0 = t.apply(*c0); *c1 = t.apply(*c1); *p = t.apply(*p);},
+			PathCommand::Close => {},
+		}}
+	}
+	pub fn extend(&mut self, other: &Outline){self.commands.extend_from_slice(&other.commands)}
+	///Sends every command to `sink`
+	pub fn emit<S: OutlineSink + ?Sized>(&self, sink: &mut S){
+		for c in self.commands.iter(){match *c{
+			PathCommand::MoveTo(p) => sink.move_to(p),
+			PathCommand::LineTo(p) => sink.line_to(p),
+			PathCommand::QuadTo(c, p) => sink.quad_to(c, p),
+			PathCommand::CurveTo(c0, c1, p) => sink.curve_to(c0, c1, p),
+			PathCommand::Close => sink.close(),
+		}}
+	}
+
+	///The bounding box of all points (including control points) as (min, max)
+	pub fn control_bounds(&self)->Option<(Point, Point)>{
+		let mut rv: Option<(Point, Point)> = None;
+		let mut add = |p: &Point|{rv = Some(match rv{
+			None => (*p, *p),
+			Some((min, max)) => (
+				Point::new(min.x.min(p.x), min.y.min(p.y)),
+				Point::new(max.x.max(p.x), max.y.max(p.y)),
+			),
+		})};
+		for c in self.commands.iter(){match c{
+			PathCommand::MoveTo(p) | PathCommand::LineTo(p) => add(p),
+			PathCommand::QuadTo(c, p) => {add(c); add(p);},
+			PathCommand::CurveTo(c0, c1, p) => {add(c0); add(c1); add(p);},
+			PathCommand::Close => {},
+		}}
+		rv
+	}
+}
+impl OutlineSink for Outline{
+	fn move_to(&mut self, p: Point){Outline::move_to(self, p)}
+	fn line_to(&mut self, p: Point){Outline::line_to(self, p)}
+	fn quad_to(&mut self, c: Point, p: Point){Outline::quad_to(self, c, p)}
+	fn curve_to(&mut self, c0: Point, c1: Point, p: Point){Outline::curve_to(self, c0, c1, p)}
+	fn close(&mut self){Outline::close(self)}
+}
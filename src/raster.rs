@@ -0,0 +1,186 @@
+use crate::core::HeadTable;
+use crate::outline::{Outline, PathCommand, Point};
+
+///An 8-bit coverage bitmap of a rendered glyph
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bitmap{
+	pub width: u32,
+	pub height: u32,
+	///Distance in pixels from the glyph origin to the left edge of the bitmap
+	pub left: i32,
+	///Distance in pixels from the baseline up to the top edge of the bitmap
+	pub top: i32,
+	///Coverage of each pixel from 0 (empty) to 255 (covered) in rows from the top
+	pub data: Vec<u8>,
+}
+impl Bitmap{
+	///The most pixels a glyph is rendered into, larger glyphs give an empty bitmap
+	pub const MAX_PIXELS: usize = 1 << 24;
+	pub fn get(&self, x: u32, y: u32)->Option<u8>{
+		if x >= self.width || y >= self.height{return None;}
+		Some(self.data[(y * self.width + x) as usize])
+	}
+}
+
+///The scale from font units to pixels for an em of `pixel_size` pixels
+pub fn scale_for_pixel_size(head: &HeadTable, pixel_size: f32)->f32{pixel_size / head.units_per_em.max(1) as f32}
+
+///Renders `outline` with an em of `pixel_size` pixels, `offset` is the subpixel position of the glyph origin
+pub fn rasterize_glyph(outline: &Outline, head: &HeadTable, pixel_size: f32, offset: Point)->Bitmap{
+	rasterize(outline, scale_for_pixel_size(head, pixel_size), offset)
+}
+
+///Renders `outline` (in font units) scaled by `scale` with its origin moved by `offset` pixels.
+///The coverage of each pixel is the exact area of it inside the outline using the non-zero winding rule (overlaps are clamped).
+pub fn rasterize(outline: &Outline, scale: f32, offset: Point)->Bitmap{
+	let (min, max) = match outline.control_bounds(){
+		Some(b) => b,
+		None => return Bitmap::default(),
+	};
+	let left = (min.x * scale + offset.x).floor() as i32;
+	let right = (max.x * scale + offset.x).ceil() as i32;
+	let bottom = (min.y * scale + offset.y).floor() as i32;
+	let top = (max.y * scale + offset.y).ceil() as i32;
+	let (width, height) = match (right.checked_sub(left), top.checked_sub(bottom)){
+		(Some(w), Some(h)) => (w.max(0) as usize, h.max(0) as usize),
+		_ => return Bitmap::default(),
+	};
+	if width.saturating_mul(height) > Bitmap::MAX_PIXELS{return Bitmap::default();}
+	let mut rasterizer = Rasterizer::new(width, height);
+	// to pixel coordinates where y goes down from the top of the bitmap
+	let to_pixel = |p: &Point|Point::new(p.x * scale + offset.x - left as f32, top as f32 - (p.y * scale + offset.y));
+	let mut start = Point::default();
+	let mut current = Point::default();
+	for command in outline.commands.iter(){match command{
+		PathCommand::MoveTo(p) => {
+			rasterizer.line(current, start);
+			start = to_pixel(p);
+			current = start;
+		},
+		PathCommand::LineTo(p) => {
+			let p = to_pixel(p);
+			rasterizer.line(current, p);
+			current = p;
+		},
+		PathCommand::QuadTo(c, p) => {
+			let (c, p) = (to_pixel(c), to_pixel(p));
+			rasterizer.quad(current, c, p);
+			current = p;
+		},
+		PathCommand::CurveTo(c0, c1, p) => {
+			let (c0, c1, p) = (to_pixel(c0), to_pixel(c1), to_pixel(p));
+			rasterizer.cubic(current, c0, c1, p);
+			current = p;
+		},
+		PathCommand::Close => {
+			rasterizer.line(current, start);
+			current = start;
+		},
+	}}
+	rasterizer.line(current, start);
+	Bitmap{width: width as u32, height: height as u32, left, top, data: rasterizer.coverage()}
+}
+
+///Accumulates the signed area each line covers in each pixel, summing a row from the left gives the coverage
+struct Rasterizer{
+	width: usize,
+	height: usize,
+	///Each row has two extra cells for area to the right of the last pixel
+	stride: usize,
+	accumulation: Vec<f32>,
+}
+impl Rasterizer{
+	///The most lines a curve is split into
+	const MAX_SEGMENTS: usize = 128;
+	///The furthest in pixels a flattened curve may stray from the real curve
+	const TOLERANCE: f32 = 0.025;
+
+	fn new(width: usize, height: usize)->Self{Self{
+		width,
+		height,
+		stride: width + 2,
+		accumulation: vec![0.0; (width + 2) * height],
+	}}
+	fn line(&mut self, p0: Point, p1: Point){
+		if p0.y == p1.y || !p0.y.is_finite() || !p1.y.is_finite(){return;}
+		let (direction, p0, p1) = if p0.y < p1.y{(1.0, p0, p1)}else{(-1.0, p1, p0)};
+		let dxdy = (p1.x - p0.x) / (p1.y - p0.y);
+		let max_x = self.width as f32;
+		let mut x = p0.x;
+		if p0.y < 0.0{x -= p0.y * dxdy;}
+		let y_end = (p1.y.ceil().max(0.0) as usize).min(self.height);
+		for y in (p0.y.max(0.0) as usize)..y_end{
+			let row = y * self.stride;
+			let dy = (y as f32 + 1.0).min(p1.y) - (y as f32).max(p0.y);
+			let x_next = x + dxdy * dy;
+			let d = dy * direction;
+			let (x0, x1) = if x < x_next{(x, x_next)}else{(x_next, x)};
+			let (x0, x1) = (x0.clamp(0.0, max_x), x1.clamp(0.0, max_x));
+			let x0_floor = x0.floor();
+			let x0_index = x0_floor as usize;
+			let x1_ceil = x1.ceil();
+			let x1_index = x1_ceil as usize;
+			if x1_index <= x0_index + 1{
+				// the line stays within one pixel of this row
+				let x_mid = 0.5 * (x0 + x1) - x0_floor;
+				self.accumulation[row + x0_index] += d - d * x_mid;
+				self.accumulation[row + x0_index + 1] += d * x_mid;
+			}else{
+				let s = (x1 - x0).recip();
+				let x0_fraction = x0 - x0_floor;
+				let a0 = 0.5 * s * (1.0 - x0_fraction) * (1.0 - x0_fraction);
+				let x1_fraction = x1 - x1_ceil + 1.0;
+				let a_end = 0.5 * s * x1_fraction * x1_fraction;
+				self.accumulation[row + x0_index] += d * a0;
+				if x1_index == x0_index + 2{
+					self.accumulation[row + x0_index + 1] += d * (1.0 - a0 - a_end);
+				}else{
+					let a1 = s * (1.5 - x0_fraction);
+					self.accumulation[row + x0_index + 1] += d * (a1 - a0);
+					for cell in self.accumulation[row + x0_index + 2..row + x1_index - 1].iter_mut(){*cell += d * s;}
+					let a2 = a1 + (x1_index - x0_index - 3) as f32 * s;
+					self.accumulation[row + x1_index - 1] += d * (1.0 - a2 - a_end);
+				}
+				self.accumulation[row + x1_index] += d * a_end;
+			}
+			x = x_next;
+		}
+	}
+	///The number of lines to split a curve into given the largest second difference of its points, a line is at most `deviation / (8 * n^2)` from the curve
+	fn segments(deviation: f32)->usize{(1 + (deviation / (8.0 * Self::TOLERANCE)).sqrt() as usize).min(Self::MAX_SEGMENTS)}
+	fn quad(&mut self, p0: Point, c: Point, p1: Point){
+		let deviation = (p0.x - 2.0 * c.x + p1.x).hypot(p0.y - 2.0 * c.y + p1.y);
+		let n = Self::segments(deviation);
+		let mut previous = p0;
+		for i in 1..=n{
+			let t = i as f32 / n as f32;
+			let p = p0.lerp(c, t).lerp(c.lerp(p1, t), t);
+			self.line(previous, p);
+			previous = p;
+		}
+	}
+	fn cubic(&mut self, p0: Point, c0: Point, c1: Point, p1: Point){
+		let deviation = (p0.x - 2.0 * c0.x + c1.x).hypot(p0.y - 2.0 * c0.y + c1.y)
+			.max((c0.x - 2.0 * c1.x + p1.x).hypot(c0.y - 2.0 * c1.y + p1.y));
+		let n = Self::segments(deviation * 2.0);
+		let mut previous = p0;
+		for i in 1..=n{
+			let t = i as f32 / n as f32;
+			let (a, b, c) = (p0.lerp(c0, t), c0.lerp(c1, t), c1.lerp(p1, t));
+			let p = a.lerp(b, t).lerp(b.lerp(c, t), t);
+			self.line(previous, p);
+			previous = p;
+		}
+	}
+	fn coverage(&self)->Vec<u8>{
+		let mut rv = Vec::with_capacity(self.width * self.height);
+		for row in self.accumulation.chunks_exact(self.stride){
+			let mut sum = 0.0f32;
+			for cell in row[..self.width].iter(){
+				sum += cell;
+				rv.push((sum.abs().min(1.0) * 255.0 + 0.5) as u8);
+			}
+		}
+		rv
+	}
+}
@@ -0,0 +1,418 @@
+use crate::core::HeadTable;
+use crate::outline::{Outline, PathCommand, Point};
+use crate::raster::scale_for_pixel_size;
+
+///How a distance field is generated from an outline
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SdfSettings{
+	///Pixels per font unit
+	pub scale: f32,
+	///The distance in pixels covered by the values, half of it either side of the edge
+	pub range: f32,
+	///Pixels added around the bounds of the outline
+	pub padding: u32,
+}
+impl SdfSettings{
+	///Settings for an em of `pixel_size` pixels
+	pub fn for_pixel_size(head: &HeadTable, pixel_size: f32, range: f32, padding: u32)->Self{
+		Self{scale: scale_for_pixel_size(head, pixel_size), range, padding}
+	}
+}
+
+///A signed distance field where a value of 128 is on the edge and higher values are inside the outline
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistanceField{
+	pub width: u32,
+	pub height: u32,
+	///Bytes per pixel: 1 for an SDF, 3 (red, green, blue) for an MSDF where the median of the channels is the distance
+	pub channels: u32,
+	///Distance in pixels from the glyph origin to the left edge of the field
+	pub left: i32,
+	///Distance in pixels from the baseline up to the top edge of the field
+	pub top: i32,
+	///The distance in pixels covered by the values, half of it either side of the edge
+	pub range: f32,
+	///The values of each pixel in rows from the top
+	pub data: Vec<u8>,
+}
+impl DistanceField{
+	///The most pixels a field is generated with, every pixel is measured against every edge so larger outlines give an empty field
+	pub const MAX_PIXELS: u64 = 1 << 22;
+	pub fn get(&self, x: u32, y: u32, channel: u32)->Option<u8>{
+		if x >= self.width || y >= self.height || channel >= self.channels{return None;}
+		Some(self.data[((y * self.width + x) * self.channels + channel) as usize])
+	}
+}
+
+///Generates a single channel signed distance field of `outline`
+pub fn generate_sdf(outline: &Outline, settings: &SdfSettings)->DistanceField{
+	generate(outline, settings, false)
+}
+///Generates a multi-channel signed distance field of `outline` which keeps corners sharp when magnified
+pub fn generate_msdf(outline: &Outline, settings: &SdfSettings)->DistanceField{
+	generate(outline, settings, true)
+}
+
+const RED: u8 = 1;
+const GREEN: u8 = 2;
+const BLUE: u8 = 4;
+const WHITE: u8 = RED | GREEN | BLUE;
+const CYAN: u8 = GREEN | BLUE;
+const MAGENTA: u8 = RED | BLUE;
+const YELLOW: u8 = RED | GREEN;
+
+///The furthest in pixels a flattened curve may stray from the real curve
+const TOLERANCE: f32 = 0.01;
+///The most lines a curve is split into
+const MAX_SEGMENTS: usize = 256;
+///Edges meeting at an angle sharper than about 3 radians form a corner
+const CORNER_CROSS_THRESHOLD: f32 = 0.14112;
+
+///A straight piece of a flattened edge in pixel coordinates
+#[derive(Debug, Clone, Copy)]
+struct Segment{
+	a: Point,
+	b: Point,
+	color: u8,
+	///Whether this segment starts or ends the edge it came from, distances past those ends are measured to the extended line
+	first: bool,
+	last: bool,
+}
+impl Segment{
+	///The squared distance to the segment, how orthogonal the closest approach is (smaller is more orthogonal) and the position along it
+	fn distance(&self, p: Point)->(f32, f32, f32){
+		let (dx, dy) = (self.b.x - self.a.x, self.b.y - self.a.y);
+		let length_squared = dx * dx + dy * dy;
+		let t = if length_squared > 0.0{((p.x - self.a.x) * dx + (p.y - self.a.y) * dy) / length_squared}else{0.0};
+		let closest = self.a.lerp(self.b, t.clamp(0.0, 1.0));
+		let (vx, vy) = (p.x - closest.x, p.y - closest.y);
+		let distance_squared = vx * vx + vy * vy;
+		let ortho = if distance_squared > 0.0 && length_squared > 0.0
+		{((vx * dx + vy * dy) / (distance_squared * length_squared).sqrt()).abs()}else{0.0};
+		(distance_squared, ortho, t)
+	}
+	///Which side of the segment `p` is on: positive to the left
+	fn side(&self, p: Point)->f32{
+		(self.b.x - self.a.x) * (p.y - self.a.y) - (self.b.y - self.a.y) * (p.x - self.a.x)
+	}
+	///The distance to the segment, or to its line past the ends of the edge, signed by `side`
+	fn pseudo_distance(&self, p: Point, t: f32, distance_squared: f32)->f32{
+		let length = (self.b.x - self.a.x).hypot(self.b.y - self.a.y);
+		let distance = if ((t < 0.0 && self.first) || (t > 1.0 && self.last)) && length > 0.0
+		{(self.side(p) / length).abs()}else{distance_squared.sqrt()};
+		if self.side(p) >= 0.0{distance}else{-distance}
+	}
+}
+
+///An edge of a contour flattened into lines with the direction it starts and ends in
+struct Edge{
+	points: Vec<Point>,
+}
+impl Edge{
+	fn start_direction(&self)->Point{
+		let start = self.points[0];
+		direction(start, *self.points.iter().find(|p|**p != start).unwrap_or(&start))
+	}
+	fn end_direction(&self)->Point{
+		let end = self.points[self.points.len() - 1];
+		direction(*self.points.iter().rev().find(|p|**p != end).unwrap_or(&end), end)
+	}
+}
+fn direction(a: Point, b: Point)->Point{
+	let length = (b.x - a.x).hypot(b.y - a.y);
+	if length > 0.0{Point::new((b.x - a.x) / length, (b.y - a.y) / length)}else{Point::default()}
+}
+fn subdivisions(deviation: f32)->usize{(1 + (deviation / (8.0 * TOLERANCE)).sqrt() as usize).min(MAX_SEGMENTS)}
+
+///Splits an outline into contours of flattened edges
+fn edges(outline: &Outline, to_pixel: impl Fn(&Point)->Point)->Vec<Vec<Edge>>{
+	let mut contours: Vec<Vec<Edge>> = Vec::new();
+	let mut start = Point::default();
+	let mut current = Point::default();
+	let mut contour: Vec<Edge> = Vec::new();
+	let close = |contour: &mut Vec<Edge>, contours: &mut Vec<Vec<Edge>>, current: Point, start: Point|{
+		if current != start{contour.push(Edge{points: vec![current, start]});}
+		if !contour.is_empty(){contours.push(std::mem::take(contour));}
+	};
+	for command in outline.commands.iter(){
+		let points = match command{
+			PathCommand::MoveTo(p) => {
+				close(&mut contour, &mut contours, current, start);
+				start = to_pixel(p);
+				current = start;
+				continue;
+			},
+			PathCommand::Close => {
+				close(&mut contour, &mut contours, current, start);
+				current = start;
+				continue;
+			},
+			PathCommand::LineTo(p) => vec![current, to_pixel(p)],
+			PathCommand::QuadTo(c, p) => {
+				let (c, p) = (to_pixel(c), to_pixel(p));
+				let n = subdivisions((current.x - 2.0 * c.x + p.x).hypot(current.y - 2.0 * c.y + p.y));
+				(0..=n).map(|i|{
+					let t = i as f32 / n as f32;
+					current.lerp(c, t).lerp(c.lerp(p, t), t)
+				}).collect()
+			},
+			PathCommand::CurveTo(c0, c1, p) => {
+				let (c0, c1, p) = (to_pixel(c0), to_pixel(c1), to_pixel(p));
+				let deviation = (current.x - 2.0 * c0.x + c1.x).hypot(current.y - 2.0 * c0.y + c1.y)
+					.max((c0.x - 2.0 * c1.x + p.x).hypot(c0.y - 2.0 * c1.y + p.y));
+				let n = subdivisions(deviation * 2.0);
+				(0..=n).map(|i|{
+					let t = i as f32 / n as f32;
+					let (a, b, c) = (current.lerp(c0, t), c0.lerp(c1, t), c1.lerp(p, t));
+					a.lerp(b, t).lerp(b.lerp(c, t), t)
+				}).collect()
+			},
+		};
+		current = points[points.len() - 1];
+		// edges with no length have no direction to measure from
+		if points.windows(2).any(|w|w[0] != w[1]){contour.push(Edge{points});}
+	}
+	close(&mut contour, &mut contours, current, start);
+	contours
+}
+
+///Colors the edges of a contour so that the two edges meeting at each corner share only one channel
+fn color_edges(contour: &[Edge])->Vec<u8>{
+	let corners: Vec<usize> = (0..contour.len()).filter(|i|{
+		let previous = contour[(i + contour.len() - 1) % contour.len()].end_direction();
+		let next = contour[*i].start_direction();
+		let dot = previous.x * next.x + previous.y * next.y;
+		let cross = previous.x * next.y - previous.y * next.x;
+		dot <= 0.0 || cross.abs() > CORNER_CROSS_THRESHOLD
+	}).collect();
+	let mut colors = vec![WHITE; contour.len()];
+	if corners.len() < 2{return colors;}
+	let mut color = CYAN;
+	for (k, corner) in corners.iter().enumerate(){
+		// alternate between two colors, an odd number of runs ends with a third so it differs from the first
+		color = if k == 0{CYAN}else if k == corners.len() - 1 && k % 2 == 0{YELLOW}else if color == CYAN{MAGENTA}else{CYAN};
+		let end = corners[(k + 1) % corners.len()];
+		let mut i = *corner;
+		loop{
+			colors[i] = color;
+			i = (i + 1) % contour.len();
+			if i == end{break;}
+		}
+	}
+	colors
+}
+
+fn generate(outline: &Outline, settings: &SdfSettings, multi_channel: bool)->DistanceField{
+	let channels = if multi_channel{3}else{1};
+	let empty = DistanceField{width: 0, height: 0, channels, left: 0, top: 0, range: settings.range, data: Vec::new()};
+	let (min, max) = match outline.control_bounds(){
+		Some(b) => b,
+		None => return empty,
+	};
+	let scale = settings.scale;
+	let padding = match i32::try_from(settings.padding){
+		Ok(p) => p,
+		Err(_) => return empty,
+	};
+	let (left, right, bottom, top) = match (
+		((min.x * scale).floor() as i32).checked_sub(padding),
+		((max.x * scale).ceil() as i32).checked_add(padding),
+		((min.y * scale).floor() as i32).checked_sub(padding),
+		((max.y * scale).ceil() as i32).checked_add(padding),
+	){
+		(Some(l), Some(r), Some(b), Some(t)) => (l, r, b, t),
+		_ => return empty,
+	};
+	let (width, height) = match (right.checked_sub(left), top.checked_sub(bottom)){
+		(Some(w), Some(h)) => (w.max(0) as u32, h.max(0) as u32),
+		_ => return empty,
+	};
+	if width as u64 * height as u64 > DistanceField::MAX_PIXELS{return empty;}
+	// pixel coordinates where y goes down from the top of the field
+	let contours = edges(outline, |p|Point::new(p.x * scale - left as f32, top as f32 - p.y * scale));
+	let mut segments = Vec::new();
+	let mut area = 0.0;
+	for contour in contours.iter(){
+		let colors = if multi_channel{color_edges(contour)}else{vec![WHITE; contour.len()]};
+		for (edge, color) in contour.iter().zip(colors){
+			let n = edge.points.len() - 1;
+			for (i, w) in edge.points.windows(2).enumerate(){
+				area += w[0].x * w[1].y - w[1].x * w[0].y;
+				segments.push(Segment{a: w[0], b: w[1], color, first: i == 0, last: i == n - 1});
+			}
+		}
+	}
+	// the inside of the outline is to the left of its edges if it winds this way
+	let orientation = if area >= 0.0{1.0}else{-1.0};
+	let to_value = |distance: f32|((distance / settings.range + 0.5).clamp(0.0, 1.0) * 255.0).round() as u8;
+	let mut data = Vec::with_capacity(width as usize * height as usize * channels as usize);
+	for y in 0..height{
+		for x in 0..width{
+			let p = Point::new(x as f32 + 0.5, y as f32 + 0.5);
+			if multi_channel{
+				for channel in [RED, GREEN, BLUE]{
+					let mut best: Option<(f32, f32, f32, &Segment)> = None;
+					for segment in segments.iter().filter(|s|s.color & channel != 0){
+						let (distance, ortho, t) = segment.distance(p);
+						let closer = match best{
+							None => true,
+							// a shared end point is closest to both its segments, the one pointing away is right
+							Some((d, o, _, _)) => distance < d - 1e-6 || (distance <= d + 1e-6 && ortho < o),
+						};
+						if closer{best = Some((distance, ortho, t, segment));}
+					}
+					data.push(match best{
+						Some((distance, _, t, segment)) => to_value(segment.pseudo_distance(p, t, distance) * orientation),
+						None => 0,
+					});
+				}
+			}else{
+				let distance = segments.iter().map(|s|s.distance(p).0).fold(f32::INFINITY, f32::min).sqrt();
+				data.push(to_value(if winding(&segments, p) != 0{distance}else{-distance}));
+			}
+		}
+	}
+	DistanceField{width, height, channels, left, top, range: settings.range, data}
+}
+
+///The winding number of the segments around `p`
+fn winding(segments: &[Segment], p: Point)->i32{
+	let mut rv = 0;
+	for s in segments.iter(){
+		if s.a.y <= p.y{
+			if s.b.y > p.y && s.side(p) > 0.0{rv += 1;}
+		}else if s.b.y <= p.y && s.side(p) < 0.0{rv -= 1;}
+	}
+	rv
+}
+
+///Where a glyph is in an atlas and how to draw it
+#[derive(Debug, Clone, PartialEq)]
+pub struct AtlasGlyph{
+	pub glyph_id: u16,
+	///Advance width in ems
+	pub advance: f32,
+	///The quad to draw relative to the glyph origin in ems as (left, bottom, right, top), `None` for glyphs without an outline
+	pub plane_bounds: Option<[f32; 4]>,
+	///The glyph's pixels in the atlas as (left, top, right, bottom)
+	pub atlas_bounds: Option<[u32; 4]>,
+	///Texture coordinates of the glyph as (u0, v0, u1, v1) where v goes down from the top of the atlas
+	pub uv: Option<[f32; 4]>,
+}
+
+///Distance fields of many glyphs packed into one image
+#[derive(Debug, Clone, PartialEq)]
+pub struct Atlas{
+	pub width: u32,
+	pub height: u32,
+	///Bytes per pixel: 1 for SDFs and 3 for MSDFs
+	pub channels: u32,
+	///Pixels per em the glyphs were generated at
+	pub em_size: f32,
+	///The distance in pixels covered by the values, half of it either side of the edge
+	pub range: f32,
+	pub data: Vec<u8>,
+	pub glyphs: Vec<AtlasGlyph>,
+}
+impl Atlas{
+	///The glyph's entry in the atlas
+	pub fn glyph(&self, glyph_id: u16)->Option<&AtlasGlyph>{self.glyphs.iter().find(|g|g.glyph_id == glyph_id)}
+	///The atlas metadata as JSON in the layout used by common MSDF tools
+	pub fn metadata_json(&self)->String{
+		let bounds = |b: &[f32; 4]|format!("{{\"left\":{},\"bottom\":{},\"right\":{},\"top\":{}}}", b[0], b[1], b[2], b[3]);
+		let glyphs: Vec<String> = self.glyphs.iter().map(|g|{
+			let mut rv = format!("{{\"index\":{},\"advance\":{}", g.glyph_id, g.advance);
+			if let Some(b) = &g.plane_bounds{rv += &format!(",\"planeBounds\":{}", bounds(b));}
+			if let Some(b) = &g.atlas_bounds{
+				// atlas bounds are given from the bottom of the image
+				let b = [b[0] as f32, (self.height - b[3]) as f32, b[2] as f32, (self.height - b[1]) as f32];
+				rv += &format!(",\"atlasBounds\":{}", bounds(&b));
+			}
+			rv + "}"
+		}).collect();
+		format!(
+			"{{\"atlas\":{{\"type\":\"{}\",\"distanceRange\":{},\"size\":{},\"width\":{},\"height\":{},\"yOrigin\":\"bottom\"}},\"glyphs\":[{}]}}",
+			if self.channels == 3{"msdf"}else{"sdf"}, self.range, self.em_size, self.width, self.height, glyphs.join(","),
+		)
+	}
+}
+
+///Packs distance fields of glyphs into an atlas of a fixed width using rows sorted by height
+#[derive(Debug, Clone)]
+pub struct AtlasBuilder{
+	settings: SdfSettings,
+	units_per_em: f32,
+	multi_channel: bool,
+	width: u32,
+	///Pixels left empty between glyphs
+	spacing: u32,
+	glyphs: Vec<(u16, f32, DistanceField)>,
+}
+impl AtlasBuilder{
+	///An atlas `width` pixels wide of SDFs (or MSDFs if `multi_channel`)
+	pub fn new(settings: SdfSettings, units_per_em: u16, multi_channel: bool, width: u32)->Self{Self{
+		settings,
+		units_per_em: units_per_em.max(1) as f32,
+		multi_channel,
+		width,
+		spacing: 1,
+		glyphs: Vec::new(),
+	}}
+	pub fn spacing(mut self, spacing: u32)->Self{self.spacing = spacing; self}
+	///Adds a glyph with its outline and advance width in font units
+	pub fn add_glyph(&mut self, glyph_id: u16, outline: &Outline, advance_width: f32){
+		let field = generate(outline, &self.settings, self.multi_channel);
+		self.glyphs.push((glyph_id, advance_width, field));
+	}
+	///Lays out the glyphs, `None` if a glyph is wider than the atlas
+	pub fn build(self)->Option<Atlas>{
+		let channels = if self.multi_channel{3}else{1};
+		let em_size = self.settings.scale * self.units_per_em;
+		let mut order: Vec<usize> = (0..self.glyphs.len()).collect();
+		order.sort_by_key(|i|std::cmp::Reverse(self.glyphs[*i].2.height));
+		// place each glyph on the current row, starting a new one when it is full
+		let mut positions = vec![None; self.glyphs.len()];
+		let (mut x, mut y, mut row_height) = (self.spacing, self.spacing, 0);
+		for i in order{
+			let field = &self.glyphs[i].2;
+			if field.width == 0 || field.height == 0{continue;}
+			if field.width + 2 * self.spacing > self.width{return None;}
+			if x + field.width + self.spacing > self.width{
+				y += row_height + self.spacing;
+				(x, row_height) = (self.spacing, 0);
+			}
+			positions[i] = Some((x, y));
+			x += field.width + self.spacing;
+			row_height = row_height.max(field.height);
+		}
+		let height = y + row_height + self.spacing;
+		let mut data = vec![0u8; (self.width * height * channels) as usize];
+		let mut glyphs = Vec::with_capacity(self.glyphs.len());
+		for ((glyph_id, advance_width, field), position) in self.glyphs.iter().zip(positions){
+			let mut glyph = AtlasGlyph{glyph_id: *glyph_id, advance: advance_width / self.units_per_em, plane_bounds: None, atlas_bounds: None, uv: None};
+			if let Some((x, y)) = position{
+				let row_bytes = (field.width * channels) as usize;
+				for row in 0..field.height{
+					let start = (((y + row) * self.width + x) * channels) as usize;
+					let source = (row * field.width * channels) as usize;
+					data[start..start + row_bytes].copy_from_slice(&field.data[source..source + row_bytes]);
+				}
+				glyph.plane_bounds = Some([
+					field.left as f32 / em_size,
+					(field.top - field.height as i32) as f32 / em_size,
+					(field.left + field.width as i32) as f32 / em_size,
+					field.top as f32 / em_size,
+				]);
+				glyph.atlas_bounds = Some([x, y, x + field.width, y + field.height]);
+				glyph.uv = Some([
+					x as f32 / self.width as f32,
+					y as f32 / height as f32,
+					(x + field.width) as f32 / self.width as f32,
+					(y + field.height) as f32 / height as f32,
+				]);
+			}
+			glyphs.push(glyph);
+		}
+		Some(Atlas{width: self.width, height, channels, em_size, range: self.settings.range, data, glyphs})
+	}
+}
@@ -0,0 +1,596 @@
+use std::io::{Read, Seek};
+
+use crate::core::{
+	ChainedSequenceContext, CmapTable, FromFileErr, GdefTable, GlyphClass, GposSubtable, GposTable,
+	GsubSubtable, GsubTable, KernTable, Lookup, LookupFlag, OTTF, PairPos, SequenceContext, SequenceLookupRecord, Table, ValueRecord,
+};
+
+///Features applied by default for horizontal text in scripts without special shaping needs
+pub const DEFAULT_FEATURES: [&str; 12] = [
+	"ccmp", "locl", "rlig", "rclt", "calt", "liga", "clig", "curs", "kern", "mark", "mkmk", "rvrn",
+];
+///Scripts that are written right to left
+const RIGHT_TO_LEFT_SCRIPTS: [&str; 10] = [
+	"arab", "hebr", "syrc", "thaa", "nkoo", "adlm", "mand", "samr", "rohg", "yezi",
+];
+///The deepest contextual lookups are allowed to nest
+const MAX_NESTING_LEVEL: usize = 64;
+///How many times longer than the text the glyph buffer is allowed to grow
+const MAX_LENGTH_FACTOR: usize = 32;
+///How many lookup applications per byte of text shaping is allowed to make, nesting only bounds the depth so each level could otherwise apply many lookups
+const MAX_OPS_FACTOR: usize = 64;
+///The fewest lookup applications shaping is allowed to make however short the text is
+const MIN_OPS: usize = 16384;
+
+///Whether the script with the OpenType tag `script` is written right to left
+pub fn is_right_to_left(script: &str)->bool{RIGHT_TO_LEFT_SCRIPTS.contains(&script)}
+
+///The tables needed to shape text
+#[derive(Debug)]
+pub struct ShapingFont{
+	pub cmap: CmapTable,
+	pub gsub: Option<GsubTable>,
+	pub gpos: Option<GposTable>,
+	pub gdef: Option<GdefTable>,
+	///Legacy kerning used when there is no `GPOS` table
+	pub kern: Option<KernTable>,
+	///The advance width of each glyph from the `hmtx` table
+	pub advance_widths: Box<[u16]>,
+}
+impl ShapingFont{
+	///Reads the tables needed for shaping from the font, `cmap` is the only table required
+	pub fn from_file<F>(f: &mut F, font: &OTTF)->Result<Self, FromFileErr<(),()>> where F: Read, F: Seek{
+		let cmap = match unwrap_or_ret!(font.load_table(f, "cmap")){
+			Some(Table::Cmap(t)) => t,
+			_ => return Err(FromFileErr::InvalidData(())),
+		};
+		let gsub = match unwrap_or_ret!(font.load_table(f, "GSUB")){Some(Table::GSUB(t)) => Some(t), _ => None};
+		let gpos = match unwrap_or_ret!(font.load_table(f, "GPOS")){Some(Table::GPOS(t)) => Some(t), _ => None};
+		let gdef = match unwrap_or_ret!(font.load_table(f, "GDEF")){Some(Table::GDEF(t)) => Some(t), _ => None};
+		let kern = match unwrap_or_ret!(font.load_table(f, "kern")){Some(Table::Kern(t)) => Some(t), _ => None};
+		let mut advance_widths = Vec::new();
+		if let (Some(Table::Maxp(maxp)), Some(Table::Hhea(hhea)), Some(Table::Hmtx(hmtx))) = (
+			unwrap_or_ret!(font.load_table(f, "maxp")),
+			unwrap_or_ret!(font.load_table(f, "hhea")),
+			unwrap_or_ret!(font.load_table(f, "hmtx")),
+		){
+			for glyph_id in 0..maxp.num_glyphs{advance_widths.push(unwrap_or_ret!(hmtx.advance_width(f, &hhea, glyph_id)));}
+		}
+		Ok(Self{cmap, gsub, gpos, gdef, kern, advance_widths: advance_widths.into()})
+	}
+	fn advance_width(&self, glyph_id: u16)->i32{self.advance_widths.get(glyph_id as usize).copied().unwrap_or(0) as i32}
+	fn is_mark(&self, glyph_id: u16)->bool{
+		self.gdef.as_ref().is_some_and(|g|g.glyph_class(glyph_id) == GlyphClass::Mark)
+	}
+}
+
+///A glyph produced by shaping with its position in font units
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PositionedGlyph{
+	pub glyph_id: u16,
+	///The byte index in the text of the first character this glyph came from
+	pub cluster: usize,
+	///How far to move the pen horizontally after drawing this glyph
+	pub x_advance: i32,
+	///How far to move the pen vertically after drawing this glyph
+	pub y_advance: i32,
+	///Horizontal offset of the glyph from the pen position
+	pub x_offset: i32,
+	///Vertical offset of the glyph from the pen position
+	pub y_offset: i32,
+}
+
+///Shapes `text` with the lookups of `features` for `script` and `language` (OpenType tags).
+///The characters are mapped through `cmap`, then the `GSUB` and `GPOS` lookups are applied in lookup list order skipping glyphs as specified by the lookup flags.
+///Fonts without a `GPOS` table are kerned with the `kern` table if `features` has `kern`.
+///Glyphs are returned in visual order so right to left text comes out reversed.
+///
+///This is a generic shaper, positional forms (`init`, `medi`, `fina` ...) and reordering needed by complex scripts aren't worked out from the text.
+pub fn shape(font: &ShapingFont, text: &str, script: &str, language: Option<&str>, features: &[&str])->Vec<PositionedGlyph>{
+	let mut shaper = Shaper{
+		font,
+		glyphs: map_characters(&font.cmap, text),
+		positions: Vec::new(),
+		next_lig_id: 1,
+		max_length: text.len() * MAX_LENGTH_FACTOR + 64,
+		ops_left: (text.len() * MAX_OPS_FACTOR).max(MIN_OPS),
+		right_to_left: is_right_to_left(script),
+	};
+	if let Some(gsub) = &font.gsub{
+		for lookup_index in gsub.lookup_indices(script, language, features){shaper.apply_gsub_lookup(lookup_index);}
+	}
+	shaper.positions = shaper.glyphs.iter().map(|g|GlyphPosition{x_advance: font.advance_width(g.glyph_id), ..Default::default()}).collect();
+	if let Some(gpos) = &font.gpos{
+		for lookup_index in gpos.lookup_indices(script, language, features){shaper.apply_gpos_lookup(lookup_index);}
+	}else if let Some(kern) = font.kern.as_ref().filter(|_|features.contains(&"kern")){
+		shaper.apply_kern(kern);
+	}
+	shaper.resolve_attachments();
+	let mut rv: Vec<PositionedGlyph> = shaper.glyphs.iter().zip(shaper.positions.iter()).map(|(g, p)|PositionedGlyph{
+		glyph_id: g.glyph_id,
+		cluster: g.cluster,
+		x_advance: p.x_advance,
+		y_advance: p.y_advance,
+		x_offset: p.x_offset,
+		y_offset: p.y_offset,
+	}).collect();
+	if shaper.right_to_left{rv.reverse();}
+	rv
+}
+
+fn is_variation_selector(chr: char)->bool{matches!(chr as u32, 0xFE00..=0xFE0F | 0xE0100..=0xE01EF)}
+
+///Maps each character to a glyph, variation selectors are combined with the character before them
+fn map_characters(cmap: &CmapTable, text: &str)->Vec<GlyphInfo>{
+	let mut rv = Vec::with_capacity(text.len());
+	let mut chars = text.char_indices().peekable();
+	while let Some((cluster, chr)) = chars.next(){
+		let glyph_id = match chars.peek(){
+			Some((_, selector)) if is_variation_selector(*selector) => {
+				let selector = *selector;
+				chars.next();
+				cmap.char_variant_to_glyph(chr, selector).or_else(||cmap.char_to_glyph(chr))
+			},
+			_ => cmap.char_to_glyph(chr),
+		}.unwrap_or(0);
+		rv.push(GlyphInfo{glyph_id, cluster, lig_id: 0, lig_component: 0});
+	}
+	rv
+}
+
+#[derive(Debug, Clone, Copy)]
+struct GlyphInfo{
+	glyph_id: u16,
+	cluster: usize,
+	///Identifies the ligature this glyph is (or a mark in one of its components is attached to)
+	lig_id: u16,
+	///For marks between the components of a ligature, the (1 based) component they follow
+	lig_component: u16,
+}
+#[derive(Debug, Clone, Copy, Default)]
+struct GlyphPosition{
+	x_advance: i32,
+	y_advance: i32,
+	x_offset: i32,
+	y_offset: i32,
+	attachment: Option<Attachment>,
+}
+impl GlyphPosition{
+	fn add(&mut self, value: &ValueRecord){
+		self.x_offset += value.x_placement as i32;
+		self.y_offset += value.y_placement as i32;
+		self.x_advance += value.x_advance as i32;
+		self.y_advance += value.y_advance as i32;
+	}
+}
+///Offsets that depend on the final position of another glyph
+#[derive(Debug, Clone, Copy)]
+enum Attachment{
+	///A mark with its anchor (dx, dy) away from the anchor of `base`
+	Mark{base: usize, dx: i32, dy: i32},
+	///A cursively attached glyph `dy` above `parent`
+	Cursive{parent: usize, dy: i32},
+}
+
+///Which glyphs a lookup skips over
+struct GlyphFilter<'a>{
+	gdef: Option<&'a GdefTable>,
+	lookup_flag: LookupFlag,
+	mark_filtering_set: Option<u16>,
+}
+impl GlyphFilter<'_>{
+	fn skips(&self, glyph_id: u16)->bool{
+		self.gdef.is_some_and(|g|g.ignores_glyph(self.lookup_flag, self.mark_filtering_set, glyph_id))
+	}
+	fn next(&self, glyphs: &[GlyphInfo], from: usize)->Option<usize>{
+		(from + 1..glyphs.len()).find(|j|!self.skips(glyphs[*j].glyph_id))
+	}
+	fn prev(&self, glyphs: &[GlyphInfo], from: usize)->Option<usize>{
+		(0..from).rev().find(|j|!self.skips(glyphs[*j].glyph_id))
+	}
+	///Matches `count` glyphs after `i` against `matches`, returning the indices of `i` and the matched glyphs
+	fn match_forward(&self, glyphs: &[GlyphInfo], i: usize, count: usize, matches: impl Fn(usize, u16)->bool)->Option<Vec<usize>>{
+		let mut rv = Vec::with_capacity(count + 1);
+		rv.push(i);
+		let mut j = i;
+		for k in 0..count{
+			j = self.next(glyphs, j)?;
+			if !matches(k, glyphs[j].glyph_id){return None;}
+			rv.push(j);
+		}
+		Some(rv)
+	}
+	///Matches `count` glyphs before `i` (nearest first) against `matches`
+	fn match_backward(&self, glyphs: &[GlyphInfo], i: usize, count: usize, matches: impl Fn(usize, u16)->bool)->bool{
+		let mut j = i;
+		for k in 0..count{
+			j = match self.prev(glyphs, j){
+				Some(j) => j,
+				None => return false,
+			};
+			if !matches(k, glyphs[j].glyph_id){return false;}
+		}
+		true
+	}
+
+	///The indices of the input glyphs and the lookups to apply to them if the context matches at `i`
+	fn match_context<'t>(&self, glyphs: &[GlyphInfo], i: usize, context: &'t SequenceContext)->Option<(Vec<usize>, &'t [SequenceLookupRecord])>{
+		let first = glyphs[i].glyph_id;
+		match context{
+			SequenceContext::Format1{coverage, seq_rule_sets} => {
+				let rule_set = seq_rule_sets.get(coverage.index(first)? as usize)?.as_ref()?;
+				rule_set.seq_rules.iter().find_map(|r|
+					self.match_forward(glyphs, i, r.input_sequence.len(), |k, g|g == r.input_sequence[k])
+						.map(|m|(m, &*r.seq_lookup_records))
+				)
+			},
+			SequenceContext::Format2{coverage, class_def, class_seq_rule_sets} => {
+				coverage.index(first)?;
+				let rule_set = class_seq_rule_sets.get(class_def.class(first) as usize)?.as_ref()?;
+				rule_set.seq_rules.iter().find_map(|r|
+					self.match_forward(glyphs, i, r.input_sequence.len(), |k, g|class_def.class(g) == r.input_sequence[k])
+						.map(|m|(m, &*r.seq_lookup_records))
+				)
+			},
+			SequenceContext::Format3{coverages, seq_lookup_records} => {
+				coverages.first()?.index(first)?;
+				self.match_forward(glyphs, i, coverages.len() - 1, |k, g|coverages[k + 1].contains(g))
+					.map(|m|(m, &**seq_lookup_records))
+			},
+		}
+	}
+	///The indices of the input glyphs and the lookups to apply to them if the chained context matches at `i`
+	fn match_chained_context<'t>(&self, glyphs: &[GlyphInfo], i: usize, context: &'t ChainedSequenceContext)->Option<(Vec<usize>, &'t [SequenceLookupRecord])>{
+		let first = glyphs[i].glyph_id;
+		// matches the input then the backtrack and lookahead sequences
+		let match_chain = |backtrack: &dyn Fn(usize, u16)->bool, backtrack_count: usize,
+			input: &dyn Fn(usize, u16)->bool, input_count: usize,
+			lookahead: &dyn Fn(usize, u16)->bool, lookahead_count: usize|->Option<Vec<usize>>{
+			let matched = self.match_forward(glyphs, i, input_count, input)?;
+			if !self.match_backward(glyphs, i, backtrack_count, backtrack){return None;}
+			self.match_forward(glyphs, *matched.last().unwrap(), lookahead_count, lookahead)?;
+			Some(matched)
+		};
+		match context{
+			ChainedSequenceContext::Format1{coverage, chained_seq_rule_sets} => {
+				let rule_set = chained_seq_rule_sets.get(coverage.index(first)? as usize)?.as_ref()?;
+				rule_set.chained_seq_rules.iter().find_map(|r|match_chain(
+					&|k, g|g == r.backtrack_sequence[k], r.backtrack_sequence.len(),
+					&|k, g|g == r.input_sequence[k], r.input_sequence.len(),
+					&|k, g|g == r.lookahead_sequence[k], r.lookahead_sequence.len(),
+				).map(|m|(m, &*r.seq_lookup_records)))
+			},
+			ChainedSequenceContext::Format2{coverage, backtrack_class_def, input_class_def, lookahead_class_def, chained_class_seq_rule_sets} => {
+				coverage.index(first)?;
+				let rule_set = chained_class_seq_rule_sets.get(input_class_def.class(first) as usize)?.as_ref()?;
+				rule_set.chained_seq_rules.iter().find_map(|r|match_chain(
+					&|k, g|backtrack_class_def.class(g) == r.backtrack_sequence[k], r.backtrack_sequence.len(),
+					&|k, g|input_class_def.class(g) == r.input_sequence[k], r.input_sequence.len(),
+					&|k, g|lookahead_class_def.class(g) == r.lookahead_sequence[k], r.lookahead_sequence.len(),
+				).map(|m|(m, &*r.seq_lookup_records)))
+			},
+			ChainedSequenceContext::Format3{backtrack_coverages, input_coverages, lookahead_coverages, seq_lookup_records} => {
+				input_coverages.first()?.index(first)?;
+				match_chain(
+					&|k, g|backtrack_coverages[k].contains(g), backtrack_coverages.len(),
+					&|k, g|input_coverages[k + 1].contains(g), input_coverages.len() - 1,
+					&|k, g|lookahead_coverages[k].contains(g), lookahead_coverages.len(),
+				).map(|m|(m, &**seq_lookup_records))
+			},
+		}
+	}
+}
+
+struct Shaper<'a>{
+	font: &'a ShapingFont,
+	glyphs: Vec<GlyphInfo>,
+	positions: Vec<GlyphPosition>,
+	next_lig_id: u16,
+	max_length: usize,
+	///Lookup applications left, once there are none lookups stop matching
+	ops_left: usize,
+	right_to_left: bool,
+}
+impl<'a> Shaper<'a>{
+	fn filter<T>(&self, lookup: &Lookup<T>)->GlyphFilter<'a>{GlyphFilter{
+		gdef: self.font.gdef.as_ref(),
+		lookup_flag: lookup.lookup_flag,
+		mark_filtering_set: lookup.mark_filtering_set,
+	}}
+
+	fn apply_gsub_lookup(&mut self, lookup_index: u16){
+		let lookup = match self.font.gsub.as_ref().and_then(|g|g.lookup_list.lookups.get(lookup_index as usize)){
+			Some(l) => l,
+			None => return,
+		};
+		let filter = self.filter(lookup);
+		// reverse chaining substitutions are applied from the end of the text
+		if matches!(lookup.subtables.first(), Some(GsubSubtable::ReverseChainSingle(_))){
+			for i in (0..self.glyphs.len()).rev(){
+				if !filter.skips(self.glyphs[i].glyph_id){self.apply_gsub_at(lookup, i, 0);}
+			}
+			return;
+		}
+		let mut i = 0;
+		while i < self.glyphs.len(){
+			if filter.skips(self.glyphs[i].glyph_id){i += 1; continue;}
+			let length = self.glyphs.len();
+			i = match self.apply_gsub_at(lookup, i, 0){
+				Some(next) if next > i || self.glyphs.len() < length => next,
+				_ => i + 1,
+			};
+		}
+	}
+	///Applies the first subtable of `lookup` that matches at `i`, returning the index to continue from if one did
+	fn apply_gsub_at(&mut self, lookup: &'a Lookup<GsubSubtable>, i: usize, depth: usize)->Option<usize>{
+		if self.ops_left == 0{return None;}
+		self.ops_left -= 1;
+		lookup.subtables.iter().find_map(|s|self.apply_gsub_subtable(lookup, s, i, depth))
+	}
+	fn apply_gsub_subtable(&mut self, lookup: &'a Lookup<GsubSubtable>, subtable: &'a GsubSubtable, i: usize, depth: usize)->Option<usize>{
+		let glyph_id = self.glyphs[i].glyph_id;
+		let filter = self.filter(lookup);
+		match subtable{
+			GsubSubtable::Single(s) => {
+				self.glyphs[i].glyph_id = s.substitute(glyph_id)?;
+				Some(i + 1)
+			},
+			GsubSubtable::Multiple(m) => {
+				let sequence = m.sequence(glyph_id)?;
+				if self.glyphs.len() + sequence.len() > self.max_length{return None;}
+				let info = self.glyphs[i];
+				self.glyphs.splice(i..=i, sequence.iter().map(|g|GlyphInfo{glyph_id: *g, ..info}));
+				Some(i + sequence.len())
+			},
+			GsubSubtable::Alternate(a) => {
+				self.glyphs[i].glyph_id = *a.alternates(glyph_id)?.first()?;
+				Some(i + 1)
+			},
+			GsubSubtable::Ligature(l) => {
+				let (ligature, matched) = l.ligatures(glyph_id)?.iter().find_map(|lig|filter.match_forward(
+					&self.glyphs, i, lig.component_glyph_ids.len(), |k, g|g == lig.component_glyph_ids[k]
+				).map(|m|(lig, m)))?;
+				self.form_ligature(ligature.ligature_glyph, &matched);
+				Some(i + 1)
+			},
+			GsubSubtable::Context(c) => {
+				let (matched, records) = filter.match_context(&self.glyphs, i, c)?;
+				Some(self.apply_gsub_records(matched, records, depth))
+			},
+			GsubSubtable::ChainedContext(c) => {
+				let (matched, records) = filter.match_chained_context(&self.glyphs, i, c)?;
+				Some(self.apply_gsub_records(matched, records, depth))
+			},
+			GsubSubtable::ReverseChainSingle(r) => {
+				let index = r.coverage.index(glyph_id)?;
+				if !filter.match_backward(&self.glyphs, i, r.backtrack_coverages.len(), |k, g|r.backtrack_coverages[k].contains(g))
+				|| filter.match_forward(&self.glyphs, i, r.lookahead_coverages.len(), |k, g|r.lookahead_coverages[k].contains(g)).is_none()
+				{return None;}
+				self.glyphs[i].glyph_id = *r.substitute_glyph_ids.get(index as usize)?;
+				Some(i + 1)
+			},
+			GsubSubtable::Unknown(_) => None,
+		}
+	}
+	///Replaces the glyphs at `matched` with `ligature_glyph`, marks skipped over between the components are kept and remember which component they follow
+	fn form_ligature(&mut self, ligature_glyph: u16, matched: &[usize]){
+		let lig_id = self.next_lig_id;
+		self.next_lig_id = self.next_lig_id.wrapping_add(1).max(1);
+		for (component, pair) in matched.windows(2).enumerate(){
+			for mark in self.glyphs[pair[0] + 1..pair[1]].iter_mut(){
+				mark.lig_id = lig_id;
+				mark.lig_component = component as u16 + 1;
+			}
+		}
+		let first = matched[0];
+		self.glyphs[first] = GlyphInfo{
+			glyph_id: ligature_glyph,
+			cluster: matched.iter().map(|j|self.glyphs[*j].cluster).min().unwrap(),
+			lig_id,
+			lig_component: 0,
+		};
+		for j in matched[1..].iter().rev(){self.glyphs.remove(*j);}
+	}
+	///Applies nested lookups to the matched input glyphs of a contextual substitution returning the index after the input
+	fn apply_gsub_records(&mut self, mut matched: Vec<usize>, records: &[SequenceLookupRecord], depth: usize)->usize{
+		let mut end = *matched.last().unwrap() as isize + 1;
+		if depth >= MAX_NESTING_LEVEL{return end as usize;}
+		for record in records{
+			let position = match matched.get(record.sequence_index as usize){
+				Some(p) if *p < self.glyphs.len() => *p,
+				_ => continue,
+			};
+			let lookup = match self.font.gsub.as_ref().and_then(|g|g.lookup_list.lookups.get(record.lookup_list_index as usize)){
+				Some(l) => l,
+				None => continue,
+			};
+			let length = self.glyphs.len() as isize;
+			self.apply_gsub_at(lookup, position, depth + 1);
+			// the nested lookup may have changed the number of glyphs so move the following positions along
+			let delta = self.glyphs.len() as isize - length;
+			if delta != 0{
+				end += delta;
+				for m in matched.iter_mut(){if *m > position{*m = (*m as isize + delta).max(position as isize) as usize;}}
+			}
+		}
+		end.max(0) as usize
+	}
+
+	fn apply_gpos_lookup(&mut self, lookup_index: u16){
+		let lookup = match self.font.gpos.as_ref().and_then(|g|g.lookup_list.lookups.get(lookup_index as usize)){
+			Some(l) => l,
+			None => return,
+		};
+		let filter = self.filter(lookup);
+		let mut i = 0;
+		while i < self.glyphs.len(){
+			if filter.skips(self.glyphs[i].glyph_id){i += 1; continue;}
+			i = match self.apply_gpos_at(lookup, i, 0){
+				Some(next) if next > i => next,
+				_ => i + 1,
+			};
+		}
+	}
+	fn apply_gpos_at(&mut self, lookup: &'a Lookup<GposSubtable>, i: usize, depth: usize)->Option<usize>{
+		if self.ops_left == 0{return None;}
+		self.ops_left -= 1;
+		lookup.subtables.iter().find_map(|s|self.apply_gpos_subtable(lookup, s, i, depth))
+	}
+	///The index of the closest glyph before `i` that isn't a mark
+	fn find_base(&self, i: usize)->Option<usize>{
+		(0..i).rev().find(|j|!self.font.is_mark(self.glyphs[*j].glyph_id))
+	}
+	fn attach_mark(&mut self, mark: usize, base: usize, (mark_x, mark_y): (i16, i16), (base_x, base_y): (i16, i16)){
+		let position = &mut self.positions[mark];
+		position.x_advance = 0;
+		position.y_advance = 0;
+		position.attachment = Some(Attachment::Mark{
+			base,
+			dx: base_x as i32 - mark_x as i32,
+			dy: base_y as i32 - mark_y as i32,
+		});
+	}
+	fn apply_gpos_subtable(&mut self, lookup: &'a Lookup<GposSubtable>, subtable: &'a GposSubtable, i: usize, depth: usize)->Option<usize>{
+		let glyph_id = self.glyphs[i].glyph_id;
+		let filter = self.filter(lookup);
+		match subtable{
+			GposSubtable::Single(s) => {
+				self.positions[i].add(s.value(glyph_id)?);
+				Some(i + 1)
+			},
+			GposSubtable::Pair(p) => {
+				let j = filter.next(&self.glyphs, i)?;
+				let (first, second) = p.adjustment(glyph_id, self.glyphs[j].glyph_id)?;
+				self.positions[i].add(first);
+				self.positions[j].add(second);
+				let value_format2 = match p{
+					PairPos::Format1{value_format2, ..} | PairPos::Format2{value_format2, ..} => *value_format2,
+				};
+				// the second glyph can start another pair unless it was adjusted
+				Some(if value_format2 != 0{j + 1}else{j})
+			},
+			GposSubtable::Cursive(c) => {
+				let (entry_x, entry_y) = c.entry_exit(glyph_id)?.entry_anchor.as_ref()?.coordinates();
+				let p = filter.prev(&self.glyphs, i)?;
+				let (exit_x, exit_y) = c.entry_exit(self.glyphs[p].glyph_id)?.exit_anchor.as_ref()?.coordinates();
+				let (entry_x, entry_y, exit_x, exit_y) = (entry_x as i32, entry_y as i32, exit_x as i32, exit_y as i32);
+				// make the exit point of the previous glyph meet the entry point of this one
+				if !self.right_to_left{
+					self.positions[p].x_advance = exit_x + self.positions[p].x_offset;
+					let d = entry_x + self.positions[i].x_offset;
+					self.positions[i].x_advance -= d;
+					self.positions[i].x_offset -= d;
+				}else{
+					let d = exit_x + self.positions[p].x_offset;
+					self.positions[p].x_advance -= d;
+					self.positions[p].x_offset -= d;
+					self.positions[i].x_advance = entry_x + self.positions[i].x_offset;
+				}
+				let (child, parent, dy) = if lookup.lookup_flag.has_flag(LookupFlag::RIGHT_TO_LEFT)
+				{(p, i, entry_y - exit_y)}else{(i, p, exit_y - entry_y)};
+				self.positions[child].attachment = Some(Attachment::Cursive{parent, dy});
+				Some(i + 1)
+			},
+			GposSubtable::MarkBase(m) => {
+				m.mark_coverage.index(glyph_id)?;
+				let base = self.find_base(i)?;
+				let (mark_anchor, base_anchor) = m.attachment(glyph_id, self.glyphs[base].glyph_id)?;
+				self.attach_mark(i, base, mark_anchor.coordinates(), base_anchor.coordinates());
+				Some(i + 1)
+			},
+			GposSubtable::MarkLig(m) => {
+				m.mark_coverage.index(glyph_id)?;
+				let ligature = self.find_base(i)?;
+				let ligature_glyph = self.glyphs[ligature].glyph_id;
+				let components = m.ligature_array.get(m.ligature_coverage.index(ligature_glyph)? as usize)?.rows.len();
+				// marks between components attach to the component before them, others to the last component
+				let (mark_info, ligature_info) = (self.glyphs[i], self.glyphs[ligature]);
+				let component = if mark_info.lig_id != 0 && mark_info.lig_id == ligature_info.lig_id && mark_info.lig_component > 0
+				{(mark_info.lig_component as usize).min(components) - 1}else{components.saturating_sub(1)};
+				let (mark_anchor, ligature_anchor) = m.attachment(glyph_id, ligature_glyph, component)?;
+				self.attach_mark(i, ligature, mark_anchor.coordinates(), ligature_anchor.coordinates());
+				Some(i + 1)
+			},
+			GposSubtable::MarkMark(m) => {
+				m.mark1_coverage.index(glyph_id)?;
+				let mark2 = filter.prev(&self.glyphs, i)?;
+				if self.font.gdef.is_some() && !self.font.is_mark(self.glyphs[mark2].glyph_id){return None;}
+				let (mark1_anchor, mark2_anchor) = m.attachment(glyph_id, self.glyphs[mark2].glyph_id)?;
+				self.attach_mark(i, mark2, mark1_anchor.coordinates(), mark2_anchor.coordinates());
+				Some(i + 1)
+			},
+			GposSubtable::Context(c) => {
+				let (matched, records) = filter.match_context(&self.glyphs, i, c)?;
+				Some(self.apply_gpos_records(matched, records, depth))
+			},
+			GposSubtable::ChainedContext(c) => {
+				let (matched, records) = filter.match_chained_context(&self.glyphs, i, c)?;
+				Some(self.apply_gpos_records(matched, records, depth))
+			},
+			GposSubtable::Unknown(_) => None,
+		}
+	}
+	///Applies nested lookups to the matched input glyphs of a contextual positioning returning the index after the input
+	fn apply_gpos_records(&mut self, matched: Vec<usize>, records: &[SequenceLookupRecord], depth: usize)->usize{
+		let end = *matched.last().unwrap() + 1;
+		if depth >= MAX_NESTING_LEVEL{return end;}
+		for record in records{
+			let position = match matched.get(record.sequence_index as usize){
+				Some(p) => *p,
+				None => continue,
+			};
+			if let Some(lookup) = self.font.gpos.as_ref().and_then(|g|g.lookup_list.lookups.get(record.lookup_list_index as usize))
+			{self.apply_gpos_at(lookup, position, depth + 1);}
+		}
+		end
+	}
+
+	///Adjusts the advance of each glyph by its kerning with the next glyph that isn't a mark
+	fn apply_kern(&mut self, kern: &KernTable){
+		let mut previous: Option<usize> = None;
+		for i in 0..self.glyphs.len(){
+			if self.font.is_mark(self.glyphs[i].glyph_id){continue;}
+			if let Some(p) = previous{
+				self.positions[p].x_advance += kern.kerning(self.glyphs[p].glyph_id, self.glyphs[i].glyph_id) as i32;
+			}
+			previous = Some(i);
+		}
+	}
+
+	///Works out the offsets of attached glyphs now that every glyph's advance is known
+	fn resolve_attachments(&mut self){
+		let count = self.positions.len();
+		// where the pen is when each glyph is drawn
+		let mut pen = vec![0i32; count];
+		if self.right_to_left{
+			for i in (0..count.saturating_sub(1)).rev(){pen[i] = pen[i + 1] + self.positions[i + 1].x_advance;}
+		}else{
+			for i in 1..count{pen[i] = pen[i - 1] + self.positions[i - 1].x_advance;}
+		}
+		let mut resolved = vec![false; count];
+		for i in 0..count{
+			// glyphs have to be resolved after what they are attached to
+			let mut chain = Vec::new();
+			let mut j = i;
+			while !resolved[j]{
+				resolved[j] = true;
+				chain.push(j);
+				match self.positions[j].attachment{
+					Some(Attachment::Mark{base: parent, ..}) | Some(Attachment::Cursive{parent, ..}) => j = parent,
+					None => break,
+				}
+			}
+			for j in chain.into_iter().rev(){match self.positions[j].attachment{
+				Some(Attachment::Mark{base, dx, dy}) => {
+					let base_position = self.positions[base];
+					self.positions[j].x_offset = base_position.x_offset + dx + pen[base] - pen[j];
+					self.positions[j].y_offset = base_position.y_offset + dy;
+				},
+				Some(Attachment::Cursive{parent, dy}) => {
+					self.positions[j].y_offset = self.positions[parent].y_offset + dy;
+				},
+				None => {},
+			}}
+		}
+	}
+}
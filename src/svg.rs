@@ -0,0 +1,80 @@
+use std::fmt::Write;
+
+use crate::core::{HeadTable, HheaTable};
+use crate::outline::{Outline, OutlineSink, Point, Transform};
+use crate::shaping::PositionedGlyph;
+
+///Builds SVG path data from outline commands, each point is transformed first
+#[derive(Debug, Clone)]
+pub struct SvgPathSink{
+	transform: Transform,
+	data: String,
+}
+impl SvgPathSink{
+	pub fn new(transform: Transform)->Self{Self{transform, data: String::new()}}
+	///The path data built so far (the `d` attribute)
+	pub fn data(&self)->&str{&self.data}
+	pub fn into_data(self)->String{self.data}
+	fn command(&mut self, command: char, points: &[Point]){
+		if !self.data.is_empty(){self.data.push(' ');}
+		self.data.push(command);
+		for p in points{
+			let p = self.transform.apply(*p);
+			let _ = write!(self.data, " {} {}", format_number(p.x), format_number(p.y));
+		}
+	}
+}
+impl OutlineSink for SvgPathSink{
+	fn move_to(&mut self, p: Point){self.command('M', &[p])}
+	fn line_to(&mut self, p: Point){self.command('L', &[p])}
+	fn quad_to(&mut self, c: Point, p: Point){self.command('Q', &[c, p])}
+	fn curve_to(&mut self, c0: Point, c1: Point, p: Point){self.command('C', &[c0, c1, p])}
+	fn close(&mut self){self.command('Z', &[])}
+}
+
+///A number rounded to 1/1000 without a trailing `.0` or negative zero
+fn format_number(v: f32)->String{
+	let v = (v * 1000.0).round() / 1000.0;
+	format!("{}", if v == 0.0{0.0}else{v})
+}
+
+///The transform from font units to SVG pixels (where y goes down) for an em of `pixel_size` pixels with the origin at `origin`
+pub fn font_to_pixel_transform(units_per_em: u16, pixel_size: f32, origin: Point)->Transform{
+	let scale = pixel_size / units_per_em.max(1) as f32;
+	Transform{xx: scale, xy: 0.0, yx: 0.0, yy: -scale, dx: origin.x, dy: origin.y}
+}
+
+///A `<path>` element drawing `outline` after applying `transform`
+pub fn glyph_path(outline: &Outline, transform: &Transform)->String{
+	let mut sink = SvgPathSink::new(*transform);
+	outline.emit(&mut sink);
+	format!("<path d=\"{}\"/>", sink.data())
+}
+
+///A standalone SVG document drawing shaped glyphs at `pixel_size` pixels per em.
+///`outline` gives the outline of a glyph id in font units, the document is as wide as the advances and as tall as the ascender to the descender.
+pub fn shaped_text_svg<O>(glyphs: &[PositionedGlyph], mut outline: O, head: &HeadTable, hhea: &HheaTable, pixel_size: f32)->String
+where O: FnMut(u16)->Option<Outline>{
+	let scale = pixel_size / head.units_per_em.max(1) as f32;
+	let width: i32 = glyphs.iter().map(|g|g.x_advance).sum();
+	let width = width as f32 * scale;
+	let height = (hhea.ascender as f32 - hhea.descender as f32) * scale;
+	let baseline = hhea.ascender as f32 * scale;
+	let mut rv = format!(
+		"<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n",
+		format_number(width), format_number(height),
+	);
+	let (mut x, mut y) = (0i32, 0i32);
+	for glyph in glyphs{
+		if let Some(o) = outline(glyph.glyph_id).filter(|o|!o.is_empty()){
+			let origin = Point::new((x + glyph.x_offset) as f32 * scale, baseline - (y + glyph.y_offset) as f32 * scale);
+			let transform = font_to_pixel_transform(head.units_per_em, pixel_size, origin);
+			rv += &glyph_path(&o, &transform);
+			rv.push('\n');
+		}
+		x += glyph.x_advance;
+		y += glyph.y_advance;
+	}
+	rv += "</svg>\n";
+	rv
+}
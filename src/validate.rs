@@ -0,0 +1,107 @@
+use std::io::{Read, Seek, SeekFrom};
+
+use crate::core::{array_from_file, calc_checksum, OTTF, SFNTVer, TableDirectory};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity{
+	///The font breaks the spec but can still be read
+	Warning,
+	///The font can't be read correctly
+	Error,
+}
+
+///A problem found by `validate`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic{
+	pub severity: Severity,
+	///The table the problem is in, `None` for problems with the directory as a whole
+	pub tag: Option<String>,
+	///Offset from the beginning of the font file of the data at fault
+	pub offset: u64,
+	pub message: String,
+}
+
+///Tables every font needs
+const REQUIRED_TABLES: [&str; 8] = ["cmap", "head", "hhea", "hmtx", "maxp", "name", "OS/2", "post"];
+///Offset of checkSumAdjustment in the head table, it is taken as 0 when the checksum of head is computed
+const CHECKSUM_ADJUSTMENT_OFFSET: usize = 8;
+///Size of the table directory before the table records
+const DIRECTORY_HEADER_SIZE: u64 = 12;
+const TABLE_RECORD_SIZE: u64 = 16;
+
+///Checks the table directory of `font` read from `f` against the file: the checksum, bounds, alignment and order of every table, the binary search fields and that the tables the font needs are present
+pub fn validate<F>(font: &OTTF, f: &mut F)->Vec<Diagnostic> where F: Read, F: Seek{
+	let mut rv = Vec::new();
+	let mut report = |severity, tag: Option<&str>, offset, message: String|rv.push(Diagnostic{severity, tag: tag.map(|t|t.to_string()), offset, message});
+	let directory = &font.table_directory;
+	let file_length = f.seek(SeekFrom::End(0)).unwrap_or(0);
+
+	let expected = TableDirectory::search_params(directory.num_tables);
+	for (offset, name, value, expected) in [
+		(6, "search_range", directory.search_range, expected.0),
+		(8, "entry_selector", directory.entry_selector, expected.1),
+		(10, "range_shift", directory.range_shift, expected.2),
+	]{if value != expected{
+		report(Severity::Warning, None, offset, format!("{} is {} but should be {} for {} tables", name, value, expected, directory.num_tables));
+	}}
+
+	let directory_end = DIRECTORY_HEADER_SIZE + TABLE_RECORD_SIZE * directory.table_records.len() as u64;
+	let mut previous_tag: Option<&str> = None;
+	for (i, record) in directory.table_records.iter().enumerate(){
+		let tag = record.table_tag.data.as_str();
+		let record_offset = DIRECTORY_HEADER_SIZE + TABLE_RECORD_SIZE * i as u64;
+		if let Some(previous) = previous_tag{
+			if previous >= tag{report(Severity::Error, Some(tag), record_offset, format!("table record comes after `{}`, records must be sorted by tag without duplicates", previous));}
+		}
+		previous_tag = Some(tag);
+
+		let start = record.offset as u64;
+		let end = start + record.length as u64;
+		if !start.is_multiple_of(4){report(Severity::Warning, Some(tag), start, "table is not 4-byte aligned".to_string());}
+		if start < directory_end{report(Severity::Error, Some(tag), start, "table overlaps the table directory".to_string());}
+		if end > file_length{
+			report(Severity::Error, Some(tag), start, format!("table ends at {} past the end of the file at {}", end, file_length));
+			continue;
+		}
+
+		let data: Option<Box<[u8]>> = match f.seek(SeekFrom::Start(start)){
+			Ok(_) => array_from_file::<_, u8, (), ()>(f, record.length as usize).ok(),
+			Err(_) => None,
+		};
+		let Some(mut data) = data else{
+			report(Severity::Error, Some(tag), start, "table could not be read".to_string());
+			continue;
+		};
+		if tag == "head" && data.len() >= CHECKSUM_ADJUSTMENT_OFFSET + 4{
+			data[CHECKSUM_ADJUSTMENT_OFFSET..CHECKSUM_ADJUSTMENT_OFFSET + 4].fill(0);
+		}
+		let checksum = calc_checksum(&data);
+		if checksum != record.checksum{
+			report(Severity::Warning, Some(tag), record_offset + 4, format!("checksum is {:#010X} but the table sums to {:#010X}", record.checksum, checksum));
+		}
+	}
+
+	// every pair of tables that overlap, ordered by where they start
+	let mut ranges: Vec<(u64, u64, &str)> = directory.table_records.iter()
+		.map(|r|(r.offset as u64, r.offset as u64 + r.length as u64, r.table_tag.data.as_str())).collect();
+	ranges.sort();
+	for (i, a) in ranges.iter().enumerate(){
+		for b in ranges[i + 1..].iter().take_while(|b|b.0 < a.1){
+			report(Severity::Error, Some(b.2), b.0, format!("table overlaps `{}`", a.2));
+		}
+	}
+
+	let has = |tag: &str|font.get_table_record(tag).is_some();
+	let outline_tables: &[&str] = match directory.sfnt_version{
+		SFNTVer::TrueType => &["glyf", "loca"],
+		SFNTVer::CFF => if has("CFF2"){&["CFF2"]}else{&["CFF "]},
+		SFNTVer::Unknown(v) => {
+			report(Severity::Error, None, 0, format!("unknown sfnt version {:#010X}", v));
+			&[]
+		},
+	};
+	for tag in REQUIRED_TABLES.iter().chain(outline_tables.iter()){
+		if !has(tag){report(Severity::Error, Some(tag), 0, format!("required table `{}` is missing", tag));}
+	}
+	rv
+}
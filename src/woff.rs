@@ -0,0 +1,216 @@
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+
+use macros::{FromFile, ToFile};
+
+use crate::builder::FontBuilder;
+use crate::core::{array_from_file, array_to_file, bounded_array_from_file, FromFile, FromFileErr, OTTF, SFNTVer, Tag, ToFile};
+use crate::deflate::{zlib_compress, zlib_decompress};
+
+#[derive(Debug,FromFile,ToFile)]
+pub struct WoffHeader{
+	///0x774F4646 'wOFF'
+	pub signature: u32,
+	///The "sfnt version" of the input font.
+	pub flavor: u32,
+	///Total size of the WOFF file.
+	pub length: u32,
+	///Number of entries in directory of font tables.
+	pub num_tables: u16,
+	///Reserved; set to zero.
+	pub reserved: u16,
+	///Total size needed for the uncompressed font data, including the sfnt header, directory, and font tables (including padding).
+	pub total_sfnt_size: u32,
+	///Major version of the WOFF file.
+	pub major_version: u16,
+	///Minor version of the WOFF file.
+	pub minor_version: u16,
+	///Offset to metadata block, from beginning of WOFF file.
+	pub meta_offset: u32,
+	///Length of compressed metadata block.
+	pub meta_length: u32,
+	///Uncompressed size of metadata block.
+	pub meta_orig_length: u32,
+	///Offset to private data block, from beginning of WOFF file.
+	pub priv_offset: u32,
+	///Length of private data block.
+	pub priv_length: u32,
+}
+#[derive(Debug,FromFile,ToFile)]
+pub struct WoffTableDirectoryEntry{
+	///4-byte sfnt table identifier.
+	pub tag: Tag,
+	///Offset to the data, from beginning of WOFF file.
+	pub offset: u32,
+	///Length of the compressed data, excluding padding.
+	pub comp_length: u32,
+	///Length of the uncompressed table, excluding padding.
+	pub orig_length: u32,
+	///Checksum of the uncompressed table.
+	pub orig_checksum: u32,
+}
+
+///A WOFF 1.0 font, it is held as the sfnt font it decodes to
+#[derive(Debug)]
+pub struct WoffFont{
+	///Major version of the WOFF file.
+	pub major_version: u16,
+	///Minor version of the WOFF file.
+	pub minor_version: u16,
+	///The decoded font, its tables are read from `data`
+	pub font: OTTF,
+	///The decoded font file
+	pub data: Vec<u8>,
+	///The extended metadata (an XML document) decompressed
+	pub metadata: Option<Vec<u8>>,
+	///Private data for use by the font designer, foundry or vendor
+	pub private_data: Option<Vec<u8>>,
+}
+impl WoffFont{
+	pub const SIGNATURE: u32 = 0x774F4646;
+	const HEADER_SIZE: usize = 44;
+	const TABLE_DIRECTORY_ENTRY_SIZE: usize = 20;
+	///The largest font a WOFF file is decoded to
+	pub const MAX_SFNT_SIZE: u32 = 1 << 30;
+
+	///A reader over the decoded font for loading tables of `font`
+	pub fn reader(&self)->Cursor<&[u8]>{Cursor::new(&self.data)}
+	///Wraps an sfnt font file to be encoded, the version is taken from the font revision in head
+	pub fn from_sfnt(data: Vec<u8>, metadata: Option<Vec<u8>>, private_data: Option<Vec<u8>>)->Result<Self, FromFileErr<(),()>>{
+		let font = unwrap_or_ret!(OTTF::from_file(&mut Cursor::new(&data)));
+		let revision = font.get_table_record("head")
+			.and_then(|r|data.get(r.offset as usize + 4..r.offset as usize + 8))
+			.map(|v|(u16::from_be_bytes([v[0], v[1]]), u16::from_be_bytes([v[2], v[3]])));
+		let (major_version, minor_version) = revision.unwrap_or((1, 0));
+		Ok(Self{major_version, minor_version, font, data, metadata, private_data})
+	}
+	///The WOFF file, each table is compressed unless that doesn't make it smaller
+	pub fn encode(&self)->Result<Vec<u8>, std::io::Error>{
+		let invalid_data = |message|std::io::Error::new(std::io::ErrorKind::InvalidData, message);
+		let records = &self.font.table_directory.table_records;
+		let mut offset = Self::HEADER_SIZE + Self::TABLE_DIRECTORY_ENTRY_SIZE * records.len();
+		let mut total_sfnt_size = 12 + 16 * records.len();
+		let mut entries = Vec::with_capacity(records.len());
+		let mut tables = Vec::with_capacity(records.len());
+		for record in records.iter(){
+			let table = match self.data.get(record.offset as usize..record.offset as usize + record.length as usize){
+				Some(t) => t,
+				None => return Err(invalid_data("table is past the end of the font")),
+			};
+			let compressed = zlib_compress(table);
+			let table = if compressed.len() < table.len(){compressed}else{table.to_vec()};
+			entries.push(WoffTableDirectoryEntry{
+				tag: Tag{data: record.table_tag.data.clone()},
+				offset: offset as u32,
+				comp_length: table.len() as u32,
+				orig_length: record.length,
+				orig_checksum: record.checksum,
+			});
+			offset += (table.len() + 3) & !3;
+			total_sfnt_size += (record.length as usize + 3) & !3;
+			tables.push(table);
+		}
+		entries.sort_by(|a, b|a.tag.data.cmp(&b.tag.data));
+		let metadata = self.metadata.as_ref().map(|m|(zlib_compress(m), m.len()));
+		let (meta_offset, meta_length, meta_orig_length) = match &metadata{
+			Some((compressed, length)) => {
+				let rv = (offset, compressed.len(), *length);
+				offset += compressed.len();
+				rv
+			},
+			None => (0, 0, 0),
+		};
+		let (priv_offset, priv_length) = match &self.private_data{
+			Some(data) => {
+				offset = (offset + 3) & !3;
+				let rv = (offset, data.len());
+				offset += data.len();
+				rv
+			},
+			None => (0, 0),
+		};
+		let header = WoffHeader{
+			signature: Self::SIGNATURE,
+			flavor: self.font.table_directory.sfnt_version.to_u32(),
+			length: offset as u32,
+			num_tables: records.len() as u16,
+			reserved: 0,
+			total_sfnt_size: total_sfnt_size as u32,
+			major_version: self.major_version,
+			minor_version: self.minor_version,
+			meta_offset: meta_offset as u32,
+			meta_length: meta_length as u32,
+			meta_orig_length: meta_orig_length as u32,
+			priv_offset: priv_offset as u32,
+			priv_length: priv_length as u32,
+		};
+
+		let mut f = Cursor::new(Vec::with_capacity(offset));
+		unwrap_or_ret!(header.to_file(&mut f));
+		unwrap_or_ret!(array_to_file(&mut f, &entries, records.len()));
+		for table in tables.iter(){
+			unwrap_or_ret!(f.write_all(table));
+			unwrap_or_ret!(f.write_all(&[0; 3][..(4 - table.len() % 4) % 4]));
+		}
+		if let Some((compressed, _)) = &metadata{unwrap_or_ret!(f.write_all(compressed));}
+		if let Some(data) = &self.private_data{
+			unwrap_or_ret!(f.seek(SeekFrom::Start(priv_offset as u64)));
+			unwrap_or_ret!(f.write_all(data));
+		}
+		Ok(f.into_inner())
+	}
+}
+///Reads `length` bytes at `offset`, the block has to be within the first `file_length` bytes and in the file itself
+fn block_from_file<F>(f: &mut F, offset: u32, length: u32, file_length: u32)->Result<Box<[u8]>, FromFileErr<(),()>> where F: Read, F: Seek{
+	if offset as u64 + length as u64 > file_length as u64{return Err(FromFileErr::InvalidData(()));}
+	if f.seek(SeekFrom::Start(offset as u64)).is_err(){return Err(FromFileErr::EOF);}
+	bounded_array_from_file(f, length, 1, u64::MAX)
+}
+impl_from_file!(WoffFont, (), (), f, {
+	let header = unwrap_or_ret!(WoffHeader::from_file(f));
+	if header.signature != Self::SIGNATURE{return Err(FromFileErr::InvalidData(()));}
+	let entries: Box<[WoffTableDirectoryEntry]> = unwrap_or_ret!(array_from_file(f, header.num_tables as usize));
+	if header.total_sfnt_size > Self::MAX_SFNT_SIZE{return Err(FromFileErr::InvalidData(()));}
+	let mut builder = FontBuilder::new(SFNTVer::from_u32(header.flavor));
+	// the tables are decompressed into a font of total_sfnt_size bytes, so their padded lengths have to fit in it
+	let mut sfnt_size = 12 + 16 * header.num_tables as u64;
+	for entry in entries.iter(){
+		sfnt_size += (entry.orig_length as u64 + 3) & !3;
+		if sfnt_size > header.total_sfnt_size as u64 || builder.table(&entry.tag.data).is_some(){return Err(FromFileErr::InvalidData(()));}
+		let data = unwrap_or_ret!(block_from_file(f, entry.offset, entry.comp_length, header.length));
+		let table = match entry.comp_length.cmp(&entry.orig_length){
+			std::cmp::Ordering::Less => unwrap_or_ret!(zlib_decompress(&data, entry.orig_length as usize)),
+			std::cmp::Ordering::Equal => data.into(),
+			std::cmp::Ordering::Greater => return Err(FromFileErr::InvalidData(())),
+		};
+		if table.len() != entry.orig_length as usize || FontBuilder::table_checksum(&entry.tag.data, &table) != entry.orig_checksum{
+			return Err(FromFileErr::InvalidData(()));
+		}
+		builder.add_raw_table(&entry.tag.data, table);
+	}
+	let metadata = match header.meta_offset{
+		0 => None,
+		offset => {
+			let compressed = unwrap_or_ret!(block_from_file(f, offset, header.meta_length, header.length));
+			let metadata = unwrap_or_ret!(zlib_decompress(&compressed, header.meta_orig_length as usize));
+			if metadata.len() != header.meta_orig_length as usize{return Err(FromFileErr::InvalidData(()));}
+			Some(metadata)
+		},
+	};
+	let private_data = match header.priv_offset{
+		0 => None,
+		offset => Some(unwrap_or_ret!(block_from_file(f, offset, header.priv_length, header.length)).into()),
+	};
+	let data = match builder.build(){
+		Ok(data) => data,
+		Err(_) => return Err(FromFileErr::InvalidData(())),
+	};
+	let font = unwrap_or_ret!(OTTF::from_file(&mut Cursor::new(&data)));
+	Ok(Self{
+		major_version: header.major_version,
+		minor_version: header.minor_version,
+		font,
+		data,
+		metadata,
+		private_data,
+	})
+});
@@ -0,0 +1,580 @@
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+
+use macros::FromFile;
+
+use crate::brotli;
+use crate::builder::FontBuilder;
+use crate::core::{array_from_file, bounded_array_from_file, calc_checksum, FontCollection, FromFile, FromFileErr, GlyphComponent, HheaTable, MaxpTable, OTTF, SFNTVer, SimpleGlyph, TableDirectory, TableRecord, Tag, ToFile};
+
+///A 32-bit unsigned integer stored in 1 to 5 bytes of 7 bits each, most significant first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UIntBase128(pub u32);
+impl_from_file!(UIntBase128, (), (), f, {
+	let mut value = 0u32;
+	for i in 0..5{
+		let byte = unwrap_or_ret!(u8::from_file(f));
+		// leading zeros and values that don't fit in 32 bits are invalid
+		if (i == 0 && byte == 0x80) || value & 0xFE000000 != 0{return Err(FromFileErr::InvalidData(()));}
+		value = value << 7 | (byte & 0x7F) as u32;
+		if byte & 0x80 == 0{return Ok(Self(value));}
+	}
+	Err(FromFileErr::InvalidData(()))
+});
+
+///A 16-bit unsigned integer stored in 1 to 3 bytes (the 255UInt16 data type)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Packed255UInt16(pub u16);
+impl Packed255UInt16{
+	///The value is the following uint16
+	const WORD_CODE: u8 = 253;
+	///The value is the following byte plus 506
+	const ONE_MORE_BYTE_CODE2: u8 = 254;
+	///The value is the following byte plus 253
+	const ONE_MORE_BYTE_CODE1: u8 = 255;
+	const LOWEST_U_CODE: u16 = 253;
+}
+impl_from_file!(Packed255UInt16, (), (), f, {Ok(Self(match unwrap_or_ret!(u8::from_file(f)){
+	Self::WORD_CODE => unwrap_or_ret!(u16::from_file(f)),
+	Self::ONE_MORE_BYTE_CODE1 => unwrap_or_ret!(u8::from_file(f)) as u16 + Self::LOWEST_U_CODE,
+	Self::ONE_MORE_BYTE_CODE2 => unwrap_or_ret!(u8::from_file(f)) as u16 + Self::LOWEST_U_CODE * 2,
+	v => v as u16,
+}))});
+
+#[derive(Debug,FromFile)]
+pub struct Woff2Header{
+	///0x774F4632 'wOF2'
+	pub signature: u32,
+	///The "sfnt version" of the input font.
+	pub flavor: u32,
+	///Total size of the WOFF file.
+	pub length: u32,
+	///Number of entries in directory of font tables.
+	pub num_tables: u16,
+	///Reserved; set to 0.
+	pub reserved: u16,
+	///Total size needed for the uncompressed font data, including the sfnt header, directory, and font tables (including padding).
+	pub total_sfnt_size: u32,
+	///Total length of the compressed data block.
+	pub total_compressed_size: u32,
+	///Major version of the WOFF file.
+	pub major_version: u16,
+	///Minor version of the WOFF file.
+	pub minor_version: u16,
+	///Offset to metadata block, from beginning of WOFF file.
+	pub meta_offset: u32,
+	///Length of compressed metadata block.
+	pub meta_length: u32,
+	///Uncompressed size of metadata block.
+	pub meta_orig_length: u32,
+	///Offset to private data block, from beginning of WOFF file.
+	pub priv_offset: u32,
+	///Length of private data block.
+	pub priv_length: u32,
+}
+
+#[derive(Debug)]
+pub struct Woff2TableDirectoryEntry{
+	///Bits 0-5 are the index of a known tag (63 if the tag is given), bits 6-7 are the transform version
+	pub flags: u8,
+	///The table tag
+	pub tag: Tag,
+	///Length of the original table
+	pub orig_length: u32,
+	///Length of the table as stored if it has been transformed
+	pub transform_length: Option<u32>,
+}
+impl Woff2TableDirectoryEntry{
+	///The tags that can be given by their index in the flags
+	pub const KNOWN_TAGS: [&'static str; 63] = [
+		"cmap", "head", "hhea", "hmtx", "maxp", "name", "OS/2", "post", "cvt ", "fpgm", "glyf", "loca", "prep", "CFF ", "VORG", "EBDT",
+		"EBLC", "gasp", "hdmx", "kern", "LTSH", "PCLT", "VDMX", "vhea", "vmtx", "BASE", "GDEF", "GPOS", "GSUB", "EBSC", "JSTF", "MATH",
+		"CBDT", "CBLC", "COLR", "CPAL", "SVG ", "sbix", "acnt", "avar", "bdat", "bloc", "bsln", "cvar", "fdsc", "feat", "fmtx", "fvar",
+		"gvar", "hsty", "just", "lcar", "mort", "morx", "opbd", "prop", "trak", "Zapf", "Silf", "Glat", "Gloc", "Feat", "Sill",
+	];
+	const ARBITRARY_TAG: u8 = 63;
+
+	pub fn transform_version(&self)->u8{self.flags >> 6}
+	///Length of the table in the decompressed data
+	fn stored_length(&self)->u32{self.transform_length.unwrap_or(self.orig_length)}
+}
+impl_from_file!(Woff2TableDirectoryEntry, (), (), f, {
+	let flags = unwrap_or_ret!(u8::from_file(f));
+	let tag = match flags & 0x3F{
+		Self::ARBITRARY_TAG => unwrap_or_ret!(Tag::from_file(f)),
+		i => Tag{data: Self::KNOWN_TAGS[i as usize].to_string()},
+	};
+	let orig_length = unwrap_or_ret!(UIntBase128::from_file(f)).0;
+	// the null transform of glyf and loca is version 3 while for other tables it is version 0
+	let transformed = match tag.data.as_str(){
+		"glyf" | "loca" => flags >> 6 == 0,
+		_ => flags >> 6 != 0,
+	};
+	let transform_length = if transformed{Some(unwrap_or_ret!(UIntBase128::from_file(f)).0)}else{None};
+	Ok(Self{flags, tag, orig_length, transform_length})
+});
+
+///A font in a WOFF2 collection
+#[derive(Debug)]
+pub struct Woff2CollectionFontEntry{
+	///The "sfnt version" of the font
+	pub flavor: u32,
+	///Index of each table of the font in the table directory
+	pub table_indices: Box<[u16]>,
+}
+impl_from_file!(Woff2CollectionFontEntry, (), (), f, {
+	let num_tables = unwrap_or_ret!(Packed255UInt16::from_file(f)).0;
+	let flavor = unwrap_or_ret!(u32::from_file(f));
+	let indices: Box<[Packed255UInt16]> = unwrap_or_ret!(array_from_file(f, num_tables as usize));
+	Ok(Self{flavor, table_indices: indices.iter().map(|i|i.0).collect()})
+});
+
+///The header of a transformed `glyf` table, the streams follow in the order of their sizes
+#[derive(Debug,FromFile)]
+pub struct TransformedGlyfHeader{
+	///Reserved; set to 0.
+	pub reserved: u16,
+	///Bit 0: an overlap simple bitmap follows the streams.
+	pub option_flags: u16,
+	///Number of glyphs.
+	pub num_glyphs: u16,
+	///Offset format for loca table, should be consistent with indexToLocFormat of the original head table.
+	pub index_format: u16,
+	///Size of nContour stream in bytes.
+	pub n_contour_stream_size: u32,
+	///Size of nPoints stream in bytes.
+	pub n_points_stream_size: u32,
+	///Size of flag stream in bytes.
+	pub flag_stream_size: u32,
+	///Size of glyph stream in bytes.
+	pub glyph_stream_size: u32,
+	///Size of composite stream in bytes.
+	pub composite_stream_size: u32,
+	///Size of bbox data in bytes representing combined length of bboxBitmap and bboxStream.
+	pub bbox_stream_size: u32,
+	///Size of instruction stream.
+	pub instruction_stream_size: u32,
+}
+impl TransformedGlyfHeader{
+	///Bit 0: the overlap simple bitmap follows the streams
+	const HAS_OVERLAP_SIMPLE_BITMAP: u16 = 0x0001;
+	const SIZE: usize = 36;
+}
+
+///The fonts a WOFF2 file decodes to
+#[derive(Debug)]
+pub enum Woff2Content{
+	Font(OTTF),
+	Collection(FontCollection),
+}
+
+///A WOFF2 font or collection, it is held as the sfnt font or TrueType Collection it decodes to
+#[derive(Debug)]
+pub struct Woff2Font{
+	///Major version of the WOFF file.
+	pub major_version: u16,
+	///Minor version of the WOFF file.
+	pub minor_version: u16,
+	///The decoded font or collection, its tables are read from `data`
+	pub content: Woff2Content,
+	///The decoded font or collection file
+	pub data: Vec<u8>,
+	///The extended metadata (an XML document) decompressed
+	pub metadata: Option<Vec<u8>>,
+	///Private data for use by the font designer, foundry or vendor
+	pub private_data: Option<Vec<u8>>,
+}
+impl Woff2Font{
+	pub const SIGNATURE: u32 = 0x774F4632;
+	///The largest font a WOFF2 file is decoded to
+	pub const MAX_SFNT_SIZE: u32 = 1 << 30;
+	///The largest extended metadata block that is decompressed
+	pub const MAX_METADATA_SIZE: u32 = 1 << 24;
+
+	///A reader over the decoded data for loading tables of the fonts
+	pub fn reader(&self)->Cursor<&[u8]>{Cursor::new(&self.data)}
+	///Every font in the file, there is one unless it is a collection
+	pub fn fonts(&self)->&[OTTF]{match &self.content{
+		Woff2Content::Font(font) => std::slice::from_ref(font),
+		Woff2Content::Collection(collection) => collection.fonts(),
+	}}
+}
+
+///Reads `length` bytes at `offset`, the block has to be within the first `file_length` bytes and in the file itself
+fn block_from_file<F>(f: &mut F, offset: u64, length: u32, file_length: u32)->Result<Box<[u8]>, FromFileErr<(),()>> where F: Read, F: Seek{
+	if offset + length as u64 > file_length as u64{return Err(FromFileErr::InvalidData(()));}
+	if f.seek(SeekFrom::Start(offset)).is_err(){return Err(FromFileErr::EOF);}
+	bounded_array_from_file(f, length, 1, u64::MAX)
+}
+
+///Whether bit `index` of a bitmap stored from the most significant bit of each byte is set
+fn bitmap_bit(bitmap: &[u8], index: usize)->bool{bitmap[index >> 3] & (0x80 >> (index & 7)) != 0}
+
+///The change in x and y of a point from its flag (without the on curve bit) and its data in the glyph stream
+fn decode_triplet(flag: u8, data: &[u8])->(i32, i32){
+	let with_sign = |flag: u8, value: i32|if flag & 1 != 0{value}else{-value};
+	let flag32 = flag as i32;
+	let byte = |i: usize|data[i] as i32;
+	match flag{
+		0..=9 => (0, with_sign(flag, ((flag32 & 14) << 7) + byte(0))),
+		10..=19 => (with_sign(flag, (((flag32 - 10) & 14) << 7) + byte(0)), 0),
+		20..=83 => {
+			let b0 = flag32 - 20;
+			(with_sign(flag, 1 + (b0 & 0x30) + (byte(0) >> 4)), with_sign(flag >> 1, 1 + ((b0 & 0x0C) << 2) + (byte(0) & 0x0F)))
+		},
+		84..=119 => {
+			let b0 = flag32 - 84;
+			(with_sign(flag, 1 + ((b0 / 12) << 8) + byte(0)), with_sign(flag >> 1, 1 + (((b0 % 12) >> 2) << 8) + byte(1)))
+		},
+		120..=123 => (with_sign(flag, (byte(0) << 4) + (byte(1) >> 4)), with_sign(flag >> 1, ((byte(1) & 0x0F) << 8) + byte(2))),
+		_ => (with_sign(flag, (byte(0) << 8) + byte(1)), with_sign(flag >> 1, (byte(2) << 8) + byte(3))),
+	}
+}
+
+///Writes the flags and coordinates of a simple glyph using short vectors and repeated flags where possible
+fn write_points(out: &mut Vec<u8>, points: &[(i32, i32, bool)], overlap: bool)->Result<(), FromFileErr<(),()>>{
+	let mut flags = Vec::with_capacity(points.len());
+	let (mut xs, mut ys) = (Vec::new(), Vec::new());
+	let (mut last_x, mut last_y) = (0, 0);
+	let (mut last_flag, mut repeat) = (None, 0u8);
+	for (i, (x, y, on_curve)) in points.iter().enumerate(){
+		let mut flag = if *on_curve{SimpleGlyph::ON_CURVE_POINT}else{0};
+		if i == 0 && overlap{flag |= SimpleGlyph::OVERLAP_SIMPLE;}
+		for (delta, coordinates, short, same_or_positive) in [
+			(x - last_x, &mut xs, SimpleGlyph::X_SHORT_VECTOR, SimpleGlyph::X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR),
+			(y - last_y, &mut ys, SimpleGlyph::Y_SHORT_VECTOR, SimpleGlyph::Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR),
+		]{
+			if delta == 0{flag |= same_or_positive;}
+			else if delta.abs() < 256{
+				flag |= short | if delta > 0{same_or_positive}else{0};
+				coordinates.push(delta.unsigned_abs() as u8);
+			}else{
+				let Ok(delta) = i16::try_from(delta) else{return Err(FromFileErr::InvalidData(()))};
+				coordinates.extend(delta.to_be_bytes());
+			}
+		}
+		(last_x, last_y) = (*x, *y);
+		if last_flag == Some(flag) && repeat != 255{
+			*flags.last_mut().unwrap() |= SimpleGlyph::REPEAT_FLAG;
+			repeat += 1;
+		}else{
+			if repeat != 0{flags.push(repeat);}
+			flags.push(flag);
+			repeat = 0;
+		}
+		last_flag = Some(flag);
+	}
+	if repeat != 0{flags.push(repeat);}
+	out.extend(flags);
+	out.extend(xs);
+	out.extend(ys);
+	Ok(())
+}
+
+///The tables rebuilt from a transformed `glyf` table
+struct ReconstructedGlyf{
+	glyf: Vec<u8>,
+	loca: Vec<u8>,
+	///The xMin of each glyph for rebuilding `hmtx`
+	x_mins: Vec<i16>,
+}
+
+///Rebuilds the `glyf` and `loca` tables from a transformed `glyf` table
+fn reconstruct_glyf(data: &[u8])->Result<ReconstructedGlyf, FromFileErr<(),()>>{
+	let header = unwrap_or_ret!(TransformedGlyfHeader::from_file(&mut Cursor::new(data)));
+	let num_glyphs = header.num_glyphs as usize;
+	let mut offset = TransformedGlyfHeader::SIZE;
+	let mut stream = |size: usize|match data.get(offset..offset + size){
+		Some(s) => {
+			offset += size;
+			Ok(Cursor::new(s))
+		},
+		None => Err(FromFileErr::EOF),
+	};
+	let mut n_contours = unwrap_or_ret!(stream(header.n_contour_stream_size as usize));
+	let mut n_points = unwrap_or_ret!(stream(header.n_points_stream_size as usize));
+	let mut flag_stream = unwrap_or_ret!(stream(header.flag_stream_size as usize));
+	let mut glyph_stream = unwrap_or_ret!(stream(header.glyph_stream_size as usize));
+	let mut composite_stream = unwrap_or_ret!(stream(header.composite_stream_size as usize));
+	let bbox_stream = unwrap_or_ret!(stream(header.bbox_stream_size as usize)).into_inner();
+	let mut instruction_stream = unwrap_or_ret!(stream(header.instruction_stream_size as usize));
+	let overlap_bitmap = if header.option_flags & TransformedGlyfHeader::HAS_OVERLAP_SIMPLE_BITMAP != 0{
+		Some(unwrap_or_ret!(stream(num_glyphs.div_ceil(8))).into_inner())
+	}else{None};
+	// the bounding box stream starts with a bitmap of which glyphs have an explicit bounding box
+	let bbox_bitmap_length = 4 * num_glyphs.div_ceil(32);
+	if bbox_stream.len() < bbox_bitmap_length{return Err(FromFileErr::EOF);}
+	let (bbox_bitmap, bboxes) = bbox_stream.split_at(bbox_bitmap_length);
+	let mut bboxes = Cursor::new(bboxes);
+
+	let mut glyf = Vec::new();
+	let mut offsets = Vec::with_capacity(num_glyphs + 1);
+	let mut x_mins = Vec::with_capacity(num_glyphs);
+	for glyph_id in 0..num_glyphs{
+		offsets.push(glyf.len());
+		let number_of_contours = unwrap_or_ret!(i16::from_file(&mut n_contours));
+		let has_bbox = bitmap_bit(bbox_bitmap, glyph_id);
+		let explicit_bbox = if has_bbox{Some(unwrap_or_ret!(array_from_file::<_, i16, (), ()>(&mut bboxes, 4)))}else{None};
+		if number_of_contours == 0{
+			if has_bbox{return Err(FromFileErr::InvalidData(()));}
+			x_mins.push(0);
+			continue;
+		}
+		let mut body = Vec::new();
+		let bbox = if number_of_contours > 0{
+			let mut end_points = Vec::with_capacity(number_of_contours as usize);
+			let mut point_count = 0u32;
+			for _ in 0..number_of_contours{
+				point_count += unwrap_or_ret!(Packed255UInt16::from_file(&mut n_points)).0 as u32;
+				if point_count == 0 || point_count > 0x10000{return Err(FromFileErr::InvalidData(()));}
+				end_points.push((point_count - 1) as u16);
+			}
+			let flags: Box<[u8]> = unwrap_or_ret!(array_from_file(&mut flag_stream, point_count as usize));
+			let mut points = Vec::with_capacity(point_count as usize);
+			let (mut x, mut y) = (0i32, 0i32);
+			for flag in flags.iter(){
+				let triplet = flag & 0x7F;
+				let length = match triplet{0..=83 => 1, 84..=119 => 2, 120..=123 => 3, _ => 4};
+				let bytes: Box<[u8]> = unwrap_or_ret!(array_from_file(&mut glyph_stream, length));
+				let (dx, dy) = decode_triplet(triplet, &bytes);
+				let (Some(next_x), Some(next_y)) = (x.checked_add(dx), y.checked_add(dy)) else{return Err(FromFileErr::InvalidData(()))};
+				(x, y) = (next_x, next_y);
+				points.push((x, y, flag & 0x80 == 0));
+			}
+			let instruction_length = unwrap_or_ret!(Packed255UInt16::from_file(&mut glyph_stream)).0;
+			let instructions: Box<[u8]> = unwrap_or_ret!(array_from_file(&mut instruction_stream, instruction_length as usize));
+			for end_point in end_points{body.extend(end_point.to_be_bytes());}
+			body.extend(instruction_length.to_be_bytes());
+			body.extend(instructions.iter());
+			let overlap = overlap_bitmap.is_some_and(|b|bitmap_bit(b, glyph_id));
+			unwrap_or_ret!(write_points(&mut body, &points, overlap));
+			match explicit_bbox{
+				Some(bbox) => bbox,
+				None => {
+					// the bounding box of the points
+					let min_max = |values: &mut dyn Iterator<Item = i32>|values.fold((i32::MAX, i32::MIN), |(min, max), v|(min.min(v), max.max(v)));
+					let (x_min, x_max) = min_max(&mut points.iter().map(|p|p.0));
+					let (y_min, y_max) = min_max(&mut points.iter().map(|p|p.1));
+					let mut bbox = [0i16; 4];
+					for (v, b) in [x_min, y_min, x_max, y_max].iter().zip(bbox.iter_mut()){
+						let Ok(v) = i16::try_from(*v) else{return Err(FromFileErr::InvalidData(()))};
+						*b = v;
+					}
+					bbox.into()
+				},
+			}
+		}else{
+			// composite glyphs are stored as they are in glyf apart from the instructions
+			let Some(bbox) = explicit_bbox else{return Err(FromFileErr::InvalidData(()))};
+			let mut have_instructions = false;
+			loop{
+				let flags = unwrap_or_ret!(u16::from_file(&mut composite_stream));
+				let mut length = 2 + if flags & GlyphComponent::ARG_1_AND_2_ARE_WORDS != 0{4}else{2};
+				if flags & GlyphComponent::WE_HAVE_A_SCALE != 0{length += 2;}
+				else if flags & GlyphComponent::WE_HAVE_AN_X_AND_Y_SCALE != 0{length += 4;}
+				else if flags & GlyphComponent::WE_HAVE_A_TWO_BY_TWO != 0{length += 8;}
+				let rest: Box<[u8]> = unwrap_or_ret!(array_from_file(&mut composite_stream, length));
+				body.extend(flags.to_be_bytes());
+				body.extend(rest.iter());
+				have_instructions |= flags & GlyphComponent::WE_HAVE_INSTRUCTIONS != 0;
+				if flags & GlyphComponent::MORE_COMPONENTS == 0{break;}
+			}
+			if have_instructions{
+				let instruction_length = unwrap_or_ret!(Packed255UInt16::from_file(&mut glyph_stream)).0;
+				let instructions: Box<[u8]> = unwrap_or_ret!(array_from_file(&mut instruction_stream, instruction_length as usize));
+				body.extend(instruction_length.to_be_bytes());
+				body.extend(instructions.iter());
+			}
+			bbox
+		};
+		glyf.extend(number_of_contours.to_be_bytes());
+		for v in bbox.iter(){glyf.extend(v.to_be_bytes());}
+		glyf.extend(body);
+		glyf.resize(glyf.len().next_multiple_of(4), 0);
+		x_mins.push(bbox[0]);
+	}
+	offsets.push(glyf.len());
+
+	let mut loca = Vec::with_capacity(offsets.len() * 4);
+	for offset in offsets{
+		if header.index_format == 0{
+			let Ok(offset) = u16::try_from(offset / 2) else{return Err(FromFileErr::InvalidData(()))};
+			loca.extend(offset.to_be_bytes());
+		}else{loca.extend((offset as u32).to_be_bytes());}
+	}
+	Ok(ReconstructedGlyf{glyf, loca, x_mins})
+}
+
+///Rebuilds the `hmtx` table from a transformed one, the left side bearings left out are the xMin of the glyphs
+fn reconstruct_hmtx(data: &[u8], num_glyphs: u16, number_of_h_metrics: u16, x_mins: &[i16])->Result<Vec<u8>, FromFileErr<(),()>>{
+	///Bit 0: the left side bearings of the glyphs with an advance width are left out
+	const PROPORTIONAL_LSB_OMITTED: u8 = 0x01;
+	///Bit 1: the left side bearings of the glyphs without an advance width are left out
+	const MONOSPACE_LSB_OMITTED: u8 = 0x02;
+	let (num_glyphs, number_of_h_metrics) = (num_glyphs as usize, number_of_h_metrics as usize);
+	if number_of_h_metrics == 0 || number_of_h_metrics > num_glyphs || x_mins.len() < num_glyphs{return Err(FromFileErr::InvalidData(()));}
+	let mut f = Cursor::new(data);
+	let flags = unwrap_or_ret!(u8::from_file(&mut f));
+	if flags & !(PROPORTIONAL_LSB_OMITTED | MONOSPACE_LSB_OMITTED) != 0 || flags == 0{return Err(FromFileErr::InvalidData(()));}
+	let advances: Box<[u16]> = unwrap_or_ret!(array_from_file(&mut f, number_of_h_metrics));
+	let mut lsbs = Vec::with_capacity(num_glyphs);
+	for (glyph_id, x_min) in x_mins.iter().enumerate().take(num_glyphs){
+		let omitted = if glyph_id < number_of_h_metrics{PROPORTIONAL_LSB_OMITTED}else{MONOSPACE_LSB_OMITTED};
+		lsbs.push(if flags & omitted != 0{*x_min}else{unwrap_or_ret!(i16::from_file(&mut f))});
+	}
+	let mut hmtx = Vec::with_capacity(number_of_h_metrics * 2 + num_glyphs * 2);
+	for (glyph_id, lsb) in lsbs.iter().enumerate(){
+		if let Some(advance) = advances.get(glyph_id){hmtx.extend(advance.to_be_bytes());}
+		hmtx.extend(lsb.to_be_bytes());
+	}
+	Ok(hmtx)
+}
+
+///Lays out a TrueType Collection of fonts sharing `tables`, each font is its flavor and the index of each of its tables
+fn collection_file(major_version: u16, fonts: &[Woff2CollectionFontEntry], tables: &[(String, Vec<u8>)])->Result<Vec<u8>, std::io::Error>{
+	let header_length = 12 + 4 * fonts.len() + if major_version >= 2{12}else{0};
+	let mut offset = header_length + fonts.iter().map(|f|12 + 16 * f.table_indices.len()).sum::<usize>();
+	let mut table_offsets = Vec::with_capacity(tables.len());
+	for (_, data) in tables.iter(){
+		table_offsets.push(offset as u32);
+		offset += data.len().next_multiple_of(4);
+	}
+	let mut f = Cursor::new(Vec::with_capacity(offset));
+	unwrap_or_ret!(FontCollection::TAG.to_file(&mut f));
+	unwrap_or_ret!(major_version.to_file(&mut f));
+	unwrap_or_ret!(0u16.to_file(&mut f));
+	unwrap_or_ret!((fonts.len() as u32).to_file(&mut f));
+	let mut directory_offset = header_length;
+	for font in fonts.iter(){
+		unwrap_or_ret!((directory_offset as u32).to_file(&mut f));
+		directory_offset += 12 + 16 * font.table_indices.len();
+	}
+	// no signature
+	if major_version >= 2{unwrap_or_ret!(f.write_all(&[0; 12]));}
+	for font in fonts.iter(){
+		let mut indices: Vec<usize> = font.table_indices.iter().map(|i|*i as usize).collect();
+		indices.sort_by(|a, b|tables[*a].0.cmp(&tables[*b].0));
+		let num_tables = indices.len() as u16;
+		let (search_range, entry_selector, range_shift) = TableDirectory::search_params(num_tables);
+		let directory = TableDirectory{
+			sfnt_version: SFNTVer::from_u32(font.flavor),
+			num_tables,
+			search_range,
+			entry_selector,
+			range_shift,
+			table_records: indices.iter().map(|i|{
+				let (tag, data) = &tables[*i];
+				let mut checksum = calc_checksum(data);
+				// the checksum of head is taken with checkSumAdjustment as 0
+				if tag == "head" && data.len() >= 12{checksum = checksum.wrapping_sub(u32::from_be_bytes([data[8], data[9], data[10], data[11]]));}
+				TableRecord{table_tag: Tag{data: tag.clone()}, checksum, offset: table_offsets[*i], length: data.len() as u32}
+			}).collect(),
+		};
+		unwrap_or_ret!(directory.to_file(&mut f));
+	}
+	for (_, data) in tables.iter(){
+		unwrap_or_ret!(f.write_all(data));
+		unwrap_or_ret!(f.write_all(&[0; 3][..(4 - data.len() % 4) % 4]));
+	}
+	Ok(f.into_inner())
+}
+
+impl_from_file!(Woff2Font, (), (), f, {
+	let header = unwrap_or_ret!(Woff2Header::from_file(f));
+	if header.signature != Self::SIGNATURE{return Err(FromFileErr::InvalidData(()));}
+	let entries: Box<[Woff2TableDirectoryEntry]> = unwrap_or_ret!(array_from_file(f, header.num_tables as usize));
+	let collection = header.flavor == FontCollection::TAG;
+	let (collection_version, fonts) = if collection{
+		let version = unwrap_or_ret!(u32::from_file(f));
+		let num_fonts = unwrap_or_ret!(Packed255UInt16::from_file(f)).0;
+		let fonts: Box<[Woff2CollectionFontEntry]> = unwrap_or_ret!(array_from_file(f, num_fonts as usize));
+		(version, fonts)
+	}else{
+		(0, vec![Woff2CollectionFontEntry{flavor: header.flavor, table_indices: (0..header.num_tables).collect()}].into())
+	};
+	if fonts.iter().flat_map(|f|f.table_indices.iter()).any(|i|*i as usize >= entries.len()){return Err(FromFileErr::InvalidData(()));}
+
+	// every table is stored one after the other in a single Brotli stream
+	let stream_start = unwrap_or_ret!(f.stream_position().map_err(|_|FromFileErr::EOF));
+	let compressed = unwrap_or_ret!(block_from_file(f, stream_start, header.total_compressed_size, header.length));
+	// the stored tables make up the decoded font so they fit in total_sfnt_size
+	let stream_length: u64 = entries.iter().map(|e|e.stored_length() as u64).sum();
+	if header.total_sfnt_size > Self::MAX_SFNT_SIZE || stream_length > header.total_sfnt_size as u64{return Err(FromFileErr::InvalidData(()));}
+	let stream_length = stream_length as usize;
+	let stream = unwrap_or_ret!(brotli::decompress(&compressed, stream_length));
+	if stream.len() != stream_length{return Err(FromFileErr::InvalidData(()));}
+	let mut offset = 0;
+	let mut stored = Vec::with_capacity(entries.len());
+	for entry in entries.iter(){
+		stored.push(&stream[offset..offset + entry.stored_length() as usize]);
+		offset += entry.stored_length() as usize;
+	}
+
+	let mut tables: Vec<Option<Vec<u8>>> = entries.iter().zip(stored.iter())
+		.map(|(e, data)|if e.transform_length.is_none(){Some(data.to_vec())}else{None}).collect();
+	let mut x_mins = vec![None; entries.len()];
+	for font in fonts.iter(){
+		let index = |tag: &str|font.table_indices.iter().map(|i|*i as usize).find(|i|entries[*i].tag.data == tag);
+		if let (Some(glyf), Some(loca)) = (index("glyf"), index("loca")){
+			if tables[glyf].is_none(){
+				if tables[loca].is_some(){return Err(FromFileErr::InvalidData(()));}
+				let rebuilt = unwrap_or_ret!(reconstruct_glyf(stored[glyf]));
+				// the rebuilt tables have to be the size of the originals
+				if rebuilt.glyf.len() != entries[glyf].orig_length as usize || rebuilt.loca.len() != entries[loca].orig_length as usize
+				{return Err(FromFileErr::InvalidData(()));}
+				tables[glyf] = Some(rebuilt.glyf);
+				tables[loca] = Some(rebuilt.loca);
+				x_mins[glyf] = Some(rebuilt.x_mins);
+			}
+		}
+		let Some(hmtx) = index("hmtx") else{continue};
+		if tables[hmtx].is_some(){continue;}
+		let table = |i: Option<usize>|i.and_then(|i|tables[i].as_deref());
+		let (Some(hhea), Some(maxp), Some(glyph_x_mins)) = (table(index("hhea")), table(index("maxp")), index("glyf").and_then(|i|x_mins[i].as_ref()))
+		else{return Err(FromFileErr::InvalidData(()))};
+		let hhea = unwrap_or_ret!(HheaTable::from_file(&mut Cursor::new(hhea)));
+		let maxp = unwrap_or_ret!(MaxpTable::from_file(&mut Cursor::new(maxp)));
+		tables[hmtx] = Some(unwrap_or_ret!(reconstruct_hmtx(stored[hmtx], maxp.num_glyphs, hhea.number_of_h_metrics, glyph_x_mins)));
+	}
+	// a transformed table that no font could rebuild
+	let Some(tables) = tables.into_iter().collect::<Option<Vec<Vec<u8>>>>() else{return Err(FromFileErr::InvalidData(()))};
+
+	let metadata = match header.meta_offset{
+		0 => None,
+		offset => {
+			if header.meta_orig_length > Self::MAX_METADATA_SIZE{return Err(FromFileErr::InvalidData(()));}
+			let compressed = unwrap_or_ret!(block_from_file(f, offset as u64, header.meta_length, header.length));
+			let metadata = unwrap_or_ret!(brotli::decompress(&compressed, header.meta_orig_length as usize));
+			if metadata.len() != header.meta_orig_length as usize{return Err(FromFileErr::InvalidData(()));}
+			Some(metadata)
+		},
+	};
+	let private_data = match header.priv_offset{
+		0 => None,
+		offset => Some(unwrap_or_ret!(block_from_file(f, offset as u64, header.priv_length, header.length)).into()),
+	};
+
+	let (content, data) = if collection{
+		let tables: Vec<(String, Vec<u8>)> = entries.iter().map(|e|e.tag.data.clone()).zip(tables).collect();
+		let data = match collection_file((collection_version >> 16) as u16, &fonts, &tables){
+			Ok(data) => data,
+			Err(_) => return Err(FromFileErr::InvalidData(())),
+		};
+		(Woff2Content::Collection(unwrap_or_ret!(FontCollection::from_file(&mut Cursor::new(&data)))), data)
+	}else{
+		let mut builder = FontBuilder::new(SFNTVer::from_u32(header.flavor));
+		for (entry, table) in entries.iter().zip(tables){builder.add_raw_table(&entry.tag.data, table);}
+		let data = match builder.build(){
+			Ok(data) => data,
+			Err(_) => return Err(FromFileErr::InvalidData(())),
+		};
+		(Woff2Content::Font(unwrap_or_ret!(OTTF::from_file(&mut Cursor::new(&data)))), data)
+	};
+	Ok(Self{
+		major_version: header.major_version,
+		minor_version: header.minor_version,
+		content,
+		data,
+		metadata,
+		private_data,
+	})
+});